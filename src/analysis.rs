@@ -0,0 +1,188 @@
+use crate::element::Element;
+use crate::trie::RadixTrie;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Prefix compression metrics of a trie, returned by [`RadixTrie::analysis`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Analysis {
+    /// Number of elements below the root
+    pub element_count: usize,
+    /// Depth of the deepest element, counted in label segments from the root
+    pub max_depth: usize,
+    /// Average depth of the elements below the root, or 0 for an empty trie
+    pub average_depth: f64,
+    /// Number of elements, the root included, by their number of children
+    pub branching: Vec<usize>,
+    /// Bytes of all labels if every label were stored in full
+    pub key_bytes: usize,
+    /// Bytes of the label segments actually stored
+    pub label_bytes: usize,
+    /// Up to `top_n` stored prefixes with the most labels under them, with their label counts,
+    /// from the heaviest down and in lexicographic order for equal counts
+    pub heaviest_prefixes: Vec<(String, usize)>,
+}
+
+impl Analysis {
+    /// Bytes saved by sharing prefixes compared to storing every label in full
+    pub fn bytes_saved(&self) -> usize {
+        self.key_bytes - self.label_bytes
+    }
+}
+
+impl<T> RadixTrie<T> {
+    /// Measure how well the labels share prefixes, walking the trie once.
+    /// Up to `top_n` of the heaviest stored prefixes are reported
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<()>::new();
+    /// trie.insert("/api/users", ());
+    /// trie.insert("/api/posts", ());
+    /// let analysis = trie.analysis(10);
+    /// assert_eq!(analysis.max_depth, 2);
+    /// assert_eq!(analysis.bytes_saved(), 5);
+    /// assert_eq!(analysis.heaviest_prefixes[0], (String::from("/api/"), 2));
+    /// ```
+    pub fn analysis(&self, top_n: usize) -> Analysis {
+        // elements in pre-order with the position of their parent and their depth
+        let mut elements: Vec<(Option<usize>, usize, &Element<T>)> = vec![];
+        let mut stack: Vec<(Option<usize>, usize, &Element<T>)> = self
+            .entry
+            .children()
+            .iter()
+            .rev()
+            .map(|child| (None, 1, child))
+            .collect();
+        let mut branching = vec![0; self.entry.children().len() + 1];
+        branching[self.entry.children().len()] += 1;
+        while let Some((parent, depth, element)) = stack.pop() {
+            let children = element.children();
+            if branching.len() <= children.len() {
+                branching.resize(children.len() + 1, 0);
+            }
+            branching[children.len()] += 1;
+            let position = elements.len();
+            elements.push((parent, depth, element));
+            stack.extend(
+                children
+                    .iter()
+                    .rev()
+                    .map(|child| (Some(position), depth + 1, child)),
+            );
+        }
+        let mut key_lens = Vec::with_capacity(elements.len());
+        let (mut key_bytes, mut label_bytes, mut depth_sum, mut max_depth) = (0, 0, 0, 0);
+        for &(parent, depth, element) in &elements {
+            let key_len = parent.map_or(0, |parent| key_lens[parent]) + element.label().len();
+            key_lens.push(key_len);
+            if element.value().is_some() {
+                key_bytes += key_len;
+            }
+            label_bytes += element.label().len();
+            depth_sum += depth;
+            max_depth = max_depth.max(depth);
+        }
+        // count the labels under every element, children before their parents
+        let mut counts = elements
+            .iter()
+            .map(|(_, _, element)| element.value().is_some() as usize)
+            .collect::<Vec<_>>();
+        for position in (0..elements.len()).rev() {
+            if let Some(parent) = elements[position].0 {
+                counts[parent] += counts[position];
+            }
+        }
+        let mut heaviest = (0..elements.len()).collect::<Vec<_>>();
+        // the sort is stable, so equal counts stay in pre-order, which is lexicographic
+        heaviest.sort_by(|first, second| counts[*second].cmp(&counts[*first]));
+        heaviest.truncate(top_n);
+        let heaviest_prefixes = heaviest
+            .into_iter()
+            .map(|position| (joined_label(&elements, position), counts[position]))
+            .collect();
+        let average_depth = if elements.is_empty() {
+            0.0
+        } else {
+            depth_sum as f64 / elements.len() as f64
+        };
+        Analysis {
+            element_count: elements.len(),
+            max_depth,
+            average_depth,
+            branching,
+            key_bytes,
+            label_bytes,
+            heaviest_prefixes,
+        }
+    }
+}
+
+/// Join the labels from the root down to the element at the position
+fn joined_label<T>(elements: &[(Option<usize>, usize, &Element<T>)], position: usize) -> String {
+    let mut segments = vec![];
+    let mut current = Some(position);
+    while let Some(position) = current {
+        let (parent, _, element) = elements[position];
+        segments.push(element.label());
+        current = parent;
+    }
+    segments.reverse();
+    segments.concat()
+}
+
+#[cfg(test)]
+mod analysis_tests {
+    use crate::trie::RadixTrie;
+
+    #[test]
+    fn test_analysis() {
+        let mut trie = RadixTrie::<()>::new();
+        let words = ["Won", "Wonder", "Wonderful", "World", "Axes", "多倫多"];
+        for word in &words {
+            trie.insert(word, ());
+        }
+        let analysis = trie.analysis(10);
+        // Wo -> (n -> der -> ful, rld), Axes, 多倫多
+        assert_eq!(analysis.element_count, trie.node_count());
+        assert_eq!(analysis.max_depth, 4);
+        assert_eq!(
+            analysis.average_depth,
+            (1 + 2 + 3 + 4 + 2 + 1 + 1) as f64 / 7.0
+        );
+        assert_eq!(analysis.branching, vec![4, 2, 1, 1]);
+        let key_bytes = words.iter().map(|word| word.len()).sum::<usize>();
+        assert_eq!(analysis.key_bytes, key_bytes);
+        assert_eq!(analysis.label_bytes, trie.memory_usage().label_bytes);
+        assert_eq!(analysis.bytes_saved(), key_bytes - analysis.label_bytes);
+        let heaviest = analysis
+            .heaviest_prefixes
+            .iter()
+            .map(|(label, count)| (label.as_str(), *count))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            heaviest,
+            vec![
+                ("Wo", 4),
+                ("Won", 3),
+                ("Wonder", 2),
+                ("Axes", 1),
+                ("Wonderful", 1),
+                ("World", 1),
+                ("多倫多", 1),
+            ]
+        );
+        let heaviest = trie.analysis(2).heaviest_prefixes;
+        assert_eq!(
+            heaviest,
+            vec![(String::from("Wo"), 4), (String::from("Won"), 3)]
+        );
+        assert!(trie.analysis(0).heaviest_prefixes.is_empty());
+        let empty = RadixTrie::<()>::new().analysis(10);
+        assert_eq!((empty.element_count, empty.max_depth), (0, 0));
+        assert_eq!(empty.average_depth, 0.0);
+        assert_eq!(empty.branching, vec![1]);
+        assert!(empty.heaviest_prefixes.is_empty());
+    }
+}
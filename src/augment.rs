@@ -0,0 +1,226 @@
+use crate::element::Element;
+use crate::trie::RadixTrie;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// An aggregate of the values below every element, kept up to date by an [`AugmentedTrie`]
+pub trait Augment<T> {
+    /// The aggregate of a non-empty group of values
+    type Summary: Clone;
+    /// The aggregate of a single value
+    fn summarize(&self, value: &T) -> Self::Summary;
+    /// The aggregate of two groups, the group of the smaller labels first
+    fn combine(&self, first: &Self::Summary, second: &Self::Summary) -> Self::Summary;
+}
+
+/// A trie caching an aggregate of the values below every element.
+///
+/// The aggregates on the path of a label are recomputed from the children when the label
+/// is inserted or removed, so the aggregate of all labels starting with a prefix is read
+/// from a single element by [`AugmentedTrie::summary`].
+/// Splits and merges of elements on writes are covered, since they only happen on that path.
+/// [`RankedTrie`](crate::RankedTrie) keeps its highest scores the same way
+///
+/// # Example
+/// ```rust
+/// use another_radix_trie::{Augment, AugmentedTrie};
+///
+/// /// The number of values and their sum
+/// struct CountSum;
+///
+/// impl Augment<u64> for CountSum {
+///     type Summary = (usize, u64);
+///
+///     fn summarize(&self, value: &u64) -> (usize, u64) {
+///         (1, *value)
+///     }
+///
+///     fn combine(&self, first: &(usize, u64), second: &(usize, u64)) -> (usize, u64) {
+///         (first.0 + second.0, first.1 + second.1)
+///     }
+/// }
+///
+/// let mut sizes = AugmentedTrie::new(CountSum);
+/// sizes.insert("/tmp/a", 10);
+/// sizes.insert("/tmp/b", 20);
+/// sizes.insert("/usr/c", 5);
+/// assert_eq!(sizes.summary("/tmp/"), Some((2, 30)));
+/// assert_eq!(sizes.summary(""), Some((3, 35)));
+/// assert_eq!(sizes.summary("/var"), None);
+/// ```
+pub struct AugmentedTrie<T, A: Augment<T>> {
+    trie: RadixTrie<T>,
+    /// The aggregate below every element, under the full label of the element
+    summaries: RadixTrie<A::Summary>,
+    augment: A,
+}
+
+impl<T, A: Augment<T>> AugmentedTrie<T, A> {
+    /// Construct an empty trie keeping the aggregates of the given augment
+    pub fn new(augment: A) -> Self {
+        AugmentedTrie {
+            trie: RadixTrie::new(),
+            summaries: RadixTrie::new(),
+            augment,
+        }
+    }
+
+    /// Insert the value under the label and recompute the aggregates on its path.
+    /// An existing value of the label is replaced and returned.
+    /// Inserting the empty label does nothing and returns None
+    pub fn insert(&mut self, label: &str, value: T) -> Option<T> {
+        let replaced = self.trie.insert(label, value);
+        self.refresh(label);
+        replaced
+    }
+
+    /// Removes the value of the label and returns it,
+    /// dropping the aggregates of the elements removed with it
+    pub fn remove(&mut self, label: &str) -> Option<T> {
+        let before = self
+            .trie
+            .path_to(label)
+            .into_iter()
+            .map(|(label, _)| label)
+            .collect::<Vec<_>>();
+        let removed = self.trie.remove(label)?;
+        let after = self.trie.path_to(label);
+        for label in &before {
+            if !after.iter().any(|(kept, _)| kept == label) {
+                self.summaries.remove(label);
+            }
+        }
+        self.refresh(label);
+        Some(removed)
+    }
+
+    /// Returns the borrowed value associated with related label.
+    /// If the label does not exist, return None
+    pub fn find(&self, label: &str) -> Option<&T> {
+        self.trie.find(label)
+    }
+
+    /// Number of values stored
+    pub fn len(&self) -> usize {
+        self.trie.len()
+    }
+
+    /// Returns true if no value is stored
+    pub fn is_empty(&self) -> bool {
+        self.trie.is_empty()
+    }
+
+    /// Returns the aggregate of the values of all labels starting with given prefix.
+    /// A non-empty prefix reads the cached aggregate of one element,
+    /// while the empty prefix combines the aggregates of the top level elements.
+    /// If no label starts with the prefix, return None
+    pub fn summary(&self, prefix: &str) -> Option<A::Summary> {
+        let mut label = String::new();
+        let element = self
+            .trie
+            .find_prefix_element(prefix, |parent| label.push_str(parent))?;
+        match element {
+            Element::Base { .. } => self.combine_children(element, &label, None),
+            _ => {
+                label.push_str(element.label());
+                self.summaries.find(&label).cloned()
+            }
+        }
+    }
+
+    /// Returns the trie holding the values
+    pub fn trie(&self) -> &RadixTrie<T> {
+        &self.trie
+    }
+
+    /// Consumes the wrapper and returns the trie holding the values
+    pub fn into_trie(self) -> RadixTrie<T> {
+        self.trie
+    }
+
+    /// Recompute the aggregates of the elements on the path of the label from the bottom up.
+    /// The elements off the path keep their subtrees, so their aggregates stay valid
+    fn refresh(&mut self, label: &str) {
+        for (label, element) in self.trie.path_to(label).into_iter().rev() {
+            let own = element.value().map(|value| self.augment.summarize(value));
+            // every element holds a value or has values below it
+            let summary = self.combine_children(element, &label, own).unwrap();
+            self.summaries.insert(&label, summary);
+        }
+    }
+
+    /// Combine the aggregate of the value of an element with the cached aggregates of its children
+    fn combine_children(
+        &self,
+        element: &Element<T>,
+        label: &str,
+        own: Option<A::Summary>,
+    ) -> Option<A::Summary> {
+        let mut child_label = String::from(label);
+        element.children().iter().fold(own, |summary, child| {
+            child_label.truncate(label.len());
+            child_label.push_str(child.label());
+            let child = self.summaries.find(&child_label).unwrap();
+            Some(match summary {
+                Some(summary) => self.augment.combine(&summary, child),
+                None => child.clone(),
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod augment_tests {
+    use crate::augment::{Augment, AugmentedTrie};
+    use crate::test_util::Lcg;
+    use crate::trie::RadixTrie;
+
+    /// The number of values, their sum, and the value of the smallest label
+    struct Stats;
+
+    impl Augment<u64> for Stats {
+        type Summary = (usize, u64, u64);
+
+        fn summarize(&self, value: &u64) -> (usize, u64, u64) {
+            (1, *value, *value)
+        }
+
+        fn combine(
+            &self,
+            first: &(usize, u64, u64),
+            second: &(usize, u64, u64),
+        ) -> (usize, u64, u64) {
+            (first.0 + second.0, first.1 + second.1, first.2)
+        }
+    }
+
+    #[test]
+    fn test_summary_matches_model() {
+        let mut trie = AugmentedTrie::new(Stats);
+        let mut model = RadixTrie::new();
+        let mut rng = Lcg(17);
+        for round in 0..600 {
+            let label = rng.label();
+            if round % 3 == 2 {
+                assert_eq!(trie.remove(&label), model.remove(&label));
+            } else {
+                let value = rng.next(100);
+                assert_eq!(trie.insert(&label, value), model.insert(&label, value));
+            }
+            // the aggregates are dropped with their elements
+            assert_eq!(trie.summaries.len(), model.node_count());
+        }
+        assert_eq!(trie.len(), model.len());
+        for prefix in &["", "a", "b", "多", "ab", "ba多", "c"] {
+            let values = model
+                .find_prefix_iter(prefix)
+                .map(|(_, value)| *value)
+                .collect::<Vec<_>>();
+            let expected = values
+                .first()
+                .map(|first| (values.len(), values.iter().sum(), *first));
+            assert_eq!(trie.summary(prefix), expected, "{}", prefix);
+        }
+        assert_eq!(trie.trie().len(), model.len());
+    }
+}
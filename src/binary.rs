@@ -0,0 +1,302 @@
+use crate::element::Element;
+use crate::trie::RadixTrie;
+use crate::util;
+use std::convert::TryFrom;
+use std::io::{self, Read, Write};
+
+/// Identifies the binary image and its version
+const MAGIC: &[u8; 4] = b"ART1";
+
+/// Values that can be written to and read back from the binary image of a trie
+pub trait BinaryValue: Sized {
+    /// Write the value to the writer
+    fn write_value<W: Write>(&self, writer: &mut W) -> io::Result<()>;
+    /// Read a value written by `write_value`
+    fn read_value<R: Read>(reader: &mut R) -> io::Result<Self>;
+}
+
+macro_rules! impl_binary_value_for_int {
+    ($($int: ty),*) => {
+        $(
+            impl BinaryValue for $int {
+                fn write_value<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+                    writer.write_all(&self.to_le_bytes())
+                }
+
+                fn read_value<R: Read>(reader: &mut R) -> io::Result<Self> {
+                    let mut bytes = [0; std::mem::size_of::<$int>()];
+                    reader.read_exact(&mut bytes)?;
+                    Ok(<$int>::from_le_bytes(bytes))
+                }
+            }
+        )*
+    };
+}
+
+impl_binary_value_for_int!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
+
+impl BinaryValue for usize {
+    /// Written as a u64, so images are portable between platforms
+    fn write_value<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        (*self as u64).write_value(writer)
+    }
+
+    fn read_value<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let value = u64::read_value(reader)?;
+        usize::try_from(value).map_err(|_| invalid_data("usize overflow"))
+    }
+}
+
+impl BinaryValue for bool {
+    fn write_value<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        (*self as u8).write_value(writer)
+    }
+
+    fn read_value<R: Read>(reader: &mut R) -> io::Result<Self> {
+        match u8::read_value(reader)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(invalid_data("invalid bool")),
+        }
+    }
+}
+
+impl BinaryValue for () {
+    fn write_value<W: Write>(&self, _: &mut W) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn read_value<R: Read>(_: &mut R) -> io::Result<Self> {
+        Ok(())
+    }
+}
+
+impl BinaryValue for Vec<u8> {
+    fn write_value<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        write_len(writer, self.len())?;
+        writer.write_all(self)
+    }
+
+    fn read_value<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let len = read_len(reader)?;
+        let mut bytes = vec![];
+        reader.take(len as u64).read_to_end(&mut bytes)?;
+        if bytes.len() != len {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        Ok(bytes)
+    }
+}
+
+impl BinaryValue for String {
+    fn write_value<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        write_len(writer, self.len())?;
+        writer.write_all(self.as_bytes())
+    }
+
+    fn read_value<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let bytes = Vec::<u8>::read_value(reader)?;
+        String::from_utf8(bytes).map_err(|_| invalid_data("string is not UTF-8"))
+    }
+}
+
+fn write_len<W: Write>(writer: &mut W, len: usize) -> io::Result<()> {
+    let len = u32::try_from(len)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "length exceeds u32"))?;
+    len.write_value(writer)
+}
+
+fn read_len<R: Read>(reader: &mut R) -> io::Result<usize> {
+    Ok(u32::read_value(reader)? as usize)
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_owned())
+}
+
+/// An element read from the image whose children are still being read
+struct Frame<T> {
+    label: String,
+    value: Option<T>,
+    children: Vec<Element<T>>,
+    /// Number of children not read yet
+    remaining: usize,
+}
+
+impl<T: BinaryValue> Frame<T> {
+    fn read<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let label = String::read_value(reader)?;
+        let value = match u8::read_value(reader)? {
+            0 => None,
+            1 => Some(T::read_value(reader)?),
+            _ => return Err(invalid_data("invalid value flag")),
+        };
+        let remaining = read_len(reader)?;
+        Ok(Frame {
+            label,
+            value,
+            children: Vec::with_capacity(remaining.min(1024)),
+            remaining,
+        })
+    }
+}
+
+impl<T: BinaryValue> RadixTrie<T> {
+    /// Write a compact binary image of the trie.
+    /// Elements are written in pre-order with their labels, values, and number of children,
+    /// so reading the image back rebuilds the same structure without any search.
+    /// Wrap the writer in a `BufWriter` for files
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<u32>::new();
+    /// trie.insert("ON", 647);
+    /// trie.insert("ON2", 416);
+    /// let mut image = vec![];
+    /// trie.write_to(&mut image).unwrap();
+    /// let loaded = RadixTrie::<u32>::read_from(image.as_slice()).unwrap();
+    /// assert_eq!(loaded.find("ON2"), Some(&416));
+    /// assert_eq!(loaded.len(), 2);
+    /// ```
+    pub fn write_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        let mut stack = vec![&self.entry];
+        while let Some(element) = stack.pop() {
+            write_len(&mut writer, element.label().len())?;
+            writer.write_all(element.label().as_bytes())?;
+            match element.value() {
+                Some(value) => {
+                    1u8.write_value(&mut writer)?;
+                    value.write_value(&mut writer)?;
+                }
+                None => 0u8.write_value(&mut writer)?,
+            }
+            write_len(&mut writer, element.children().len())?;
+            stack.extend(element.children().iter().rev());
+        }
+        writer.flush()
+    }
+
+    /// Read a trie from an image written by `write_to`.
+    /// The structure of the image is validated, and an `InvalidData` error is returned
+    /// if it breaks the invariants of the trie.
+    /// Wrap the reader in a `BufReader` for files
+    pub fn read_from<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_data("not a trie image"));
+        }
+        let root = Frame::read(&mut reader)?;
+        if !root.label.is_empty() || root.value.is_some() {
+            return Err(invalid_data("invalid root"));
+        }
+        let mut len = 0;
+        let mut stack = vec![root];
+        loop {
+            let top = stack.last_mut().unwrap();
+            if top.remaining > 0 {
+                top.remaining -= 1;
+                let child = Frame::read(&mut reader)?;
+                if child.label.is_empty() {
+                    return Err(invalid_data("empty label"));
+                }
+                len += child.value.is_some() as usize;
+                stack.push(child);
+                continue;
+            }
+            let Frame {
+                label,
+                value,
+                children,
+                ..
+            } = stack.pop().unwrap();
+            let parent = match stack.last_mut() {
+                Some(parent) => parent,
+                None => {
+                    return Ok(RadixTrie {
+                        entry: Element::Base { label, children },
+                        len,
+                    })
+                }
+            };
+            if value.is_none() && children.len() < 2 {
+                return Err(invalid_data("uncompacted element"));
+            }
+            let first_char = util::first_char(&label);
+            if let Some(previous) = parent.children.last() {
+                if util::first_char(previous.label()) >= first_char {
+                    return Err(invalid_data("children are not sorted"));
+                }
+            }
+            parent.children.push(Element::pack(label, value, children));
+        }
+    }
+}
+
+#[cfg(test)]
+mod binary_tests {
+    use crate::binary::BinaryValue;
+    use crate::trie::RadixTrie;
+    use std::io;
+
+    fn get_test_trie() -> RadixTrie<String> {
+        let mut trie = RadixTrie::new();
+        let words = ["Won", "Wonder", "Wonderful", "World", "Axes", "多倫多"];
+        for word in &words {
+            trie.insert(word, word.to_uppercase());
+        }
+        trie
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let trie = get_test_trie();
+        let mut image = vec![];
+        trie.write_to(&mut image).unwrap();
+        let loaded = RadixTrie::<String>::read_from(image.as_slice()).unwrap();
+        assert_eq!(format!("{:?}", loaded.entry), format!("{:?}", trie.entry));
+        assert_eq!(loaded.len(), trie.len());
+
+        let mut image = vec![];
+        RadixTrie::<u64>::new().write_to(&mut image).unwrap();
+        let loaded = RadixTrie::<u64>::read_from(image.as_slice()).unwrap();
+        assert!(loaded.is_empty());
+    }
+
+    #[test]
+    fn test_value_round_trip() {
+        let mut image = vec![];
+        (-3i16).write_value(&mut image).unwrap();
+        usize::MAX.write_value(&mut image).unwrap();
+        true.write_value(&mut image).unwrap();
+        1.5f64.write_value(&mut image).unwrap();
+        vec![1u8, 2].write_value(&mut image).unwrap();
+        let mut reader = image.as_slice();
+        assert_eq!(i16::read_value(&mut reader).unwrap(), -3);
+        assert_eq!(usize::read_value(&mut reader).unwrap(), usize::MAX);
+        assert!(bool::read_value(&mut reader).unwrap());
+        assert_eq!(f64::read_value(&mut reader).unwrap(), 1.5);
+        assert_eq!(Vec::<u8>::read_value(&mut reader).unwrap(), vec![1, 2]);
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn test_rejects_broken_images() {
+        let mut image = vec![];
+        get_test_trie().write_to(&mut image).unwrap();
+        let truncated = RadixTrie::<String>::read_from(&image[..image.len() - 1]);
+        assert_eq!(
+            truncated.err().unwrap().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+        let mut wrong_magic = image.clone();
+        wrong_magic[0] = b'X';
+        let res = RadixTrie::<String>::read_from(wrong_magic.as_slice());
+        assert_eq!(res.err().unwrap().kind(), io::ErrorKind::InvalidData);
+        // the values are strings, so reading them as u8 misreads the structure
+        let res = RadixTrie::<u8>::read_from(image.as_slice());
+        assert!(res.is_err());
+    }
+}
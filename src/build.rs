@@ -0,0 +1,435 @@
+use crate::element::Element;
+use crate::error::TrieError;
+use crate::iter::Iter;
+use crate::trie::RadixTrie;
+use crate::util;
+use alloc::borrow::ToOwned;
+use alloc::collections::TryReserveError;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::iter::FromIterator;
+
+/// An element under construction on the current path of the builder
+struct Frame<T> {
+    label: String,
+    value: Option<T>,
+    /// Position of the first child of this element among the closed elements of the builder
+    children_start: usize,
+    /// Offset in the key where the label of this element ends
+    end: usize,
+}
+
+impl<T> Frame<T> {
+    fn start(&self) -> usize {
+        self.end - self.label.len()
+    }
+}
+
+/// Builds a trie bottom up from entries sorted by label without duplicates.
+/// Panics if the labels are not sorted and unique
+pub(crate) fn from_sorted<K, T, I>(entries: I) -> RadixTrie<T>
+where
+    K: AsRef<str>,
+    I: IntoIterator<Item = (K, T)>,
+{
+    try_from_sorted(entries).unwrap_or_else(|error| panic!("{}", error))
+}
+
+/// Builds a trie bottom up from entries sorted by label without duplicates.
+/// Only the elements on the path of the last label are kept open,
+/// so every entry is placed without descending from the root
+fn try_from_sorted<K, T, I>(entries: I) -> Result<RadixTrie<T>, TrieError>
+where
+    K: AsRef<str>,
+    I: IntoIterator<Item = (K, T)>,
+{
+    let mut builder = SortedBuilder::new();
+    for (label, value) in entries {
+        let label = label.as_ref();
+        if !label.is_empty() && builder.push(label, value).is_err() {
+            return Err(TrieError::UnsortedLabels);
+        }
+    }
+    Ok(builder.finish())
+}
+
+/// A trie built bottom up from non-empty labels arriving in ascending order.
+/// The children of the open elements wait in one buffer until their parent is closed,
+/// when their number is known and they are moved into a vector of exactly that capacity
+pub(crate) struct SortedBuilder<T> {
+    /// The open elements on the path of the last label, the root first
+    stack: Vec<Frame<T>>,
+    /// The closed elements whose parents are still open, in the order of their labels
+    closed: Vec<Element<T>>,
+    previous: String,
+    len: usize,
+}
+
+impl<T> SortedBuilder<T> {
+    pub(crate) fn new() -> Self {
+        SortedBuilder {
+            stack: vec![root_frame()],
+            closed: vec![],
+            previous: String::new(),
+            len: 0,
+        }
+    }
+
+    /// Construct the builder like `new`, reporting a failed allocation instead of aborting
+    pub(crate) fn try_new() -> Result<Self, TryReserveError> {
+        let mut stack = Vec::new();
+        stack.try_reserve_exact(1)?;
+        stack.push(root_frame());
+        Ok(SortedBuilder {
+            stack,
+            closed: vec![],
+            previous: String::new(),
+            len: 0,
+        })
+    }
+
+    /// Adds the non-empty label after the previous one,
+    /// or gives the value back if the label does not come after it
+    pub(crate) fn push(&mut self, label: &str, value: T) -> Result<(), T> {
+        if !self.follows(label) {
+            return Err(value);
+        }
+        let shared_len = util::longest_shared_prefix(&self.previous, label).len();
+        close_until(&mut self.stack, &mut self.closed, shared_len, None);
+        self.open(label, label[shared_len..].to_owned(), value);
+        Ok(())
+    }
+
+    /// Adds the non-empty label like `push`, but allocates everything the push needs first.
+    /// A failed allocation is reported as [`TrieError::AllocationFailed`] and leaves the builder unchanged
+    pub(crate) fn try_push(&mut self, label: &str, value: T) -> Result<(), TrieError> {
+        if !self.follows(label) {
+            return Err(TrieError::UnsortedLabels);
+        }
+        let shared_len = util::longest_shared_prefix(&self.previous, label).len();
+        let reserved = (|| {
+            self.stack.try_reserve(1)?;
+            self.previous
+                .try_reserve(label.len().saturating_sub(self.previous.len()))?;
+            let new_label = try_to_owned(&label[shared_len..])?;
+            let close = reserve_close(&self.stack, &mut self.closed, shared_len)?;
+            Ok((new_label, close))
+        })();
+        let (new_label, close) =
+            reserved.map_err(|_: TryReserveError| TrieError::AllocationFailed)?;
+        close_until(&mut self.stack, &mut self.closed, shared_len, Some(close));
+        self.open(label, new_label, value);
+        Ok(())
+    }
+
+    fn follows(&self, label: &str) -> bool {
+        self.len == 0 || self.previous.as_str() < label
+    }
+
+    /// Opens the element of the label, whose own part is given, on top of the stack
+    fn open(&mut self, label: &str, own_label: String, value: T) {
+        self.stack.push(Frame {
+            label: own_label,
+            value: Some(value),
+            children_start: self.closed.len(),
+            end: label.len(),
+        });
+        self.previous.clear();
+        self.previous.push_str(label);
+        self.len += 1;
+    }
+
+    pub(crate) fn finish(mut self) -> RadixTrie<T> {
+        close_until(&mut self.stack, &mut self.closed, 0, None);
+        let children = self.closed.drain(..).collect();
+        self.into_trie(children)
+    }
+
+    /// Finishes the trie like `finish`, reporting a failed allocation instead of aborting
+    pub(crate) fn try_finish(mut self) -> Result<RadixTrie<T>, TryReserveError> {
+        let close = reserve_close(&self.stack, &mut self.closed, 0)?;
+        close_until(&mut self.stack, &mut self.closed, 0, Some(close));
+        let mut children = Vec::new();
+        children.try_reserve_exact(self.closed.len())?;
+        children.append(&mut self.closed);
+        Ok(self.into_trie(children))
+    }
+
+    fn into_trie(self, children: Vec<Element<T>>) -> RadixTrie<T> {
+        RadixTrie {
+            entry: Element::Base {
+                label: String::new(),
+                children,
+            },
+            len: self.len,
+        }
+    }
+}
+
+/// The root never closes, so its end stays at the start of every key
+fn root_frame<T>() -> Frame<T> {
+    Frame {
+        label: String::new(),
+        value: None,
+        children_start: 0,
+        end: 0,
+    }
+}
+
+/// The buffers of the elements closed by `close_until`, allocated ahead by `reserve_close`
+struct Close<T> {
+    /// The children of every closed element, the last one to close first
+    children: Vec<Vec<Element<T>>>,
+    /// The labels of the head and the tail of a split element
+    split: Option<(String, String)>,
+}
+
+/// Allocates what `close_until` needs to close the open elements beyond the given offset:
+/// a vector for the children of every closed element, the room for the closed element
+/// left without its parent, and the labels of the split if there is one
+fn reserve_close<T>(
+    stack: &[Frame<T>],
+    closed: &mut Vec<Element<T>>,
+    offset: usize,
+) -> Result<Close<T>, TryReserveError> {
+    closed.try_reserve(1)?;
+    let closing = stack
+        .iter()
+        .rev()
+        .take_while(|frame| frame.end > offset)
+        .count();
+    let mut children = Vec::new();
+    children.try_reserve_exact(closing)?;
+    let mut split = None;
+    let mut closed_len = closed.len();
+    for frame in stack.iter().rev().take(closing) {
+        let mut buffer = Vec::new();
+        buffer.try_reserve_exact(closed_len - frame.children_start)?;
+        children.push(buffer);
+        // the children of the frame are replaced by the frame itself
+        closed_len = frame.children_start + 1;
+        if frame.start() < offset {
+            let head_len = offset - frame.start();
+            let head = try_to_owned(&frame.label[..head_len])?;
+            let tail = try_to_owned(&frame.label[head_len..])?;
+            split = Some((head, tail));
+        }
+    }
+    children.reverse();
+    Ok(Close { children, split })
+}
+
+fn try_to_owned(label: &str) -> Result<String, TryReserveError> {
+    let mut owned = String::new();
+    owned.try_reserve_exact(label.len())?;
+    owned.push_str(label);
+    Ok(owned)
+}
+
+/// Close the open elements beyond the given offset, moving their children out of the buffer.
+/// An element spanning the offset is split there, and its head stays open as a node holding the tail.
+/// Without the buffers of `reserve_close`, they are allocated as the elements close
+fn close_until<T>(
+    stack: &mut Vec<Frame<T>>,
+    closed: &mut Vec<Element<T>>,
+    offset: usize,
+    mut close: Option<Close<T>>,
+) {
+    while stack.last().unwrap().end > offset {
+        let top = stack.pop().unwrap();
+        let children = match close.as_mut().and_then(|close| close.children.pop()) {
+            Some(mut children) => {
+                children.extend(closed.drain(top.children_start..));
+                children
+            }
+            None => closed.drain(top.children_start..).collect(),
+        };
+        if top.start() < offset {
+            let head_len = offset - top.start();
+            let (head, tail) = match close.as_mut().and_then(|close| close.split.take()) {
+                Some(split) => split,
+                None => (
+                    top.label[..head_len].to_owned(),
+                    top.label[head_len..].to_owned(),
+                ),
+            };
+            closed.push(Element::pack(tail, top.value, children));
+            stack.push(Frame {
+                label: head,
+                value: None,
+                children_start: closed.len() - 1,
+                end: offset,
+            });
+        } else {
+            closed.push(Element::pack(top.label, top.value, children));
+        }
+    }
+}
+
+impl<T> RadixTrie<T> {
+    /// Returns a lazy iterator over all labels and borrowed values in lexicographic order,
+    /// the snapshot format read back by [`RadixTrie::import_sorted`].
+    /// Labels are produced one at a time, so a snapshot can be sent in chunks
+    /// without collecting the pairs first
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("lab", 3);
+    /// trie.insert("label", 5);
+    /// let mut export = trie.export();
+    /// assert_eq!(export.next(), Some((String::from("lab"), &3)));
+    /// ```
+    pub fn export(&self) -> Iter<'_, T> {
+        self.iter()
+    }
+
+    /// Rebuilds a trie from labels and values sorted by label without duplicates,
+    /// such as the pairs produced by [`RadixTrie::export`].
+    /// The trie is built bottom up as the pairs arrive, without searching from the root for each label
+    /// and without holding the pairs in memory.
+    /// Every label and children vector is allocated once at its final size,
+    /// so the trie needs no [`RadixTrie::shrink_to_fit`].
+    /// Empty labels are skipped, and labels out of order are reported as an error
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::{RadixTrie, TrieError};
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("lab", 3);
+    /// trie.insert("label", 5);
+    /// trie.insert("lazy", 4);
+    /// let chunks = vec![vec![("lab", 3), ("label", 5)], vec![("lazy", 4)]];
+    /// let imported = RadixTrie::import_sorted(chunks.into_iter().flatten()).unwrap();
+    /// assert_eq!(imported, trie);
+    /// let unsorted = RadixTrie::import_sorted(vec![("b", 1), ("a", 2)]);
+    /// assert_eq!(unsorted, Err(TrieError::UnsortedLabels));
+    /// ```
+    pub fn import_sorted<K, I>(entries: I) -> Result<Self, TrieError>
+    where
+        K: AsRef<str>,
+        I: IntoIterator<Item = (K, T)>,
+    {
+        try_from_sorted(entries)
+    }
+}
+
+impl<K: AsRef<str>, T> FromIterator<(K, T)> for RadixTrie<T> {
+    /// Sorts the entries and builds the trie bottom up.
+    /// When a label appears more than once, the last value wins like in `insert`
+    fn from_iter<I: IntoIterator<Item = (K, T)>>(iter: I) -> Self {
+        let mut entries = iter.into_iter().collect::<Vec<_>>();
+        // stable sort keeps duplicated labels in their original order
+        entries.sort_by(|(label1, _), (label2, _)| label1.as_ref().cmp(label2.as_ref()));
+        let mut unique: Vec<(K, T)> = Vec::with_capacity(entries.len());
+        for (label, value) in entries {
+            match unique.last_mut() {
+                Some(last) if last.0.as_ref() == label.as_ref() => last.1 = value,
+                _ => unique.push((label, value)),
+            }
+        }
+        from_sorted(unique)
+    }
+}
+
+impl<K: AsRef<str>, T> Extend<(K, T)> for RadixTrie<T> {
+    fn extend<I: IntoIterator<Item = (K, T)>>(&mut self, iter: I) {
+        if self.entry.children().is_empty() {
+            *self = iter.into_iter().collect();
+            return;
+        }
+        for (label, value) in iter {
+            self.insert(label.as_ref(), value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod build_tests {
+    use crate::error::TrieError;
+    use crate::trie::RadixTrie;
+
+    fn inserted(words: &[&str]) -> RadixTrie<usize> {
+        let mut trie = RadixTrie::new();
+        for (index, word) in words.iter().enumerate() {
+            trie.insert(word, index);
+        }
+        trie
+    }
+
+    #[test]
+    fn test_from_iter_matches_insert() {
+        let words = [
+            "Wonderful",
+            "World",
+            "Won",
+            "Axes",
+            "Wonder",
+            "exe",
+            "execute",
+            "exec",
+            "example",
+            "ex",
+            "Toronto多倫多",
+            "Toronto多伦多",
+            "exec",
+        ];
+        let built = words
+            .iter()
+            .enumerate()
+            .map(|(index, word)| (*word, index))
+            .collect::<RadixTrie<_>>();
+        let expected = inserted(&words);
+        assert_eq!(
+            format!("{:?}", built.entry),
+            format!("{:?}", expected.entry)
+        );
+        assert_eq!(built.find("exec"), Some(&12));
+    }
+
+    #[test]
+    fn test_extend() {
+        let mut trie = RadixTrie::new();
+        trie.extend(vec![(String::from("Won"), 0), (String::from("Wonder"), 1)]);
+        trie.extend(vec![("World", 2), ("Wonder", 3)]);
+        let expected = inserted(&["Won", "Wonder", "World", "Wonder"]);
+        assert_eq!(format!("{:?}", trie.entry), format!("{:?}", expected.entry));
+        let empty = Vec::<(&str, usize)>::new()
+            .into_iter()
+            .collect::<RadixTrie<_>>();
+        assert!(empty.iter().next().is_none());
+    }
+
+    #[test]
+    fn test_export_import_sorted() {
+        let trie = inserted(&[
+            "Wonderful",
+            "World",
+            "Won",
+            "Axes",
+            "Wonder",
+            "多倫多",
+            "Wo",
+        ]);
+        let exported = trie.export().collect::<Vec<_>>();
+        assert_eq!(exported, trie.iter().collect::<Vec<_>>());
+        let imported =
+            RadixTrie::import_sorted(trie.export().map(|(label, value)| (label, *value))).unwrap();
+        assert_eq!(format!("{:?}", imported.entry), format!("{:?}", trie.entry));
+        assert_eq!(imported.len(), trie.len());
+        // the children of every element are collected once their number is known
+        let stats = imported.memory_usage();
+        assert_eq!(stats.children_capacity, stats.children_len);
+        assert_eq!(stats.label_capacity, stats.label_bytes);
+        assert_eq!(
+            RadixTrie::import_sorted(vec![("Won", 0), ("Won", 1)]),
+            Err(TrieError::UnsortedLabels)
+        );
+        assert_eq!(
+            RadixTrie::import_sorted(vec![("", 0), ("Won", 1), ("World", 2)])
+                .map(|trie| trie.len()),
+            Ok(2)
+        );
+    }
+}
@@ -0,0 +1,104 @@
+use crate::entry::Entry;
+use crate::trie::RadixTrie;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// A trie that loads missing values on lookup.
+///
+/// On a miss, `find` invokes the loader with the label, caches the loaded value,
+/// and returns it. Labels the loader has no value for are not cached.
+///
+/// # Example
+/// ```rust
+/// use another_radix_trie::ReadThroughTrie;
+///
+/// let mut cache = ReadThroughTrie::new(|label: &str| Some(label.len()));
+/// assert_eq!(cache.find("label"), Some(&5));
+/// assert_eq!(cache.start_with("lab"), vec![(String::from("label"), &5)]);
+/// ```
+pub struct ReadThroughTrie<T, F> {
+    trie: RadixTrie<T>,
+    loader: F,
+}
+
+impl<T, F> ReadThroughTrie<T, F>
+where
+    F: FnMut(&str) -> Option<T>,
+{
+    /// Construct an empty cache with the given loader
+    pub fn new(loader: F) -> Self {
+        Self::with_trie(RadixTrie::new(), loader)
+    }
+
+    /// Construct a cache preloaded with the values of the trie
+    pub fn with_trie(trie: RadixTrie<T>, loader: F) -> Self {
+        ReadThroughTrie { trie, loader }
+    }
+
+    /// Returns the cached value of the label, loading and caching it on a miss.
+    /// The label is searched once, and a loaded value goes where the search stopped.
+    /// If the loader has no value for the label, return None
+    pub fn find(&mut self, label: &str) -> Option<&T> {
+        if label.is_empty() {
+            return None;
+        }
+        match self.trie.entry(label) {
+            Entry::Occupied(entry) => Some(entry.into_mut()),
+            Entry::Vacant(entry) => {
+                let value = (self.loader)(entry.key())?;
+                Some(entry.insert(value))
+            }
+        }
+    }
+
+    /// Returns the cached value of the label without invoking the loader
+    pub fn find_cached(&self, label: &str) -> Option<&T> {
+        self.trie.find(label)
+    }
+
+    /// Returns all cached values with their labels where the labels start with given prefix
+    pub fn start_with(&self, prefix: &str) -> Vec<(String, &T)> {
+        self.trie.start_with(prefix)
+    }
+
+    /// Removes the cached value, so the next lookup goes through the loader again
+    pub fn invalidate(&mut self, label: &str) -> Option<T> {
+        self.trie.remove(label)
+    }
+
+    /// Returns the trie holding the cached values
+    pub fn into_trie(self) -> RadixTrie<T> {
+        self.trie
+    }
+}
+
+#[cfg(test)]
+mod cache_tests {
+    use crate::cache::ReadThroughTrie;
+
+    #[test]
+    fn test_read_through() {
+        let mut loads = 0;
+        let mut cache = ReadThroughTrie::new(|label: &str| {
+            loads += 1;
+            if label.starts_with("W") {
+                Some(label.len())
+            } else {
+                None
+            }
+        });
+        assert_eq!(cache.find("Wonder"), Some(&6));
+        assert_eq!(cache.find("Wonder"), Some(&6));
+        assert_eq!(cache.find("Axes"), None);
+        assert_eq!(cache.find_cached("Axes"), None);
+        assert_eq!(cache.find("Won"), Some(&3));
+        assert_eq!(cache.start_with("Won").len(), 2);
+        assert_eq!(cache.invalidate("Won"), Some(3));
+        assert_eq!(cache.find_cached("Won"), None);
+        assert_eq!(cache.find("Won"), Some(&3));
+        assert_eq!(cache.find(""), None);
+        let trie = cache.into_trie();
+        assert_eq!(trie.find("Wonder"), Some(&6));
+        assert_eq!(loads, 4);
+    }
+}
@@ -0,0 +1,423 @@
+use crate::element::Element;
+use crate::util;
+use alloc::collections::VecDeque;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+
+/// A read-only trie compiled from a [`RadixTrie`](crate::RadixTrie).
+///
+/// Nodes are numbered breadth first, so the children of a node are contiguous.
+/// The shape of the tree is encoded as LOUDS bits, one set bit per child and a clear bit after
+/// the children of every node, and another bit per node tells whether it holds a value.
+/// Both are read through rank and select queries, so the structure takes a few bits per node
+/// on top of the labels and one offset per node into the concatenated labels.
+///
+/// # Example
+/// ```rust
+/// use another_radix_trie::RadixTrie;
+///
+/// let mut trie = RadixTrie::<usize>::new();
+/// trie.insert("ON", 647);
+/// trie.insert("ON2", 416);
+/// let compiled = trie.compile();
+/// assert_eq!(compiled.find("ON2"), Some(&416));
+/// ```
+pub struct CompiledRadixTrie<T> {
+    /// All labels concatenated in node order
+    labels: String,
+    /// Node `i` has label `labels[label_offsets[i]..label_offsets[i + 1]]`
+    label_offsets: Vec<u32>,
+    /// The children of every node in node order, as set bits ended by a clear bit
+    louds: Bits,
+    /// Bit `i` is set if node `i` holds a value, which is at the rank of the bit in `values`
+    has_value: Bits,
+    values: Vec<T>,
+}
+
+const ROOT: usize = 0;
+/// Number of bits in a word of [`Bits`]
+const WORD_BITS: usize = 64;
+
+/// A bit vector answering rank and select queries through the number of set bits before every word
+struct Bits {
+    words: Vec<u64>,
+    /// Number of set bits in the words before each word
+    ranks: Vec<u32>,
+    len: usize,
+}
+
+impl Bits {
+    fn new() -> Self {
+        Bits {
+            words: vec![],
+            ranks: vec![],
+            len: 0,
+        }
+    }
+
+    fn push(&mut self, bit: bool) {
+        if self.len == self.words.len() * WORD_BITS {
+            self.words.push(0);
+        }
+        if bit {
+            *self.words.last_mut().unwrap() |= 1 << (self.len % WORD_BITS);
+        }
+        self.len += 1;
+    }
+
+    /// Count the set bits before every word once all bits are pushed
+    fn finish(&mut self) {
+        let mut rank = 0;
+        self.ranks = Vec::with_capacity(self.words.len());
+        for word in &self.words {
+            self.ranks.push(to_u32(rank));
+            rank += word.count_ones() as usize;
+        }
+        self.words.shrink_to_fit();
+    }
+
+    fn get(&self, index: usize) -> bool {
+        self.words[index / WORD_BITS] & (1 << (index % WORD_BITS)) != 0
+    }
+
+    /// Number of set bits before the index
+    fn rank(&self, index: usize) -> usize {
+        let word = index / WORD_BITS;
+        let below = (1u64 << (index % WORD_BITS)) - 1;
+        self.ranks[word] as usize + (self.words[word] & below).count_ones() as usize
+    }
+
+    /// Position of the clear bit with the given number of clear bits before it
+    fn select_clear(&self, nth: usize) -> usize {
+        // the last word with at most `nth` clear bits before it holds the bit
+        let clear_before = |word: usize| word * WORD_BITS - self.ranks[word] as usize;
+        let (mut first, mut last) = (0, self.words.len());
+        while last - first > 1 {
+            let mid = first + (last - first) / 2;
+            if clear_before(mid) <= nth {
+                first = mid;
+            } else {
+                last = mid;
+            }
+        }
+        let mut clear = !self.words[first];
+        for _ in 0..nth - clear_before(first) {
+            clear &= clear - 1;
+        }
+        first * WORD_BITS + clear.trailing_zeros() as usize
+    }
+
+    fn heap_bytes(&self) -> usize {
+        self.words.capacity() * core::mem::size_of::<u64>()
+            + self.ranks.capacity() * core::mem::size_of::<u32>()
+    }
+}
+
+impl<T> CompiledRadixTrie<T> {
+    /// Lay out the element tree breadth first
+    pub(crate) fn from_element(root: Element<T>) -> Self {
+        let mut compiled = CompiledRadixTrie {
+            labels: String::new(),
+            label_offsets: vec![0],
+            louds: Bits::new(),
+            has_value: Bits::new(),
+            values: vec![],
+        };
+        let mut queue = VecDeque::new();
+        queue.push_back(root);
+        while let Some(element) = queue.pop_front() {
+            let (label, value, children) = element.unpack();
+            compiled.labels.push_str(&label);
+            compiled.label_offsets.push(to_u32(compiled.labels.len()));
+            compiled.has_value.push(value.is_some());
+            compiled.values.extend(value);
+            for _ in &children {
+                compiled.louds.push(true);
+            }
+            compiled.louds.push(false);
+            queue.extend(children);
+        }
+        compiled.louds.finish();
+        compiled.has_value.finish();
+        compiled.labels.shrink_to_fit();
+        compiled.label_offsets.shrink_to_fit();
+        compiled.values.shrink_to_fit();
+        compiled
+    }
+
+    /// Returns the borrowed value associated with related label.
+    /// If the label does not exist, return None
+    pub fn find(&self, mut label: &str) -> Option<&T> {
+        let mut node = ROOT;
+        while !label.is_empty() {
+            let child = self.find_child(node, util::first_char(label))?;
+            let child_label = self.label(child);
+            if child_label == label {
+                return self.value(child);
+            } else if label.starts_with(child_label) {
+                label = &label[child_label.len()..];
+                node = child;
+            } else {
+                break;
+            }
+        }
+        None
+    }
+
+    /// Returns all values with their labels where the labels start with given prefix.
+    /// The results are in the same order as [`RadixTrie::start_with`](crate::RadixTrie::start_with)
+    pub fn start_with(&self, mut prefix: &str) -> Vec<(String, &T)> {
+        if prefix.is_empty() {
+            return self.collect_all_child_values(ROOT, String::new());
+        }
+        let mut node = ROOT;
+        let mut existing_prefix = String::new();
+        while !prefix.is_empty() {
+            let child = match self.find_child(node, util::first_char(prefix)) {
+                Some(child) => child,
+                None => break,
+            };
+            let child_label = self.label(child);
+            if child_label.starts_with(prefix) {
+                return self.collect_all_child_values(child, existing_prefix);
+            } else if prefix.starts_with(child_label) {
+                existing_prefix.push_str(child_label);
+                prefix = &prefix[child_label.len()..];
+                node = child;
+            } else {
+                break;
+            }
+        }
+        vec![]
+    }
+
+    /// Returns true if the label exists in the trie
+    pub fn contains_key(&self, label: &str) -> bool {
+        self.find(label).is_some()
+    }
+
+    /// Returns the borrowed value associated with the longest label
+    /// that is a prefix of the given query, together with the matched part of the query.
+    /// If no label is a prefix of the query, return None
+    pub fn longest_prefix<'a>(&self, query: &'a str) -> Option<(&'a str, &T)> {
+        let mut node = ROOT;
+        let mut matched = 0;
+        let mut longest = None;
+        while matched < query.len() {
+            let rest = &query[matched..];
+            let child = match self.find_child(node, util::first_char(rest)) {
+                Some(child) if rest.starts_with(self.label(child)) => child,
+                _ => break,
+            };
+            matched += self.label(child).len();
+            if let Some(value) = self.value(child) {
+                longest = Some((matched, value));
+            }
+            node = child;
+        }
+        longest.map(|(len, value)| (&query[..len], value))
+    }
+
+    /// Estimated bytes allocated on the heap by the compiled trie,
+    /// to compare with [`RadixTrie::memory_usage`](crate::RadixTrie::memory_usage).
+    /// Only the inline size of the values is counted
+    pub fn heap_bytes(&self) -> usize {
+        self.labels.capacity()
+            + self.label_offsets.capacity() * core::mem::size_of::<u32>()
+            + self.louds.heap_bytes()
+            + self.has_value.heap_bytes()
+            + self.values.capacity() * core::mem::size_of::<T>()
+    }
+
+    /// Number of values stored
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns true if no value is stored
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    fn label(&self, node: usize) -> &str {
+        let start = self.label_offsets[node] as usize;
+        let end = self.label_offsets[node + 1] as usize;
+        &self.labels[start..end]
+    }
+
+    fn value(&self, node: usize) -> Option<&T> {
+        match self.has_value.get(node) {
+            true => Some(&self.values[self.has_value.rank(node)]),
+            false => None,
+        }
+    }
+
+    fn children(&self, node: usize) -> core::ops::Range<usize> {
+        self.first_child(node)..self.first_child(node + 1)
+    }
+
+    /// The number of the first child of the node, counting the children of the nodes before it.
+    /// Every set bit before the clear bit ending the previous node is a node after the root
+    fn first_child(&self, node: usize) -> usize {
+        match node {
+            ROOT => 1,
+            _ => self.louds.select_clear(node - 1) - (node - 1) + 1,
+        }
+    }
+
+    /// Binary search the child whose label starts with the given char
+    fn find_child(&self, node: usize, target: char) -> Option<usize> {
+        let children = self.children(node);
+        let (mut first, mut last) = (children.start, children.end);
+        while first < last {
+            let mid = first + (last - first) / 2;
+            if util::first_char(self.label(mid)) < target {
+                first = mid + 1;
+            } else {
+                last = mid;
+            }
+        }
+        if first < children.end && util::first_char(self.label(first)) == target {
+            Some(first)
+        } else {
+            None
+        }
+    }
+
+    fn collect_all_child_values(&self, node: usize, prefix: String) -> Vec<(String, &T)> {
+        let mut res = vec![];
+        let mut queue = VecDeque::new();
+        queue.push_back((node, prefix));
+        while let Some((node, mut label)) = queue.pop_front() {
+            label.push_str(self.label(node));
+            queue.extend(self.children(node).map(|child| (child, label.clone())));
+            if let Some(value) = self.value(node) {
+                res.push((label, value));
+            }
+        }
+        res
+    }
+}
+
+fn to_u32(n: usize) -> u32 {
+    match u32::try_from(n) {
+        Ok(n) => n,
+        _ => panic!("Compiled trie exceeds u32 capacity"),
+    }
+}
+
+#[cfg(test)]
+mod compiled_tests {
+    use crate::test_util::Lcg;
+    use crate::trie::RadixTrie;
+
+    #[test]
+    fn test_compiled_matches_trie() {
+        let mut trie = RadixTrie::<usize>::new();
+        let words = [
+            "Won",
+            "Wonder",
+            "Wonderful",
+            "World",
+            "Axes",
+            "Toronto多倫多",
+        ];
+        for word in &words {
+            trie.insert(word, word.len());
+        }
+        let expected = trie
+            .start_with("W")
+            .into_iter()
+            .map(|(label, value)| (label, *value))
+            .collect::<Vec<_>>();
+        let compiled = trie.compile();
+        assert_eq!(compiled.len(), words.len());
+        for word in &words {
+            assert_eq!(compiled.find(word), Some(&word.len()));
+        }
+        assert_eq!(compiled.find("Wo"), None);
+        assert_eq!(compiled.find("Wonders"), None);
+        assert_eq!(compiled.find("Toronto"), None);
+        let res = compiled
+            .start_with("W")
+            .into_iter()
+            .map(|(label, value)| (label, *value))
+            .collect::<Vec<_>>();
+        assert_eq!(res, expected);
+        assert_eq!(compiled.start_with("Wonde").len(), 2);
+        assert!(compiled.start_with("Wx").is_empty());
+        assert_eq!(compiled.start_with("").len(), words.len());
+    }
+
+    #[test]
+    fn test_compiled_prefix_queries_and_size() {
+        let mut trie = RadixTrie::<usize>::new();
+        for word in &["Won", "Wonder", "Wonderful", "World", "多倫多"] {
+            trie.insert(word, word.len());
+        }
+        let queries = [
+            "Wonderfully",
+            "Wonders",
+            "Won",
+            "Wo",
+            "多倫多大學",
+            "",
+            "Axes",
+        ];
+        let expected = queries
+            .iter()
+            .map(|query| {
+                trie.longest_prefix(query)
+                    .map(|(label, value)| (label, *value))
+            })
+            .collect::<Vec<_>>();
+        let heap_bytes = trie.memory_usage().heap_bytes;
+        let compiled = trie.compile();
+        let res = queries
+            .iter()
+            .map(|query| {
+                compiled
+                    .longest_prefix(query)
+                    .map(|(label, value)| (label, *value))
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(res, expected);
+        assert!(compiled.contains_key("Wonder") && !compiled.contains_key("Wond"));
+        assert!(compiled.heap_bytes() < heap_bytes);
+    }
+
+    #[test]
+    fn test_compiled_layout_matches_trie() {
+        let mut trie = RadixTrie::<usize>::new();
+        let mut rng = Lcg(3);
+        for index in 0..3000 {
+            trie.insert(format!("/{}/{:x}", rng.label(), index * 7919), index);
+        }
+        let copy = trie.clone();
+        let stats = trie.memory_usage();
+        let compiled = trie.compile();
+        // the shape and the value flags take less than a byte per node
+        let structure = compiled.louds.heap_bytes() + compiled.has_value.heap_bytes();
+        assert!(structure < copy.node_count());
+        assert!(compiled.heap_bytes() * 4 < stats.heap_bytes);
+        for (label, value) in copy.iter() {
+            assert_eq!(compiled.find(&label), Some(value));
+            assert_eq!(
+                compiled.find(&label[..label.len() - 1]),
+                copy.find(&label[..label.len() - 1])
+            );
+        }
+        for prefix in &["/a", "/b多", "/c/", "/", "/x"] {
+            assert_eq!(compiled.start_with(prefix), copy.start_with(prefix));
+        }
+    }
+
+    #[test]
+    fn test_compile_empty() {
+        let compiled = RadixTrie::<()>::new().compile();
+        assert!(compiled.is_empty());
+        assert_eq!(compiled.find("a"), None);
+        assert!(compiled.start_with("a").is_empty());
+    }
+}
@@ -0,0 +1,137 @@
+use crate::trie::RadixTrie;
+use alloc::collections::BinaryHeap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cmp::{Ordering, Reverse};
+
+/// A completion kept while ranking.
+/// Higher scores are greater, and on equal scores the smaller label is greater
+struct Candidate<'a, S, T> {
+    score: S,
+    label: String,
+    value: &'a T,
+}
+
+impl<'a, S: Ord, T> Candidate<'a, S, T> {
+    fn rank(&self, score: &S, label: &str) -> Ordering {
+        self.score
+            .cmp(score)
+            .then_with(|| label.cmp(self.label.as_str()))
+    }
+}
+
+impl<'a, S: Ord, T> Ord for Candidate<'a, S, T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.rank(&other.score, &other.label)
+    }
+}
+
+impl<'a, S: Ord, T> PartialOrd for Candidate<'a, S, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<'a, S: Ord, T> PartialEq for Candidate<'a, S, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<'a, S: Ord, T> Eq for Candidate<'a, S, T> {}
+
+impl<T> RadixTrie<T> {
+    /// Returns the k labels starting with given prefix that have the highest scores,
+    /// with their values, from the highest score down. Equal scores are in lexicographic order.
+    /// The scores are computed by the closure on every value below the prefix,
+    /// and only the k best labels are kept while walking. The empty prefix ranks every label.
+    /// No score is cached on the elements, so ranking n labels costs O(n log k) on every call.
+    /// A [`RankedTrie`](crate::RankedTrie) caches the best score below every element instead,
+    /// and skips the subtrees that cannot make the k best
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("label", 5);
+    /// trie.insert("lab", 30);
+    /// trie.insert("lazy", 12);
+    /// trie.insert("tab", 90);
+    /// let res = trie.complete_top_k("la", 2, |hits| *hits);
+    /// assert_eq!(res, vec![(String::from("lab"), &30), (String::from("lazy"), &12)]);
+    /// ```
+    pub fn complete_top_k<S, F>(&self, prefix: &str, k: usize, mut score: F) -> Vec<(String, &T)>
+    where
+        S: Ord,
+        F: FnMut(&T) -> S,
+    {
+        let mut label = String::new();
+        let element = self.find_prefix_element(prefix, |parent| label.push_str(parent));
+        let element = match element {
+            Some(element) if k > 0 => element,
+            _ => return vec![],
+        };
+        // the worst kept candidate is on top
+        let mut best = BinaryHeap::with_capacity(k.min(element.value_count()) + 1);
+        element.for_each_value(&mut label, |label, value| {
+            let score = score(value);
+            if best.len() == k {
+                let Reverse(worst): &Reverse<Candidate<S, T>> = best.peek().unwrap();
+                if worst.rank(&score, label) != Ordering::Less {
+                    return;
+                }
+                best.pop();
+            }
+            best.push(Reverse(Candidate {
+                score,
+                label: label.into(),
+                value,
+            }));
+        });
+        best.into_sorted_vec()
+            .into_iter()
+            .map(|Reverse(candidate)| (candidate.label, candidate.value))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod complete_tests {
+    use crate::trie::RadixTrie;
+
+    #[test]
+    fn test_complete_top_k() {
+        let mut trie = RadixTrie::<usize>::new();
+        let words = [
+            ("Won", 7),
+            ("Wonder", 3),
+            ("Wonderful", 9),
+            ("World", 3),
+            ("Wo", 1),
+            ("Axes", 20),
+            ("多倫多", 4),
+        ];
+        for (word, hits) in &words {
+            trie.insert(word, *hits);
+        }
+        for k in 0..=words.len() + 1 {
+            let mut expected = trie.find_prefix_iter("Wo").collect::<Vec<_>>();
+            expected.sort_by(|(label1, hits1), (label2, hits2)| {
+                hits2.cmp(hits1).then_with(|| label1.cmp(label2))
+            });
+            expected.truncate(k);
+            assert_eq!(trie.complete_top_k("Wo", k, |hits| *hits), expected);
+        }
+        let res = trie.complete_top_k("Wonder", 1, |hits| *hits);
+        assert_eq!(res, vec![(String::from("Wonderful"), &9)]);
+        let res = trie.complete_top_k("", 2, |hits| core::cmp::Reverse(*hits));
+        assert_eq!(
+            res,
+            vec![(String::from("Wo"), &1), (String::from("Wonder"), &3)]
+        );
+        assert!(trie.complete_top_k("x", 3, |hits| *hits).is_empty());
+        // the heap is sized by the labels below the prefix, not by k
+        let res = trie.complete_top_k("Won", usize::MAX, |hits| *hits);
+        assert_eq!(res.len(), 3);
+    }
+}
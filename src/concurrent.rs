@@ -0,0 +1,205 @@
+use crate::build;
+use crate::trie::RadixTrie;
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// Number of shards, one for every possible first byte of a label
+const SHARDS: usize = 256;
+
+/// A trie shared between threads, split into shards by the first byte of the labels.
+///
+/// Every shard is a [`RadixTrie`] behind its own `RwLock`, so readers never wait for each other
+/// and writers only wait for the threads using labels with the same first byte.
+/// All labels starting with a non-empty prefix live in one shard,
+/// so prefix scans lock a single shard as well.
+/// Values are handed out as clones or through a closure, since no borrow can outlive the lock.
+///
+/// # Example
+/// ```rust
+/// use another_radix_trie::ConcurrentRadixTrie;
+/// use std::sync::Arc;
+/// use std::thread;
+///
+/// let trie = Arc::new(ConcurrentRadixTrie::new());
+/// let handles = (0..4)
+///     .map(|index| {
+///         let trie = Arc::clone(&trie);
+///         thread::spawn(move || trie.insert(format!("{}/route", index), index))
+///     })
+///     .collect::<Vec<_>>();
+/// for handle in handles {
+///     handle.join().unwrap();
+/// }
+/// assert_eq!(trie.len(), 4);
+/// assert_eq!(trie.get("2/route"), Some(2));
+/// ```
+#[derive(Debug)]
+pub struct ConcurrentRadixTrie<T> {
+    shards: Vec<RwLock<RadixTrie<T>>>,
+}
+
+impl<T> Default for ConcurrentRadixTrie<T> {
+    fn default() -> Self {
+        ConcurrentRadixTrie::new()
+    }
+}
+
+impl<T> ConcurrentRadixTrie<T> {
+    /// Construct an empty trie
+    pub fn new() -> Self {
+        ConcurrentRadixTrie {
+            shards: (0..SHARDS).map(|_| RwLock::new(RadixTrie::new())).collect(),
+        }
+    }
+
+    /// Returns the number of values stored.
+    /// The shards are counted one after another, so concurrent writes may or may not be included
+    pub fn len(&self) -> usize {
+        self.shards.iter().map(|shard| read(shard).len()).sum()
+    }
+
+    /// Returns true if no value is stored
+    pub fn is_empty(&self) -> bool {
+        self.shards.iter().all(|shard| read(shard).is_empty())
+    }
+
+    /// Insert label and associated value into the trie, returning the replaced value.
+    /// Inserting the empty label does nothing and returns None
+    pub fn insert<K: AsRef<str>>(&self, label: K, value: T) -> Option<T> {
+        let label = label.as_ref();
+        let shard = self.shard(label)?;
+        write(shard).insert(label, value)
+    }
+
+    /// Returns a clone of the value associated with related label.
+    /// If the label does not exist, return None
+    pub fn get<K: AsRef<str>>(&self, label: K) -> Option<T>
+    where
+        T: Clone,
+    {
+        self.find_with(label, T::clone)
+    }
+
+    /// Calls the closure with the borrowed value associated with related label
+    /// while the shard is locked for reading, and returns its result.
+    /// If the label does not exist, return None
+    pub fn find_with<K, F, R>(&self, label: K, f: F) -> Option<R>
+    where
+        K: AsRef<str>,
+        F: FnOnce(&T) -> R,
+    {
+        let label = label.as_ref();
+        read(self.shard(label)?).find(label).map(f)
+    }
+
+    /// Returns true if the label exists in the trie
+    pub fn contains_key<K: AsRef<str>>(&self, label: K) -> bool {
+        self.find_with(label, |_| ()).is_some()
+    }
+
+    /// Removes the value of the label and returns it
+    pub fn remove<K: AsRef<str>>(&self, label: K) -> Option<T> {
+        let label = label.as_ref();
+        write(self.shard(label)?).remove(label)
+    }
+
+    /// Returns clones of all values with their labels where the labels start with given prefix,
+    /// in lexicographic order.
+    /// A non-empty prefix locks a single shard for the whole scan, while the empty prefix
+    /// visits the shards one after another
+    pub fn start_with(&self, prefix: &str) -> Vec<(String, T)>
+    where
+        T: Clone,
+    {
+        let clone = |(label, value): (String, &T)| (label, value.clone());
+        match self.shard(prefix) {
+            Some(shard) => read(shard).find_prefix_iter(prefix).map(clone).collect(),
+            None => self
+                .shards
+                .iter()
+                .flat_map(|shard| read(shard).iter().map(clone).collect::<Vec<_>>())
+                .collect(),
+        }
+    }
+
+    /// Consumes the shards and joins them into a single trie
+    pub fn into_trie(self) -> RadixTrie<T> {
+        // the shards are in the order of their first byte, so the labels come out sorted
+        build::from_sorted(self.shards.into_iter().flat_map(|shard| {
+            shard
+                .into_inner()
+                .unwrap_or_else(|error| error.into_inner())
+        }))
+    }
+
+    /// The shard holding the label, or None for the empty label
+    fn shard(&self, label: &str) -> Option<&RwLock<RadixTrie<T>>> {
+        let first = *label.as_bytes().first()?;
+        Some(&self.shards[first as usize])
+    }
+}
+
+impl<T> From<RadixTrie<T>> for ConcurrentRadixTrie<T> {
+    fn from(trie: RadixTrie<T>) -> Self {
+        let concurrent = ConcurrentRadixTrie::new();
+        for (label, value) in trie {
+            concurrent.insert(label, value);
+        }
+        concurrent
+    }
+}
+
+/// Lock the shard for reading. Writers run no user code while holding the lock,
+/// so a poisoned shard still holds a consistent trie and is used as is
+fn read<T>(shard: &RwLock<RadixTrie<T>>) -> RwLockReadGuard<'_, RadixTrie<T>> {
+    shard.read().unwrap_or_else(|error| error.into_inner())
+}
+
+/// Lock the shard for writing, recovering from poisoning like [`read`]
+fn write<T>(shard: &RwLock<RadixTrie<T>>) -> RwLockWriteGuard<'_, RadixTrie<T>> {
+    shard.write().unwrap_or_else(|error| error.into_inner())
+}
+
+#[cfg(test)]
+mod concurrent_tests {
+    use crate::concurrent::ConcurrentRadixTrie;
+    use crate::trie::RadixTrie;
+    use std::thread;
+
+    #[test]
+    fn test_concurrent_readers_and_writers() {
+        let trie = ConcurrentRadixTrie::new();
+        thread::scope(|scope| {
+            for thread in 0..4 {
+                let trie = &trie;
+                scope.spawn(move || {
+                    for index in 0..250 {
+                        let label =
+                            format!("{}{:x}", ["/api/", "/static/", "多", "a"][thread], index);
+                        assert_eq!(trie.insert(&label, index), None);
+                        assert_eq!(trie.get(&label), Some(index));
+                        if index % 5 == 0 {
+                            assert_eq!(trie.remove(&label), Some(index));
+                        }
+                    }
+                });
+            }
+        });
+        assert_eq!(trie.len(), 800);
+        assert!(trie.insert("", 0).is_none() && !trie.contains_key(""));
+        assert_eq!(trie.find_with("/api/1", |value| value * 2), Some(2));
+        assert_eq!(trie.start_with("/static/f").len(), 8);
+        let all = trie.start_with("");
+        assert_eq!(all.len(), 800);
+        let trie = trie.into_trie();
+        assert_eq!(trie.validate(), Ok(()));
+        assert_eq!(
+            trie.iter()
+                .map(|(label, value)| (label, *value))
+                .collect::<Vec<_>>(),
+            all
+        );
+        let concurrent = ConcurrentRadixTrie::from(trie);
+        assert_eq!(concurrent.len(), 800);
+        assert!(ConcurrentRadixTrie::<()>::new().into_trie() == RadixTrie::new());
+    }
+}
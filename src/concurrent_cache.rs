@@ -0,0 +1,232 @@
+use crate::concurrent::ConcurrentRadixTrie;
+use crate::trie::RadixTrie;
+use std::collections::HashMap;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+
+/// The labels being loaded, each with the flight the other threads wait on
+type InFlight<T> = Mutex<HashMap<String, Arc<Flight<T>>>>;
+
+/// A [`ReadThroughTrie`](crate::ReadThroughTrie) shared between threads.
+///
+/// The values are cached in a [`ConcurrentRadixTrie`]. Concurrent misses on the same label
+/// are loaded once: the first thread calls the loader while the others wait for its result,
+/// so a burst of requests for a cold label does not reach the backing store more than once.
+/// Misses on different labels load in parallel.
+///
+/// # Example
+/// ```rust
+/// use another_radix_trie::ConcurrentReadThroughTrie;
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use std::thread;
+///
+/// let loads = AtomicUsize::new(0);
+/// let cache = ConcurrentReadThroughTrie::new(|label: &str| {
+///     loads.fetch_add(1, Ordering::Relaxed);
+///     Some(label.len())
+/// });
+/// thread::scope(|scope| {
+///     for _ in 0..4 {
+///         scope.spawn(|| assert_eq!(cache.get("label"), Some(5)));
+///     }
+/// });
+/// assert_eq!(loads.load(Ordering::Relaxed), 1);
+/// ```
+pub struct ConcurrentReadThroughTrie<T, F> {
+    trie: ConcurrentRadixTrie<T>,
+    loader: F,
+    in_flight: InFlight<T>,
+}
+
+/// A load in progress, completed with the result of the loader
+struct Flight<T> {
+    /// None until the load is done, then the loaded value if any
+    result: Mutex<Option<Option<T>>>,
+    done: Condvar,
+}
+
+impl<T: Clone> Flight<T> {
+    /// Blocks until the load is done and returns a clone of its result
+    fn wait(&self) -> Option<T> {
+        let mut result = lock(&self.result);
+        loop {
+            if let Some(value) = &*result {
+                return value.clone();
+            }
+            result = self
+                .done
+                .wait(result)
+                .unwrap_or_else(|error| error.into_inner());
+        }
+    }
+}
+
+/// Ends the flight of the loading thread when dropped, even if the loader panics,
+/// so the waiting threads are released and the next miss loads again
+struct Landing<'a, T> {
+    in_flight: &'a InFlight<T>,
+    label: &'a str,
+    flight: Arc<Flight<T>>,
+    result: Option<T>,
+}
+
+impl<'a, T> Drop for Landing<'a, T> {
+    fn drop(&mut self) {
+        lock(self.in_flight).remove(self.label);
+        *lock(&self.flight.result) = Some(self.result.take());
+        self.flight.done.notify_all();
+    }
+}
+
+impl<T, F> ConcurrentReadThroughTrie<T, F>
+where
+    T: Clone,
+    F: Fn(&str) -> Option<T>,
+{
+    /// Construct an empty cache with the given loader
+    pub fn new(loader: F) -> Self {
+        Self::with_trie(ConcurrentRadixTrie::new(), loader)
+    }
+
+    /// Construct a cache preloaded with the values of the trie
+    pub fn with_trie(trie: ConcurrentRadixTrie<T>, loader: F) -> Self {
+        ConcurrentReadThroughTrie {
+            trie,
+            loader,
+            in_flight: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns a clone of the cached value of the label, loading and caching it on a miss.
+    /// A thread missing a label already being loaded waits for that load instead of starting another.
+    /// If the loader has no value for the label, return None
+    pub fn get(&self, label: &str) -> Option<T> {
+        if label.is_empty() {
+            return None;
+        }
+        if let Some(value) = self.trie.get(label) {
+            return Some(value);
+        }
+        let flight = {
+            let mut in_flight = lock(&self.in_flight);
+            // a load may have landed since the first lookup
+            if let Some(value) = self.trie.get(label) {
+                return Some(value);
+            }
+            if let Some(flight) = in_flight.get(label) {
+                let flight = Arc::clone(flight);
+                drop(in_flight);
+                return flight.wait();
+            }
+            let flight = Arc::new(Flight {
+                result: Mutex::new(None),
+                done: Condvar::new(),
+            });
+            in_flight.insert(label.to_owned(), Arc::clone(&flight));
+            flight
+        };
+        let mut landing = Landing {
+            in_flight: &self.in_flight,
+            label,
+            flight,
+            result: None,
+        };
+        landing.result = (self.loader)(label);
+        if let Some(value) = &landing.result {
+            // cached before the flight ends, so the later misses find it
+            self.trie.insert(label, value.clone());
+        }
+        landing.result.clone()
+    }
+
+    /// Returns a clone of the cached value of the label without invoking the loader
+    pub fn get_cached(&self, label: &str) -> Option<T> {
+        self.trie.get(label)
+    }
+
+    /// Returns clones of all cached values with their labels where the labels start with given prefix
+    pub fn start_with(&self, prefix: &str) -> Vec<(String, T)> {
+        self.trie.start_with(prefix)
+    }
+
+    /// Removes the cached value, so the next lookup goes through the loader again
+    pub fn invalidate(&self, label: &str) -> Option<T> {
+        self.trie.remove(label)
+    }
+
+    /// Returns the trie holding the cached values
+    pub fn into_trie(self) -> RadixTrie<T> {
+        self.trie.into_trie()
+    }
+}
+
+/// Lock the mutex, recovering from poisoning like the shards of [`ConcurrentRadixTrie`].
+/// Every change made under the locks is complete before a value is cloned,
+/// so a panicking clone leaves them consistent
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|error| error.into_inner())
+}
+
+#[cfg(test)]
+mod concurrent_cache_tests {
+    use crate::concurrent_cache::ConcurrentReadThroughTrie;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::Barrier;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn test_single_flight() {
+        let loads = AtomicUsize::new(0);
+        let cache = ConcurrentReadThroughTrie::new(|label: &str| {
+            loads.fetch_add(1, Ordering::SeqCst);
+            // slow enough for the other threads to miss while loading
+            thread::sleep(Duration::from_millis(20));
+            if label.starts_with('W') {
+                Some(label.len())
+            } else {
+                None
+            }
+        });
+        let barrier = Barrier::new(8);
+        thread::scope(|scope| {
+            for thread in 0..8 {
+                let (cache, barrier) = (&cache, &barrier);
+                scope.spawn(move || {
+                    barrier.wait();
+                    let label = ["Won", "Wonder"][thread % 2];
+                    assert_eq!(cache.get(label), Some(label.len()));
+                });
+            }
+        });
+        assert_eq!(loads.load(Ordering::SeqCst), 2);
+        assert_eq!(cache.get_cached("Wonder"), Some(6));
+        // labels without value are not cached
+        assert_eq!(cache.get("Axes"), None);
+        assert_eq!(cache.get("Axes"), None);
+        assert_eq!(loads.load(Ordering::SeqCst), 4);
+        assert_eq!(cache.invalidate("Won"), Some(3));
+        assert_eq!(cache.get("Won"), Some(3));
+        assert_eq!(cache.get(""), None);
+        assert_eq!(loads.load(Ordering::SeqCst), 5);
+        assert_eq!(cache.start_with("Won").len(), 2);
+        let trie = cache.into_trie();
+        assert_eq!(trie.find("Wonder"), Some(&6));
+    }
+
+    #[test]
+    fn test_panicking_loader_releases_flight() {
+        let panicked = AtomicBool::new(false);
+        let cache = ConcurrentReadThroughTrie::new(|label: &str| {
+            if !panicked.swap(true, Ordering::SeqCst) {
+                panic!("backing store unavailable");
+            }
+            Some(label.len())
+        });
+        thread::scope(|scope| {
+            let failed = scope.spawn(|| cache.get("Won"));
+            assert!(failed.join().is_err());
+        });
+        assert_eq!(cache.get_cached("Won"), None);
+        assert_eq!(cache.get("Won"), Some(3));
+    }
+}
@@ -0,0 +1,188 @@
+use crate::element::Element;
+use crate::iter::{Iter, Traversal};
+use crate::trie::RadixTrie;
+use crate::util;
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+
+/// Where a [`Cursor`] stands after the chars pushed so far
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CursorStatus {
+    /// The chars are a prefix of some labels but not a label themselves
+    Prefix,
+    /// The chars form a label holding a value, which may also be a prefix of longer labels
+    Key,
+    /// No label starts with the chars
+    Dead,
+}
+
+/// A position in a trie, moved forward one char at a time.
+///
+/// Every push only looks at the current element,
+/// so following a user typing does not search from the root on each keystroke.
+///
+/// Created by [`RadixTrie::cursor`]
+pub struct Cursor<'a, T> {
+    /// The element whose label contains the position, or None once dead
+    element: Option<&'a Element<T>>,
+    /// Bytes of the element label matched so far
+    offset: usize,
+    /// All chars pushed so far
+    label: String,
+}
+
+impl<'a, T> Cursor<'a, T> {
+    /// Move the cursor forward by the char and return the new status.
+    /// A dead cursor stays dead
+    pub fn push(&mut self, char: char) -> CursorStatus {
+        self.label.push(char);
+        if let Some(element) = self.element {
+            let rest = &element.label()[self.offset..];
+            if rest.is_empty() {
+                let children = element.children();
+                let index = util::binary_search(char, children);
+                self.element = children
+                    .get(index)
+                    .filter(|child| util::first_char(child.label()) == char);
+                self.offset = char.len_utf8();
+            } else if rest.starts_with(char) {
+                self.offset += char.len_utf8();
+            } else {
+                self.element = None;
+            }
+        }
+        self.status()
+    }
+
+    /// Move the cursor forward by all chars of the string and return the new status
+    pub fn push_str(&mut self, chars: &str) -> CursorStatus {
+        chars.chars().for_each(|char| {
+            self.push(char);
+        });
+        self.status()
+    }
+
+    /// Returns the status of the current position
+    pub fn status(&self) -> CursorStatus {
+        match self.value() {
+            Some(_) => CursorStatus::Key,
+            None if self
+                .element
+                .is_some_and(|element| element.value_count() > 0) =>
+            {
+                CursorStatus::Prefix
+            }
+            None => CursorStatus::Dead,
+        }
+    }
+
+    /// Returns the value of the label formed by the pushed chars
+    pub fn value(&self) -> Option<&'a T> {
+        let element = self.element?;
+        if self.offset == element.label().len() {
+            element.value()
+        } else {
+            None
+        }
+    }
+
+    /// Returns the chars pushed so far
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// Returns the bytes of the chars pushed so far
+    pub fn key_bytes(&self) -> &[u8] {
+        self.label.as_bytes()
+    }
+
+    /// Returns a lazy iterator over the labels and values starting with the pushed chars,
+    /// in lexicographic order
+    pub fn completions(&self) -> Iter<'a, T> {
+        let traversal = match self.element {
+            Some(element) => {
+                let ancestors_len = self.label.len() - self.offset;
+                Traversal::new(element, self.label[..ancestors_len].to_owned(), 0)
+            }
+            None => Traversal::empty(),
+        };
+        Iter { traversal }
+    }
+}
+
+impl<T> RadixTrie<T> {
+    /// Returns a cursor at the root, before any char
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::{CursorStatus, RadixTrie};
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("help", 0);
+    /// trie.insert("hello", 1);
+    /// let mut cursor = trie.cursor();
+    /// assert_eq!(cursor.push_str("hel"), CursorStatus::Prefix);
+    /// assert_eq!(cursor.completions().count(), 2);
+    /// assert_eq!(cursor.push('p'), CursorStatus::Key);
+    /// assert_eq!(cursor.value(), Some(&0));
+    /// assert_eq!(cursor.push('s'), CursorStatus::Dead);
+    /// ```
+    pub fn cursor(&self) -> Cursor<'_, T> {
+        Cursor {
+            element: Some(&self.entry),
+            offset: 0,
+            label: String::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod cursor_tests {
+    use crate::cursor::CursorStatus;
+    use crate::trie::RadixTrie;
+
+    #[test]
+    fn test_cursor_matches_lookups() {
+        let mut trie = RadixTrie::<usize>::new();
+        let words = [
+            "Won",
+            "Wonder",
+            "Wonderful",
+            "World",
+            "Axes",
+            "多倫多",
+            "多",
+        ];
+        for word in &words {
+            trie.insert(word, word.len());
+        }
+        let empty = RadixTrie::<usize>::new();
+        assert_eq!(empty.cursor().status(), CursorStatus::Dead);
+        assert_eq!(empty.cursor().completions().next(), None);
+        for query in &["Wonderfully", "Worlds", "多倫多", "Axe", "Wx", "B"] {
+            let mut cursor = trie.cursor();
+            // no char pushed yet, so every label starts with the position
+            assert_eq!(cursor.status(), CursorStatus::Prefix);
+            assert_eq!(cursor.completions().count(), words.len());
+            for (index, char) in query.char_indices() {
+                let status = cursor.push(char);
+                let label = &query[..index + char.len_utf8()];
+                assert_eq!(cursor.label(), label);
+                assert_eq!(cursor.key_bytes(), label.as_bytes());
+                let expected = if trie.find(label).is_some() {
+                    CursorStatus::Key
+                } else if trie.count_prefix(label) > 0 {
+                    CursorStatus::Prefix
+                } else {
+                    CursorStatus::Dead
+                };
+                assert_eq!(status, expected, "{}", label);
+                assert_eq!(cursor.value(), trie.find(label));
+                let completions = cursor.completions().collect::<Vec<_>>();
+                assert_eq!(
+                    completions,
+                    trie.find_prefix_iter(label).collect::<Vec<_>>()
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,341 @@
+use crate::element::Element;
+use crate::trie::RadixTrie;
+use crate::util;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+/// A difference between two tries, yielded by [`Diff`]
+#[derive(Debug, PartialEq, Eq)]
+pub enum DiffEntry<'a, T> {
+    /// The label only exists in the other trie
+    Added(String, &'a T),
+    /// The label only exists in this trie
+    Removed(String, &'a T),
+    /// The label exists in both tries with different values,
+    /// given as the value of this trie and the value of the other trie
+    Changed(String, &'a T, &'a T),
+}
+
+/// An element of a trie compared by the side by side walk of [`Diff`]
+pub(crate) trait DiffElement: Sized {
+    type Value;
+
+    fn label(&self) -> &str;
+
+    fn value(&self) -> Option<&Self::Value>;
+
+    fn children(&self) -> &[Self];
+
+    /// True if both are the same shared subtree, which holds no difference
+    fn same(&self, other: &Self) -> bool;
+}
+
+impl<T> DiffElement for Element<T> {
+    type Value = T;
+
+    fn label(&self) -> &str {
+        Element::label(self)
+    }
+
+    fn value(&self) -> Option<&T> {
+        Element::value(self)
+    }
+
+    fn children(&self) -> &[Self] {
+        Element::children(self)
+    }
+
+    fn same(&self, other: &Self) -> bool {
+        core::ptr::eq(self, other)
+    }
+}
+
+/// The trie an element only exists in
+#[derive(Clone, Copy)]
+enum Side {
+    Mine,
+    Theirs,
+}
+
+/// The work left on the walk, each with the length of the label of the ancestors.
+/// Elements are paired with the length of their label already matched by the ancestors
+enum Task<'a, E> {
+    /// Pair up the elements of both lists by the first char of their labels
+    Compare(&'a [E], usize, &'a [E], usize),
+    /// Compare the element of this trie with the element of the other trie
+    Pair(&'a E, usize, &'a E, usize),
+    /// Report every value of the element and its descendants, found in one trie only
+    Only(Side, &'a E, usize),
+}
+
+/// Iterator over the differences between two tries in lexicographic order of labels.
+///
+/// Created by [`RadixTrie::diff`]
+pub struct Diff<'a, T> {
+    walk: DiffWalk<'a, Element<T>>,
+}
+
+/// Walks the elements of two tries side by side, comparing the labels of one element at a time.
+/// Labels are only built for the differences, and shared subtrees are skipped
+pub(crate) struct DiffWalk<'a, E> {
+    stack: Vec<(usize, Task<'a, E>)>,
+    label: String,
+}
+
+impl<'a, E: DiffElement> DiffWalk<'a, E> {
+    pub(crate) fn new(mine: &'a E, theirs: &'a E) -> Self {
+        DiffWalk {
+            stack: vec![(0, Task::Pair(mine, 0, theirs, 0))],
+            label: String::new(),
+        }
+    }
+
+    /// Push the pairs of elements of both lists starting with the same char,
+    /// and the elements of one list only, so that they are popped in lexicographic order
+    fn push_pairs(&mut self, mine: (&'a [E], usize), theirs: (&'a [E], usize)) {
+        let label_len = self.label.len();
+        let start = self.stack.len();
+        let (mut mine_elements, mut theirs_elements) = (mine.0.iter(), theirs.0.iter());
+        let (mut next_mine, mut next_theirs) = (mine_elements.next(), theirs_elements.next());
+        let first_char = |element: &E, offset: usize| util::first_char(&element.label()[offset..]);
+        loop {
+            let order = match (next_mine, next_theirs) {
+                (Some(mine_element), Some(theirs_element)) => {
+                    first_char(mine_element, mine.1).cmp(&first_char(theirs_element, theirs.1))
+                }
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => break,
+            };
+            let task = match order {
+                Ordering::Less => Task::Only(Side::Mine, next_mine.unwrap(), mine.1),
+                Ordering::Greater => Task::Only(Side::Theirs, next_theirs.unwrap(), theirs.1),
+                Ordering::Equal => {
+                    Task::Pair(next_mine.unwrap(), mine.1, next_theirs.unwrap(), theirs.1)
+                }
+            };
+            if order != Ordering::Greater {
+                next_mine = mine_elements.next();
+            }
+            if order != Ordering::Less {
+                next_theirs = theirs_elements.next();
+            }
+            self.stack.push((label_len, task));
+        }
+        self.stack[start..].reverse();
+    }
+
+    fn report(&self, side: Side, value: &'a E::Value) -> DiffEntry<'a, E::Value> {
+        match side {
+            Side::Mine => DiffEntry::Removed(self.label.clone(), value),
+            Side::Theirs => DiffEntry::Added(self.label.clone(), value),
+        }
+    }
+}
+
+impl<'a, E> Iterator for DiffWalk<'a, E>
+where
+    E: DiffElement,
+    E::Value: PartialEq,
+{
+    type Item = DiffEntry<'a, E::Value>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((label_len, task)) = self.stack.pop() {
+            self.label.truncate(label_len);
+            let (mine, mine_offset, theirs, theirs_offset) = match task {
+                Task::Compare(mine, mine_offset, theirs, theirs_offset) => {
+                    self.push_pairs((mine, mine_offset), (theirs, theirs_offset));
+                    continue;
+                }
+                Task::Only(side, element, offset) => {
+                    self.label.push_str(&element.label()[offset..]);
+                    let label_len = self.label.len();
+                    let children = element.children().iter().rev();
+                    let children = children.map(|child| (label_len, Task::Only(side, child, 0)));
+                    self.stack.extend(children);
+                    match element.value() {
+                        Some(value) => return Some(self.report(side, value)),
+                        None => continue,
+                    }
+                }
+                Task::Pair(mine, mine_offset, theirs, theirs_offset) => {
+                    (mine, mine_offset, theirs, theirs_offset)
+                }
+            };
+            if mine_offset == theirs_offset && mine.same(theirs) {
+                continue;
+            }
+            let mine_label = &mine.label()[mine_offset..];
+            let theirs_label = &theirs.label()[theirs_offset..];
+            let shared_len = util::longest_shared_prefix(mine_label, theirs_label).len();
+            if shared_len < mine_label.len() && shared_len < theirs_label.len() {
+                // the labels part ways, so no label of one subtree is in the other
+                let mine_task = (label_len, Task::Only(Side::Mine, mine, mine_offset));
+                let theirs_task = (label_len, Task::Only(Side::Theirs, theirs, theirs_offset));
+                if mine_label < theirs_label {
+                    self.stack.extend([theirs_task, mine_task]);
+                } else {
+                    self.stack.extend([mine_task, theirs_task]);
+                }
+                continue;
+            }
+            self.label.push_str(&mine_label[..shared_len]);
+            let label_len = self.label.len();
+            // an element whose label ends here goes on with its children,
+            // and the element with the longer label goes on with the rest of it
+            let mine_ends = shared_len == mine_label.len();
+            let theirs_ends = shared_len == theirs_label.len();
+            let (mine_below, mine_offset) = if mine_ends {
+                (mine.children(), 0)
+            } else {
+                (core::slice::from_ref(mine), mine_offset + shared_len)
+            };
+            let (theirs_below, theirs_offset) = if theirs_ends {
+                (theirs.children(), 0)
+            } else {
+                (core::slice::from_ref(theirs), theirs_offset + shared_len)
+            };
+            let compare = Task::Compare(mine_below, mine_offset, theirs_below, theirs_offset);
+            self.stack.push((label_len, compare));
+            let mine_value = mine.value().filter(|_| mine_ends);
+            let theirs_value = theirs.value().filter(|_| theirs_ends);
+            match (mine_value, theirs_value) {
+                (Some(mine), Some(theirs)) if mine != theirs => {
+                    return Some(DiffEntry::Changed(self.label.clone(), mine, theirs))
+                }
+                (Some(mine), None) => return Some(self.report(Side::Mine, mine)),
+                (None, Some(theirs)) => return Some(self.report(Side::Theirs, theirs)),
+                _ => (),
+            }
+        }
+        None
+    }
+}
+
+impl<'a, T: PartialEq> Iterator for Diff<'a, T> {
+    type Item = DiffEntry<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.walk.next()
+    }
+}
+
+impl<T: PartialEq> RadixTrie<T> {
+    /// Returns a lazy iterator over the changes turning this trie into the other one,
+    /// in lexicographic order of labels.
+    /// Both tries are walked side by side once, comparing the labels of one element at a time,
+    /// so labels are only built for the changes
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::{DiffEntry, RadixTrie};
+    ///
+    /// let mut old = RadixTrie::<usize>::new();
+    /// old.insert("lab", 3);
+    /// old.insert("label", 5);
+    /// let mut new = old.clone();
+    /// new.insert("label", 6);
+    /// new.insert("lazy", 4);
+    /// new.remove("lab");
+    /// let changes = old.diff(&new).collect::<Vec<_>>();
+    /// assert_eq!(changes, vec![
+    ///     DiffEntry::Removed(String::from("lab"), &3),
+    ///     DiffEntry::Changed(String::from("label"), &5, &6),
+    ///     DiffEntry::Added(String::from("lazy"), &4),
+    /// ]);
+    /// ```
+    pub fn diff<'a>(&'a self, other: &'a RadixTrie<T>) -> Diff<'a, T> {
+        Diff {
+            walk: DiffWalk::new(&self.entry, &other.entry),
+        }
+    }
+}
+
+#[cfg(test)]
+mod diff_tests {
+    use crate::diff::DiffEntry;
+    use crate::test_util::Lcg;
+    use crate::trie::RadixTrie;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_diff_matches_maps() {
+        let mut old = RadixTrie::<usize>::new();
+        let mut new = RadixTrie::<usize>::new();
+        for word in &["Won", "Wonder", "Wonderful", "World", "Axes", "多倫多"] {
+            old.insert(word, word.len());
+        }
+        for word in &["Wo", "Wonder", "Wonderful", "Axes", "多倫多", "多"] {
+            new.insert(word, word.len());
+        }
+        *new.find_mut("Wonderful").unwrap() += 1;
+        let old_map = old.iter().collect::<BTreeMap<_, _>>();
+        let new_map = new.iter().collect::<BTreeMap<_, _>>();
+        let mut labels = old_map.keys().chain(new_map.keys()).collect::<Vec<_>>();
+        labels.sort();
+        labels.dedup();
+        let expected = labels
+            .into_iter()
+            .filter_map(|label| match (old_map.get(label), new_map.get(label)) {
+                (Some(mine), None) => Some(DiffEntry::Removed(label.clone(), *mine)),
+                (None, Some(theirs)) => Some(DiffEntry::Added(label.clone(), *theirs)),
+                (Some(mine), Some(theirs)) if mine != theirs => {
+                    Some(DiffEntry::Changed(label.clone(), *mine, *theirs))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(old.diff(&new).collect::<Vec<_>>(), expected);
+        assert_eq!(expected.len(), 5);
+        assert_eq!(old.diff(&old).next(), None);
+        assert_eq!(RadixTrie::new().diff(&old).count(), old.len());
+    }
+
+    #[test]
+    fn test_random_diff_matches_maps() {
+        let mut rng = Lcg(5);
+        for _ in 0..100 {
+            let mut tries = [RadixTrie::new(), RadixTrie::new()];
+            for _ in 0..rng.next(30) {
+                let key = rng.label();
+                let value = rng.next(2);
+                let sides = match rng.next(3) {
+                    0 => 0..1,
+                    1 => 1..2,
+                    _ => 0..2,
+                };
+                for trie in &mut tries[sides] {
+                    trie.insert(&key, value);
+                }
+            }
+            let old = tries[0].iter().collect::<BTreeMap<_, _>>();
+            let new = tries[1].iter().collect::<BTreeMap<_, _>>();
+            let mut expected = vec![];
+            for (label, value) in &old {
+                match new.get(label) {
+                    None => expected.push(DiffEntry::Removed(label.clone(), *value)),
+                    Some(theirs) if theirs != value => {
+                        expected.push(DiffEntry::Changed(label.clone(), *value, *theirs))
+                    }
+                    Some(_) => (),
+                }
+            }
+            for (label, value) in &new {
+                if !old.contains_key(label) {
+                    expected.push(DiffEntry::Added(label.clone(), *value));
+                }
+            }
+            expected.sort_by(|entry1, entry2| label_of(entry1).cmp(label_of(entry2)));
+            assert_eq!(tries[0].diff(&tries[1]).collect::<Vec<_>>(), expected);
+        }
+    }
+
+    fn label_of<'a, T>(entry: &'a DiffEntry<'_, T>) -> &'a str {
+        match entry {
+            DiffEntry::Added(label, _)
+            | DiffEntry::Removed(label, _)
+            | DiffEntry::Changed(label, _, _) => label,
+        }
+    }
+}
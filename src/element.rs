@@ -1,4 +1,7 @@
-use std::collections::VecDeque;
+use alloc::borrow::ToOwned;
+use alloc::collections::{TryReserveError, VecDeque};
+use alloc::string::String;
+use alloc::vec::Vec;
 
 #[derive(Debug)]
 pub enum Element<T> {
@@ -6,10 +9,14 @@ pub enum Element<T> {
         label: String,
         value: T,
         children: Vec<Element<T>>,
+        /// Number of values held by this element and its descendants
+        count: usize,
     },
     Node {
         label: String,
         children: Vec<Element<T>>,
+        /// Number of values held by the descendants
+        count: usize,
     },
     Base {
         label: String,
@@ -24,15 +31,58 @@ macro_rules! unpack {
                 label,
                 value,
                 children,
+                ..
             } => (label, Some(value), children),
-            Element::Node { label, children } => (label, None, children),
+            Element::Node {
+                label, children, ..
+            } => (label, None, children),
             Element::Base { label, children } => (label, None, children),
         }
     };
 }
 
+impl<T: Clone> Clone for Element<T> {
+    /// Clones the element and its descendants without recursion
+    fn clone(&self) -> Self {
+        // every frame holds a source element, the index of its next child to clone and the cloned children
+        let mut stack = vec![(self, 0, Vec::with_capacity(self.children().len()))];
+        loop {
+            let top = stack.last_mut().unwrap();
+            if let Some(child) = top.0.children().get(top.1) {
+                top.1 += 1;
+                stack.push((child, 0, Vec::with_capacity(child.children().len())));
+                continue;
+            }
+            let (source, _, children) = stack.pop().unwrap();
+            let label = source.label().to_owned();
+            let element = match source {
+                Element::Value { value, count, .. } => Element::Value {
+                    label,
+                    value: value.clone(),
+                    children,
+                    count: *count,
+                },
+                Element::Node { count, .. } => Element::Node {
+                    label,
+                    children,
+                    count: *count,
+                },
+                Element::Base { .. } => Element::Base { label, children },
+            };
+            match stack.last_mut() {
+                Some((_, _, cloned)) => cloned.push(element),
+                None => return element,
+            }
+        }
+    }
+}
+
 impl<T> Element<T> {
     pub fn remove_label_prefix(&mut self, prefix_len: usize) {
+        debug_assert!(
+            self.label().is_char_boundary(prefix_len),
+            "Label split inside a char"
+        );
         self.label_mut().replace_range(..prefix_len, "");
     }
 
@@ -40,10 +90,28 @@ impl<T> Element<T> {
         self.label_mut().insert_str(0, prefix.as_ref());
     }
 
+    /// Reserve room in the label for the given number of bytes,
+    /// such as a prefix added later by [`Element::add_label_prefix`]
+    pub fn try_reserve_label(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.label_mut().try_reserve(additional)
+    }
+
     pub fn label(&self) -> &str {
         unpack!(self).0
     }
 
+    /// Bytes allocated for the label
+    pub fn label_capacity(&self) -> usize {
+        unpack!(self).0.capacity()
+    }
+
+    /// Release the unused capacity of the label and the children vector of this element only
+    pub fn shrink_to_fit(&mut self) {
+        let (label, _, children) = unpack!(self);
+        label.shrink_to_fit();
+        children.shrink_to_fit();
+    }
+
     fn label_mut(&mut self) -> &mut String {
         unpack!(self).0
     }
@@ -58,23 +126,41 @@ impl<T> Element<T> {
 
     /// Converts the Element::Node to Element::Value
     pub fn node_to_value(node: &mut Element<T>, value: T) {
-        assert!(matches!(node, Element::Node {..}));
+        assert!(matches!(node, Element::Node { .. }));
+        let count = node.value_count() + 1;
         let children = node.take_children();
+        let label = core::mem::take(node.label_mut());
         *node = Element::Value {
-            label: node.label().to_string(),
+            label,
             value,
             children,
+            count,
         }
     }
 
-    fn take_children(&mut self) -> Vec<Element<T>> {
-        let children = self.children_mut();
-        let mut old = Vec::with_capacity(children.capacity());
-        while let Some(child) = children.pop() {
-            old.push(child);
+    /// Converts the Element::Value to Element::Node and returns the value.
+    /// Other elements are left untouched and None is returned
+    pub fn take_value(&mut self) -> Option<T> {
+        if !matches!(self, Element::Value { .. }) {
+            return None;
         }
-        old.reverse();
-        old
+        let count = self.value_count() - 1;
+        let placeholder = Element::Node {
+            label: String::new(),
+            children: vec![],
+            count: 0,
+        };
+        let (label, value, children) = core::mem::replace(self, placeholder).unpack();
+        *self = Element::Node {
+            label,
+            children,
+            count,
+        };
+        value
+    }
+
+    fn take_children(&mut self) -> Vec<Element<T>> {
+        core::mem::take(self.children_mut())
     }
 
     pub fn value(&self) -> Option<&T> {
@@ -85,13 +171,96 @@ impl<T> Element<T> {
         unpack!(self).1
     }
 
-    pub fn is_node(&self) -> bool {
+    /// Borrow the value and the children mutably at the same time
+    pub fn value_and_children_mut(&mut self) -> (Option<&mut T>, &mut Vec<Element<T>>) {
+        let (_, value, children) = unpack!(self);
+        (value, children)
+    }
+
+    /// Number of values held by this element and its descendants.
+    /// It is stored on every element below the root, so only the root sums its children
+    pub fn value_count(&self) -> usize {
         match self {
-            Element::Node {
-                label: _,
-                children: _,
-            } => true,
-            _ => false,
+            Element::Value { count, .. } | Element::Node { count, .. } => *count,
+            Element::Base { children, .. } => children.iter().map(Element::value_count).sum(),
+        }
+    }
+
+    /// Number of values held by this element and its descendants,
+    /// computed from the counts stored on the children
+    pub fn counted_values(&self) -> usize {
+        let own = self.value().is_some() as usize;
+        own + self
+            .children()
+            .iter()
+            .map(Element::value_count)
+            .sum::<usize>()
+    }
+
+    /// Recompute the stored count from the counts of the children
+    pub fn recount(&mut self) {
+        let counted = self.counted_values();
+        if let Some(count) = self.count_mut() {
+            *count = counted;
+        }
+    }
+
+    fn count_mut(&mut self) -> Option<&mut usize> {
+        match self {
+            Element::Value { count, .. } | Element::Node { count, .. } => Some(count),
+            Element::Base { .. } => None,
+        }
+    }
+
+    /// Apply the update to the stored counts of the elements on the path,
+    /// given as the indices of the children from the entry down,
+    /// and return the children of the last element on it
+    pub fn update_counts<'a, F>(
+        mut entry: &'a mut Vec<Element<T>>,
+        path: &[usize],
+        mut update: F,
+    ) -> &'a mut Vec<Element<T>>
+    where
+        F: FnMut(&mut usize),
+    {
+        for &index in path {
+            let element = &mut entry[index];
+            if let Some(count) = element.count_mut() {
+                update(count);
+            }
+            entry = element.children_mut();
+        }
+        entry
+    }
+
+    pub fn is_node(&self) -> bool {
+        matches!(self, Element::Node { .. })
+    }
+
+    /// Pack the label, value, and children into a value element, or a node without value
+    pub fn pack(label: String, value: Option<T>, children: Vec<Element<T>>) -> Self {
+        let count = children.iter().map(Element::value_count).sum::<usize>();
+        match value {
+            Some(value) => Element::Value {
+                label,
+                value,
+                children,
+                count: count + 1,
+            },
+            None => Element::Node {
+                label,
+                children,
+                count,
+            },
+        }
+    }
+
+    /// Drop the elements with all their descendants one by one,
+    /// so deep chains do not recurse through the nested children.
+    /// The vector is left empty with its allocation kept
+    pub fn drop_all(elements: &mut Vec<Element<T>>) {
+        while let Some(mut element) = elements.pop() {
+            elements.append(element.children_mut());
         }
     }
 
@@ -100,6 +269,24 @@ impl<T> Element<T> {
         unpack!(self)
     }
 
+    /// Visit this element and all its descendant values with their labels in lexicographic order.
+    /// The labels are built in `buf`, which should hold the labels of the ancestors
+    pub fn for_each_value<'a, F>(&'a self, buf: &mut String, mut visit: F)
+    where
+        F: FnMut(&str, &'a T),
+    {
+        let mut stack = vec![(buf.len(), self)];
+        while let Some((prefix_len, element)) = stack.pop() {
+            buf.truncate(prefix_len);
+            buf.push_str(element.label());
+            if let Some(value) = element.value() {
+                visit(buf, value);
+            }
+            let children = element.children().iter().rev();
+            stack.extend(children.map(|child| (buf.len(), child)));
+        }
+    }
+
     /// Collect all the descendant values with their labels
     pub fn collect_all_child_values(&self) -> Vec<(String, &T)> {
         // contains all the parent labels
@@ -111,7 +298,7 @@ impl<T> Element<T> {
         // for all children with value, pack the parent label with the child element
         let mut children = self
             .children()
-            .into_iter()
+            .iter()
             .map(|child| (labels.len() - 1, child))
             .collect::<VecDeque<_>>();
         while let Some((prefix_index, element)) = children.pop_front() {
@@ -123,7 +310,7 @@ impl<T> Element<T> {
                 res.push((labels[index].to_owned(), value));
             }
             // update the label storage
-            children.extend(element.children().into_iter().map(|child| (index, child)))
+            children.extend(element.children().iter().map(|child| (index, child)))
         }
         res
     }
@@ -135,39 +322,18 @@ mod element_tests {
 
     fn get_test_example() -> Element<()> {
         // vec![ "in", "industry", "industrial", "industrialization", "india", "indian", ];
+        let value = |label: &str, children| Element::pack(label.into(), Some(()), children);
+        let node = |label: &str, children| Element::pack(label.into(), None, children);
         Element::Base {
             label: "in".into(),
-            children: vec![Element::Node {
-                label: "d".into(),
-                children: vec![
-                    Element::Value {
-                        label: "ustry".into(),
-                        value: (),
-                        children: vec![],
-                    },
-                    Element::Node {
-                        label: "ustri".into(),
-                        children: vec![Element::Value {
-                            label: "al".into(),
-                            value: (),
-                            children: vec![Element::Value {
-                                label: "ization".into(),
-                                value: (),
-                                children: vec![],
-                            }],
-                        }],
-                    },
-                    Element::Value {
-                        label: "ia".into(),
-                        value: (),
-                        children: vec![Element::Value {
-                            label: "n".into(),
-                            value: (),
-                            children: vec![],
-                        }],
-                    },
+            children: vec![node(
+                "d",
+                vec![
+                    value("ustry", vec![]),
+                    node("ustri", vec![value("al", vec![value("ization", vec![])])]),
+                    value("ia", vec![value("n", vec![])]),
                 ],
-            }],
+            )],
         }
     }
 
@@ -191,4 +357,24 @@ mod element_tests {
         .collect::<Vec<_>>();
         assert_eq!(res, expected)
     }
+
+    #[test]
+    fn test_value_counts() {
+        let mut example = get_test_example();
+        assert_eq!(example.value_count(), 5);
+        let d = &mut example.children_mut()[0];
+        assert_eq!(d.value_count(), 5);
+        assert_eq!(d.children()[1].value_count(), 2);
+        // "india" loses its value, so "d" above it holds one value less
+        let ia = &mut d.children_mut()[2];
+        assert_eq!(ia.take_value(), Some(()));
+        assert_eq!(ia.value_count(), 1);
+        Element::update_counts(example.children_mut(), &[0], |count| *count -= 1);
+        let d = &mut example.children_mut()[0];
+        assert_eq!((d.value_count(), d.counted_values()), (4, 4));
+        Element::node_to_value(&mut d.children_mut()[2], ());
+        assert_eq!(d.children()[2].value_count(), 2);
+        d.recount();
+        assert_eq!(d.value_count(), 5);
+    }
 }
@@ -0,0 +1,616 @@
+use crate::element::Element;
+use crate::error::TrieError;
+use crate::trie::FindOutcome::*;
+use crate::trie::RadixTrie;
+use alloc::borrow::ToOwned;
+use alloc::collections::TryReserveError;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::Infallible;
+use core::fmt;
+
+/// A view into a single label of a trie, which is either occupied or vacant.
+///
+/// Created by [`RadixTrie::entry`]
+pub enum Entry<'a, T> {
+    /// The label holds a value
+    Occupied(OccupiedEntry<'a, T>),
+    /// The label does not hold a value
+    Vacant(VacantEntry<'a, T>),
+}
+
+/// A view into a label holding a value
+pub struct OccupiedEntry<'a, T> {
+    trie: &'a mut RadixTrie<T>,
+    /// The indices of the children from the root down to the element holding the value
+    path: Vec<usize>,
+    key: String,
+}
+
+/// A view into a label without value.
+/// The position found by the lookup is kept, so inserting does not search again
+pub struct VacantEntry<'a, T> {
+    trie: &'a mut RadixTrie<T>,
+    /// The indices of the children from the root down to the element the label goes below
+    path: Vec<usize>,
+    key: String,
+    /// The length of the part of the label matched by the ancestors
+    matched: usize,
+    position: Position,
+}
+
+/// The error returned by [`RadixTrie::try_insert`] when the label already holds a value.
+/// It gives back the refused value together with the entry of the existing one
+pub struct OccupiedError<'a, T> {
+    /// The entry of the label holding the existing value
+    pub entry: OccupiedEntry<'a, T>,
+    /// The value that was not inserted
+    pub value: T,
+}
+
+impl<'a, T: fmt::Debug> fmt::Debug for OccupiedError<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OccupiedError")
+            .field("old_value", self.entry.get())
+            .field("new_value", &self.value)
+            .finish()
+    }
+}
+
+impl<'a, T: fmt::Debug> fmt::Display for OccupiedError<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "failed to insert {:?}, label already holds {:?}",
+            self.value,
+            self.entry.get()
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, T: fmt::Debug> std::error::Error for OccupiedError<'a, T> {}
+
+/// The error returned by [`RadixTrie::try_insert`] when the value is not inserted.
+/// Both variants give back the refused value
+pub enum TryInsertError<'a, T> {
+    /// The label already holds a value
+    Occupied(OccupiedError<'a, T>),
+    /// The label cannot hold a value, such as the empty label
+    Invalid {
+        /// Why the label is refused
+        error: TrieError,
+        /// The value that was not inserted
+        value: T,
+    },
+}
+
+impl<'a, T> TryInsertError<'a, T> {
+    /// Consumes the error and returns the value that was not inserted
+    pub fn into_value(self) -> T {
+        match self {
+            TryInsertError::Occupied(error) => error.value,
+            TryInsertError::Invalid { value, .. } => value,
+        }
+    }
+}
+
+impl<'a, T: fmt::Debug> fmt::Debug for TryInsertError<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TryInsertError::Occupied(error) => f.debug_tuple("Occupied").field(error).finish(),
+            TryInsertError::Invalid { error, value } => f
+                .debug_struct("Invalid")
+                .field("error", error)
+                .field("value", value)
+                .finish(),
+        }
+    }
+}
+
+impl<'a, T: fmt::Debug> fmt::Display for TryInsertError<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TryInsertError::Occupied(error) => error.fmt(f),
+            TryInsertError::Invalid { error, value } => {
+                write!(f, "failed to insert {:?}, {}", value, error)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, T: fmt::Debug> std::error::Error for TryInsertError<'a, T> {}
+
+/// Where a label is found by [`RadixTrie::locate`]
+pub(crate) enum Location {
+    /// The label holds a value
+    Occupied,
+    /// The label does not hold a value. It goes at the position once
+    /// the given length of it is matched by the ancestors
+    Vacant(usize, Position),
+}
+
+/// Where a vacant label goes in its entry
+pub(crate) enum Position {
+    /// A new element at the index
+    At(usize),
+    /// The label is a prefix of the element at the index
+    PrefixOf(usize),
+    /// The label shares a prefix of the given length with the element at the index
+    Intersects(usize, usize),
+    /// The element at the index is a node with the same label
+    Node(usize),
+}
+
+/// The labels and children an insertion at a position allocates, made before the trie is changed
+pub(crate) struct Reservation<T> {
+    /// The label of the new value, without the shared prefix
+    label: String,
+    /// The label of the node joining an intersected element with the new value
+    shared_prefix: String,
+    /// The children of the new elements, with the room for the elements moved below them
+    children: Vec<Element<T>>,
+}
+
+impl<T> Reservation<T> {
+    /// Allocates what inserting the label at the position needs
+    pub(crate) fn new(label: &str, position: &Position) -> Self {
+        let (shared_len, label_len, children) = Reservation::<T>::sizes(label, position);
+        Reservation {
+            label: label[shared_len..label_len].to_owned(),
+            shared_prefix: label[..shared_len].to_owned(),
+            children: Vec::with_capacity(children),
+        }
+    }
+
+    /// Allocates what inserting the label at the position needs like [`Reservation::new`],
+    /// or returns the error of the first allocation failed
+    pub(crate) fn try_new(label: &str, position: &Position) -> Result<Self, TryReserveError> {
+        let (shared_len, label_len, children) = Reservation::<T>::sizes(label, position);
+        let mut reservation = Reservation {
+            label: String::new(),
+            shared_prefix: String::new(),
+            children: Vec::new(),
+        };
+        reservation
+            .label
+            .try_reserve_exact(label_len - shared_len)?;
+        reservation.shared_prefix.try_reserve_exact(shared_len)?;
+        reservation.children.try_reserve_exact(children)?;
+        reservation.label.push_str(&label[shared_len..label_len]);
+        reservation.shared_prefix.push_str(&label[..shared_len]);
+        Ok(reservation)
+    }
+
+    /// The length of the shared prefix, the end of the new label,
+    /// and the number of children the new elements hold
+    fn sizes(label: &str, position: &Position) -> (usize, usize, usize) {
+        match position {
+            Position::At(_) => (0, label.len(), 0),
+            Position::PrefixOf(_) => (0, label.len(), 1),
+            Position::Intersects(_, shared_len) => (*shared_len, label.len(), 2),
+            Position::Node(_) => (0, 0, 0),
+        }
+    }
+}
+
+impl<'a, T> Entry<'a, T> {
+    /// Ensures a value is in the entry by inserting the default if empty,
+    /// and returns a mutable reference to the value
+    pub fn or_insert(self, default: T) -> &'a mut T {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting the result of the function if empty,
+    /// and returns a mutable reference to the value
+    pub fn or_insert_with<F: FnOnce() -> T>(self, default: F) -> &'a mut T {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting the default value if empty,
+    /// and returns a mutable reference to the value
+    pub fn or_default(self) -> &'a mut T
+    where
+        T: Default,
+    {
+        self.or_insert_with(T::default)
+    }
+
+    /// Modifies the value of an occupied entry in place
+    pub fn and_modify<F: FnOnce(&mut T)>(self, modify: F) -> Self {
+        match self {
+            Entry::Occupied(mut entry) => {
+                modify(entry.get_mut());
+                Entry::Occupied(entry)
+            }
+            Entry::Vacant(entry) => Entry::Vacant(entry),
+        }
+    }
+}
+
+impl<'a, T> OccupiedEntry<'a, T> {
+    /// Returns the label of the entry
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Returns the borrowed value
+    pub fn get(&self) -> &T {
+        self.trie.element_at(&self.path).value().unwrap()
+    }
+
+    /// Returns the mutable borrowed value
+    pub fn get_mut(&mut self) -> &mut T {
+        self.trie.element_at_mut(&self.path).value_mut().unwrap()
+    }
+
+    /// Converts the entry into the mutable borrowed value, bound to the lifetime of the trie
+    pub fn into_mut(self) -> &'a mut T {
+        self.trie.element_at_mut(&self.path).value_mut().unwrap()
+    }
+
+    /// Replaces the value and returns the old one
+    pub fn insert(&mut self, value: T) -> T {
+        core::mem::replace(self.get_mut(), value)
+    }
+
+    /// Removes the value from the trie and returns it.
+    /// The elements left without value are compacted like [`RadixTrie::remove`] does,
+    /// without searching for the label again
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::{Entry, RadixTrie};
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("lab", 3);
+    /// trie.insert("label", 5);
+    /// if let Entry::Occupied(entry) = trie.entry("label") {
+    ///     assert_eq!(entry.key(), "label");
+    ///     assert_eq!(entry.remove(), 5);
+    /// }
+    /// assert_eq!(trie.find("label"), None);
+    /// assert_eq!(trie.node_count(), 1);
+    /// ```
+    pub fn remove(self) -> T {
+        self.trie.remove_at(&self.path)
+    }
+}
+
+impl<'a, T> VacantEntry<'a, T> {
+    /// Returns the label of the entry
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Inserts the value at the label and returns a mutable reference to it
+    pub fn insert(self, value: T) -> &'a mut T {
+        let VacantEntry {
+            trie,
+            path,
+            key,
+            matched,
+            position,
+        } = self;
+        trie.insert_at(&path, &key[matched..], position, value)
+    }
+}
+
+impl<T> RadixTrie<T> {
+    /// Returns the entry of the label for in-place manipulation, found in one traversal
+    ///
+    /// # Panics
+    /// Panics if the label is empty, since the empty label cannot hold a value
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// for word in "the cat and the hat".split(' ') {
+    ///     trie.entry(word).and_modify(|count| *count += 1).or_insert(1);
+    /// }
+    /// assert_eq!(trie.find("the"), Some(&2));
+    /// assert_eq!(trie.find("hat"), Some(&1));
+    /// ```
+    pub fn entry<K: AsRef<str>>(&mut self, label: K) -> Entry<'_, T> {
+        let label = label.as_ref();
+        if label.is_empty() {
+            panic!("{}", TrieError::EmptyLabel);
+        }
+        self.locate_entry(label)
+    }
+
+    /// Returns the entry of the label like [`RadixTrie::entry`],
+    /// or an error instead of panicking if the label is empty
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::{RadixTrie, TrieError};
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// assert_eq!(trie.try_entry("label").map(|entry| *entry.or_insert(5)), Ok(5));
+    /// assert_eq!(trie.try_entry("").err(), Some(TrieError::EmptyLabel));
+    /// ```
+    pub fn try_entry<K: AsRef<str>>(&mut self, label: K) -> Result<Entry<'_, T>, TrieError> {
+        let label = label.as_ref();
+        if label.is_empty() {
+            return Err(TrieError::EmptyLabel);
+        }
+        Ok(self.locate_entry(label))
+    }
+
+    /// Returns the entry of the non-empty label
+    pub(crate) fn locate_entry(&mut self, label: &str) -> Entry<'_, T> {
+        let (path, location) = self.locate(label);
+        let key = label.to_owned();
+        match location {
+            Location::Occupied => Entry::Occupied(OccupiedEntry {
+                trie: self,
+                path,
+                key,
+            }),
+            Location::Vacant(matched, position) => Entry::Vacant(VacantEntry {
+                trie: self,
+                path,
+                key,
+                matched,
+                position,
+            }),
+        }
+    }
+
+    /// Finds where the non-empty label goes in one traversal,
+    /// as the indices of the children from the root down to the element holding it,
+    /// or down to the element it goes below if it holds no value
+    pub(crate) fn locate(&self, label: &str) -> (Vec<usize>, Location) {
+        let mut path = vec![];
+        let location = self.descend(label, |index| {
+            path.push(index);
+            Ok::<_, Infallible>(())
+        });
+        match location {
+            Ok(location) => (path, location),
+            Err(never) => match never {},
+        }
+    }
+
+    /// Finds where the non-empty label goes like [`RadixTrie::locate`],
+    /// handing the indices of the path to the closure one by one.
+    /// The first error of the closure stops the search
+    pub(crate) fn descend<F, E>(&self, label: &str, mut push: F) -> Result<Location, E>
+    where
+        F: FnMut(usize) -> Result<(), E>,
+    {
+        let mut matched = 0;
+        let mut entry = self.entry.children();
+        loop {
+            let position = match Self::find_from_entry(entry, &label[matched..]) {
+                BeyondSizeLimit => Position::At(entry.len()),
+                NotMatch(index) => Position::At(index),
+                AsPrefixOf(index) => Position::PrefixOf(index),
+                Intersects(index, shared_prefix) => {
+                    Position::Intersects(index, shared_prefix.len())
+                }
+                ExactMatch(index) if entry[index].value().is_none() => Position::Node(index),
+                ExactMatch(index) => {
+                    push(index)?;
+                    return Ok(Location::Occupied);
+                }
+                PrefixMatch(index) => {
+                    push(index)?;
+                    matched += entry[index].label().len();
+                    entry = entry[index].children();
+                    continue;
+                }
+            };
+            return Ok(Location::Vacant(matched, position));
+        }
+    }
+
+    /// Inserts the value at the position found by [`RadixTrie::locate`] below the path,
+    /// where the label is the part not matched by the ancestors
+    pub(crate) fn insert_at(
+        &mut self,
+        path: &[usize],
+        label: &str,
+        position: Position,
+        value: T,
+    ) -> &mut T {
+        let reservation = Reservation::new(label, &position);
+        self.insert_reserved(path, position, reservation, value)
+    }
+
+    /// Inserts the value at the position like [`RadixTrie::insert_at`]
+    /// with the allocations made ahead, so nothing is allocated
+    /// once the entry has room for a new element
+    pub(crate) fn insert_reserved(
+        &mut self,
+        path: &[usize],
+        position: Position,
+        reservation: Reservation<T>,
+        value: T,
+    ) -> &mut T {
+        self.len += 1;
+        let entry = Element::update_counts(self.entry.children_mut(), path, |count| *count += 1);
+        let Reservation {
+            label,
+            shared_prefix,
+            children,
+        } = reservation;
+        match position {
+            Position::At(index) => {
+                entry.insert(index, Element::pack(label, Some(value), children));
+                entry[index].value_mut().unwrap()
+            }
+            Position::PrefixOf(index) => {
+                RadixTrie::insert_prefix_node(entry, index, label, children, value)
+            }
+            Position::Intersects(index, _) => RadixTrie::join_intersected_nodes(
+                entry,
+                index,
+                shared_prefix,
+                label,
+                children,
+                value,
+            ),
+            Position::Node(index) => {
+                let target = &mut entry[index];
+                Element::node_to_value(target, value);
+                target.value_mut().unwrap()
+            }
+        }
+    }
+
+    /// Inserts the value if the label holds no value yet, and returns a mutable reference to it.
+    /// An existing value is never replaced; the refused value is returned in the error instead,
+    /// and so is the value of the empty label, which cannot hold one
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::{RadixTrie, TrieError, TryInsertError};
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// assert_eq!(trie.try_insert("label", 5).ok(), Some(&mut 5));
+    /// match trie.try_insert("label", 6) {
+    ///     Err(TryInsertError::Occupied(error)) => assert_eq!((error.entry.get(), error.value), (&5, 6)),
+    ///     _ => unreachable!(),
+    /// }
+    /// match trie.try_insert("", 7) {
+    ///     Err(TryInsertError::Invalid { error, value }) => assert_eq!((error, value), (TrieError::EmptyLabel, 7)),
+    ///     _ => unreachable!(),
+    /// }
+    /// ```
+    pub fn try_insert<K: AsRef<str>>(
+        &mut self,
+        label: K,
+        value: T,
+    ) -> Result<&mut T, TryInsertError<'_, T>> {
+        match self.try_entry(label) {
+            Ok(Entry::Occupied(entry)) => {
+                Err(TryInsertError::Occupied(OccupiedError { entry, value }))
+            }
+            Ok(Entry::Vacant(entry)) => Ok(entry.insert(value)),
+            Err(error) => Err(TryInsertError::Invalid { error, value }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod entry_tests {
+    use crate::entry::{Entry, TryInsertError};
+    use crate::test_util::words_trie;
+    use crate::trie::RadixTrie;
+
+    #[test]
+    fn test_entry_all_positions() {
+        let mut trie = RadixTrie::<usize>::new();
+        let words = ["exe", "execute", "exec", "example", "ex", "exe", "zoo", "a"];
+        for word in &words {
+            *trie.entry(word).or_default() += 1;
+        }
+        for word in &["execute", "exec", "example", "ex", "zoo", "a"] {
+            assert_eq!(trie.find(word), Some(&1));
+        }
+        assert_eq!(trie.find("exe"), Some(&2));
+        assert_eq!(trie.iter().count(), 7);
+    }
+
+    #[test]
+    fn test_occupied_and_vacant() {
+        let mut trie = RadixTrie::<usize>::new();
+        trie.insert("label", 5);
+        match trie.entry("label") {
+            Entry::Occupied(mut entry) => {
+                assert_eq!(entry.get(), &5);
+                assert_eq!(entry.insert(6), 5);
+            }
+            Entry::Vacant(_) => panic!("label is occupied"),
+        }
+        match trie.entry("lab") {
+            Entry::Occupied(_) => panic!("lab is vacant"),
+            Entry::Vacant(entry) => *entry.insert(2) += 1,
+        }
+        assert_eq!(trie.find("lab"), Some(&3));
+        assert_eq!(trie.find("label"), Some(&6));
+        let value = trie
+            .entry("lazy")
+            .and_modify(|_| panic!())
+            .or_insert_with(|| 4);
+        assert_eq!(*value, 4);
+    }
+
+    #[test]
+    fn test_insert_and_try_insert() {
+        let mut trie = RadixTrie::<usize>::new();
+        assert_eq!(trie.insert("exec", 4), None);
+        assert_eq!(trie.insert("exe", 3), None);
+        assert_eq!(trie.insert("exec", 5), Some(4));
+        assert_eq!(trie.insert("", 0), None);
+        assert_eq!(trie.len(), 2);
+        // "ex" becomes a valueless node once "example" is inserted
+        *trie.try_insert("example", 7).unwrap() += 1;
+        assert_eq!(trie.try_insert("ex", 2).ok(), Some(&mut 2));
+        match trie.try_insert("example", 0) {
+            Err(TryInsertError::Occupied(mut error)) => {
+                assert_eq!(error.value, 0);
+                assert_eq!(
+                    error.to_string(),
+                    "failed to insert 0, label already holds 8"
+                );
+                *error.entry.get_mut() += 1;
+            }
+            _ => panic!("example is occupied"),
+        }
+        assert_eq!(trie.find("example"), Some(&9));
+        assert_eq!(trie.find("exec"), Some(&5));
+        // the empty label is refused instead of panicking
+        let error = trie.try_insert("", 1).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "failed to insert 1, empty label cannot hold a value"
+        );
+        assert_eq!(error.into_value(), 1);
+        assert_eq!(trie.len(), 4);
+    }
+
+    #[test]
+    fn test_entry_key_and_remove() {
+        let mut trie = words_trie();
+        match trie.entry("Wo") {
+            Entry::Vacant(entry) => assert_eq!(entry.key(), "Wo"),
+            Entry::Occupied(_) => panic!("Wo is a valueless node"),
+        }
+        // the entry state machine counts down and removes the label once it reaches zero
+        for expected in (0..6).rev() {
+            match trie.entry("Wonder") {
+                Entry::Occupied(mut entry) if *entry.get() > 1 => {
+                    assert_eq!(entry.key(), "Wonder");
+                    *entry.get_mut() -= 1;
+                }
+                Entry::Occupied(entry) => assert_eq!(entry.remove(), 1),
+                Entry::Vacant(_) => panic!("Wonder is removed too early"),
+            }
+            assert_eq!(trie.find("Wonder").copied().unwrap_or(0), expected);
+        }
+        // "Won" keeps one child, so "Wonderful" moves up into it
+        match trie.entry("Won") {
+            Entry::Occupied(entry) => assert_eq!(entry.remove(), 3),
+            Entry::Vacant(_) => panic!("Won is occupied"),
+        }
+        assert_eq!(trie.validate(), Ok(()));
+        assert_eq!(trie.len(), 3);
+        assert_eq!(trie.node_count(), 4);
+        assert_eq!(trie.find("Wonderful"), Some(&9));
+        let value = match trie.entry("Won") {
+            Entry::Vacant(entry) => entry.insert(1),
+            Entry::Occupied(_) => panic!("Won is removed"),
+        };
+        *value += 1;
+        assert_eq!(trie.find("Won"), Some(&2));
+        assert_eq!(trie.validate(), Ok(()));
+    }
+}
@@ -0,0 +1,117 @@
+use crate::trie::RadixTrie;
+use core::fmt;
+
+/// Errors of the fallible trie operations, such as [`RadixTrie::try_entry`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum TrieError {
+    /// The label is empty, and the empty label cannot hold a value
+    EmptyLabel,
+    /// The labels of a sorted import are out of order or repeated
+    UnsortedLabels,
+    /// The label is longer than the maximum length of a [`LimitedRadixTrie`](crate::LimitedRadixTrie),
+    /// both in bytes
+    KeyTooLong { len: usize, max_len: usize },
+    /// The label is refused by the validator of a [`LimitedRadixTrie`](crate::LimitedRadixTrie)
+    /// for the given reason
+    InvalidKey { reason: &'static str },
+    /// An allocation failed in a fallible bulk operation, such as [`RadixTrie::try_import_sorted_reserve`]
+    AllocationFailed,
+}
+
+impl fmt::Display for TrieError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrieError::EmptyLabel => write!(f, "empty label cannot hold a value"),
+            TrieError::UnsortedLabels => write!(f, "labels are not sorted and unique"),
+            TrieError::KeyTooLong { len, max_len } => write!(
+                f,
+                "label of {} bytes is longer than the maximum of {}",
+                len, max_len
+            ),
+            TrieError::InvalidKey { reason } => write!(f, "invalid label: {}", reason),
+            TrieError::AllocationFailed => write!(f, "failed to allocate memory"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TrieError {}
+
+impl<T> RadixTrie<T> {
+    /// Insert label and associated value into the trie like [`RadixTrie::insert`],
+    /// returning the replaced value.
+    /// Unlike `insert`, the empty label is reported as an error instead of being ignored.
+    /// A trie refusing more labels is a [`LimitedRadixTrie`](crate::LimitedRadixTrie)
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::{RadixTrie, TrieError};
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// assert_eq!(trie.try_insert_key("label", 5), Ok(None));
+    /// assert_eq!(trie.try_insert_key("label", 6), Ok(Some(5)));
+    /// assert_eq!(trie.try_insert_key("", 7), Err(TrieError::EmptyLabel));
+    /// ```
+    pub fn try_insert_key<K: AsRef<str>>(
+        &mut self,
+        label: K,
+        value: T,
+    ) -> Result<Option<T>, TrieError> {
+        let label = label.as_ref();
+        if label.is_empty() {
+            return Err(TrieError::EmptyLabel);
+        }
+        Ok(self.insert(label, value))
+    }
+
+    /// Returns the mutable value of the label, inserting the default value first if it is missing,
+    /// like [`RadixTrie::get_or_default`] but without panicking on the empty label
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::{RadixTrie, TrieError};
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// *trie.try_get_or_default("label").unwrap() += 1;
+    /// assert_eq!(trie.find("label"), Some(&1));
+    /// assert!(trie.try_get_or_default("").is_err());
+    /// ```
+    pub fn try_get_or_default<K: AsRef<str>>(&mut self, label: K) -> Result<&mut T, TrieError>
+    where
+        T: Default,
+    {
+        self.try_entry(label).map(|entry| entry.or_default())
+    }
+}
+
+#[cfg(test)]
+mod error_tests {
+    use crate::error::TrieError;
+    use crate::trie::RadixTrie;
+
+    #[test]
+    fn test_fallible_variants() {
+        let mut trie = RadixTrie::<usize>::new();
+        assert_eq!(trie.try_insert_key("", 1), Err(TrieError::EmptyLabel));
+        assert!(trie.try_entry("").is_err());
+        assert!(trie.try_get_or_default(String::new()).is_err());
+        assert!(trie.is_empty());
+        assert_eq!(trie.try_insert_key("Won", 1), Ok(None));
+        *trie.try_get_or_default("Wonder").unwrap() += 2;
+        assert_eq!(
+            trie.try_entry("Won").map(|entry| *entry.or_insert(0)),
+            Ok(1)
+        );
+        assert_eq!(trie.find("Wonder"), Some(&2));
+        assert_eq!(trie.len(), 2);
+        assert_eq!(
+            TrieError::EmptyLabel.to_string(),
+            "empty label cannot hold a value"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "empty label cannot hold a value")]
+    fn test_entry_panics_on_empty_label() {
+        RadixTrie::<usize>::new().entry("");
+    }
+}
@@ -0,0 +1,162 @@
+use crate::element::Element;
+use crate::iter::Traversal;
+use crate::trie::FindOutcome::*;
+use crate::trie::RadixTrie;
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// The maximum number of nearest labels reported by [`RadixTrie::explain`]
+const NEAREST_LIMIT: usize = 5;
+
+/// Trace of a lookup, returned by [`RadixTrie::explain`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation {
+    /// Labels of the elements compared against the key, from the root down
+    pub visited: Vec<String>,
+    /// Number of bytes of the key matched before the lookup stopped.
+    /// Matching diverged right after this offset unless the whole key matched
+    pub matched_len: usize,
+    /// Whether the key holds a value in the trie
+    pub found: bool,
+    /// Stored labels closest to where the lookup stopped, in lexicographic order.
+    /// If no label shares a first character with the key,
+    /// these are the labels sorting right before and right after the key
+    pub nearest: Vec<String>,
+}
+
+impl<T> RadixTrie<T> {
+    /// Trace the lookup of the given label, reporting the visited labels,
+    /// where the matching diverged, and the nearest stored labels
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<()>::new();
+    /// trie.insert("/api/users", ());
+    /// trie.insert("/api/posts", ());
+    /// let explanation = trie.explain("/api/user");
+    /// assert!(!explanation.found);
+    /// assert_eq!(explanation.visited, vec!["/api/", "users"]);
+    /// assert_eq!(explanation.matched_len, 9);
+    /// assert_eq!(explanation.nearest, vec!["/api/users"]);
+    /// ```
+    pub fn explain(&self, label: &str) -> Explanation {
+        let mut visited = vec![];
+        let mut matched_len = 0;
+        let mut found = false;
+        // the last entered element and the length of the labels of its ancestors
+        let mut node: &Element<T> = &self.entry;
+        let mut node_prefix_len = 0;
+        let mut nearest_root = None;
+        while matched_len < label.len() {
+            let entry = node.children();
+            let rest = &label[matched_len..];
+            match Self::find_from_entry(entry, rest) {
+                BeyondSizeLimit | NotMatch(_) => break,
+                PrefixMatch(target_index) => {
+                    let target = &entry[target_index];
+                    visited.push(target.label().to_owned());
+                    node_prefix_len = matched_len;
+                    matched_len += target.label().len();
+                    node = target;
+                }
+                ExactMatch(target_index) => {
+                    let target = &entry[target_index];
+                    visited.push(target.label().to_owned());
+                    found = target.value().is_some();
+                    nearest_root = Some((target, matched_len));
+                    matched_len += target.label().len();
+                }
+                AsPrefixOf(target_index) => {
+                    visited.push(entry[target_index].label().to_owned());
+                    nearest_root = Some((&entry[target_index], matched_len));
+                    matched_len = label.len();
+                }
+                Intersects(target_index, shared_prefix) => {
+                    visited.push(entry[target_index].label().to_owned());
+                    nearest_root = Some((&entry[target_index], matched_len));
+                    matched_len += shared_prefix.len();
+                    break;
+                }
+            }
+        }
+        let nearest = match nearest_root {
+            // no stored label shares a first character with the key,
+            // so the labels sorting around the key are the closest ones
+            None if visited.is_empty() => {
+                let before = self.prev_key_before(label);
+                let after = self.next_key_after(label);
+                before
+                    .into_iter()
+                    .chain(after)
+                    .map(|(label, _)| label)
+                    .collect()
+            }
+            _ => {
+                let (root, prefix_len) = nearest_root.unwrap_or((node, node_prefix_len));
+                Traversal::new(root, label[..prefix_len].to_owned(), 0)
+                    .take(NEAREST_LIMIT)
+                    .map(|(_, label, _)| label)
+                    .collect()
+            }
+        };
+        Explanation {
+            visited,
+            matched_len,
+            found,
+            nearest,
+        }
+    }
+}
+
+#[cfg(test)]
+mod explain_tests {
+    use crate::test_util::WORDS;
+    use crate::trie::RadixTrie;
+
+    fn get_test_trie() -> RadixTrie<()> {
+        let mut trie = RadixTrie::new();
+        for word in &WORDS {
+            trie.insert(word, ());
+        }
+        trie
+    }
+
+    #[test]
+    fn test_explain_found() {
+        let explanation = get_test_trie().explain("Wonder");
+        assert!(explanation.found);
+        assert_eq!(explanation.visited, vec!["Wo", "n", "der"]);
+        assert_eq!(explanation.matched_len, 6);
+        assert_eq!(explanation.nearest, vec!["Wonder", "Wonderful"]);
+    }
+
+    #[test]
+    fn test_explain_diverged() {
+        let trie = get_test_trie();
+        let explanation = trie.explain("Wonky");
+        assert!(!explanation.found);
+        assert_eq!(explanation.visited, vec!["Wo", "n"]);
+        assert_eq!(explanation.matched_len, 3);
+        assert_eq!(explanation.nearest, vec!["Won", "Wonder", "Wonderful"]);
+
+        let explanation = trie.explain("Wondrous");
+        assert_eq!(explanation.visited, vec!["Wo", "n", "der"]);
+        assert_eq!(explanation.matched_len, 4);
+        assert_eq!(explanation.nearest, vec!["Wonder", "Wonderful"]);
+
+        let explanation = trie.explain("Zebra");
+        assert!(explanation.visited.is_empty());
+        assert_eq!(explanation.matched_len, 0);
+        assert_eq!(explanation.nearest, vec!["World"]);
+
+        let explanation = trie.explain("Bee");
+        assert!(explanation.visited.is_empty());
+        assert_eq!(explanation.nearest, vec!["Axes", "Won"]);
+
+        let explanation = trie.explain("Aa");
+        assert_eq!(explanation.nearest, vec!["Axes"]);
+        assert_eq!(trie.explain("").nearest, vec!["Axes"]);
+    }
+}
@@ -0,0 +1,327 @@
+use crate::element::Element;
+use crate::trie::RadixTrie;
+use crate::util;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+impl<T> RadixTrie<T> {
+    /// Export the trie as nested JSON objects mirroring its internal structure.
+    /// Every label maps to an object with its optional `value` and its `children`.
+    /// Values are rendered by the given closure, which must return valid JSON
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("ON", 3);
+    /// trie.insert("ON20", 4);
+    /// assert_eq!(
+    ///     trie.to_json_tree(|value| value.to_string()),
+    ///     r#"{"ON":{"value":3,"children":{"20":{"value":4,"children":{}}}}}"#
+    /// );
+    /// ```
+    pub fn to_json_tree<F>(&self, mut render: F) -> String
+    where
+        F: FnMut(&T) -> String,
+    {
+        enum Step<'a, T> {
+            Open(&'a Element<T>, bool),
+            Close,
+        }
+        fn push_children<'a, T>(stack: &mut Vec<Step<'a, T>>, element: &'a Element<T>) {
+            let children = element.children().iter().enumerate().rev();
+            stack.extend(children.map(|(index, child)| Step::Open(child, index == 0)))
+        }
+        let mut json = String::from("{");
+        let mut stack = vec![];
+        push_children(&mut stack, &self.entry);
+        while let Some(step) = stack.pop() {
+            match step {
+                Step::Open(element, is_first) => {
+                    if !is_first {
+                        json.push(',');
+                    }
+                    json.push_str(&util::json_string(element.label()));
+                    json.push_str(":{");
+                    if let Some(value) = element.value() {
+                        json.push_str("\"value\":");
+                        json.push_str(&render(value));
+                        json.push(',');
+                    }
+                    json.push_str("\"children\":{");
+                    stack.push(Step::Close);
+                    push_children(&mut stack, element);
+                }
+                Step::Close => json.push_str("}}"),
+            }
+        }
+        json.push('}');
+        json
+    }
+
+    /// Export the internal structure as a Graphviz DOT digraph.
+    /// Every element is a node, with the rendered value as its text if it has one,
+    /// and every edge is marked with the label of the child
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("ON", 3);
+    /// let dot = trie.to_dot(|value| value.to_string());
+    /// assert!(dot.starts_with("digraph {"));
+    /// assert!(dot.contains("n0 -> n1 [label=\"ON\"];"));
+    /// ```
+    pub fn to_dot<F>(&self, mut render: F) -> String
+    where
+        F: FnMut(&T) -> String,
+    {
+        fn quote(text: &str) -> String {
+            let mut quoted = String::with_capacity(text.len() + 2);
+            quoted.push('"');
+            for char in text.chars() {
+                if char == '"' || char == '\\' {
+                    quoted.push('\\');
+                }
+                quoted.push(char);
+            }
+            quoted.push('"');
+            quoted
+        }
+        let mut dot = String::from("digraph {\n    n0 [shape=point];\n");
+        let mut next_id = 1;
+        let mut stack = vec![(0, &self.entry)];
+        while let Some((id, element)) = stack.pop() {
+            for child in element.children().iter().rev() {
+                let child_id = next_id;
+                next_id += 1;
+                let node = match child.value() {
+                    Some(value) => format!("label={}, shape=box", quote(&render(value))),
+                    None => String::from("label=\"\", shape=circle"),
+                };
+                dot.push_str(&format!("    n{} [{}];\n", child_id, node));
+                let edge = quote(child.label());
+                dot.push_str(&format!("    n{} -> n{} [label={}];\n", id, child_id, edge));
+                stack.push((child_id, child));
+            }
+        }
+        dot.push('}');
+        dot
+    }
+
+    /// Export the internal structure as a flat JSON graph of nodes and edges,
+    /// numbered like [`RadixTrie::to_dot`] with the root as node 0.
+    /// Nodes holding a value carry it rendered by the given closure, which must return valid JSON,
+    /// and every edge carries the label of the child
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("ON", 3);
+    /// assert_eq!(
+    ///     trie.to_json_structure(|value| value.to_string()),
+    ///     r#"{"nodes":[{"id":0},{"id":1,"value":3}],"edges":[{"from":0,"to":1,"label":"ON"}]}"#
+    /// );
+    /// ```
+    pub fn to_json_structure<F>(&self, mut render: F) -> String
+    where
+        F: FnMut(&T) -> String,
+    {
+        let mut nodes = String::from("{\"id\":0}");
+        let mut edges = String::new();
+        let mut next_id = 1;
+        let mut stack = vec![(0, &self.entry)];
+        while let Some((id, element)) = stack.pop() {
+            for child in element.children().iter().rev() {
+                let child_id = next_id;
+                next_id += 1;
+                nodes.push_str(&format!(",{{\"id\":{}", child_id));
+                if let Some(value) = child.value() {
+                    nodes.push_str(",\"value\":");
+                    nodes.push_str(&render(value));
+                }
+                nodes.push('}');
+                if !edges.is_empty() {
+                    edges.push(',');
+                }
+                let label = util::json_string(child.label());
+                edges.push_str(&format!(
+                    "{{\"from\":{},\"to\":{},\"label\":{}}}",
+                    id, child_id, label
+                ));
+                stack.push((child_id, child));
+            }
+        }
+        format!("{{\"nodes\":[{}],\"edges\":[{}]}}", nodes, edges)
+    }
+}
+
+impl<T: fmt::Debug> RadixTrie<T> {
+    /// Render the internal structure as an indented tree, one element per line.
+    /// Every line shows the label, the value after `=` if there is one,
+    /// and the number of children in brackets if there are any
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("exe", 3);
+    /// trie.insert("exec", 4);
+    /// trie.insert("example", 7);
+    /// let expected = "\
+    /// . [1]
+    /// └── ex [2]
+    ///     ├── ample = 7
+    ///     └── e = 3 [1]
+    ///         └── c = 4
+    /// ";
+    /// assert_eq!(trie.dump(), expected);
+    /// ```
+    pub fn dump(&self) -> String {
+        let mut dump = String::new();
+        self.dump_to(&mut dump)
+            .expect("Writing to a String does not fail");
+        dump
+    }
+
+    /// Render the internal structure as an indented tree into the writer.
+    /// The format is the same as [`RadixTrie::dump`]
+    pub fn dump_to<W: fmt::Write>(&self, out: &mut W) -> fmt::Result {
+        fn write_line<T: fmt::Debug, W: fmt::Write>(
+            out: &mut W,
+            element: &Element<T>,
+        ) -> fmt::Result {
+            if let Some(value) = element.value() {
+                write!(out, " = {:?}", value)?;
+            }
+            match element.children().len() {
+                0 => writeln!(out),
+                count => writeln!(out, " [{}]", count),
+            }
+        }
+        fn push_children<'a, T>(
+            stack: &mut Vec<(&'a Element<T>, usize, bool)>,
+            element: &'a Element<T>,
+            indent: usize,
+        ) {
+            let children = element.children();
+            let last = children.len().saturating_sub(1);
+            let frames = children.iter().enumerate().rev();
+            stack.extend(frames.map(|(index, child)| (child, indent, index == last)))
+        }
+        out.write_char('.')?;
+        write_line(out, &self.entry)?;
+        // every frame holds an element, the byte length of its indentation and whether it is the last child
+        let mut stack = vec![];
+        push_children(&mut stack, &self.entry, 0);
+        let mut indent = String::new();
+        while let Some((element, indent_len, is_last)) = stack.pop() {
+            indent.truncate(indent_len);
+            let branch = if is_last { "└──" } else { "├──" };
+            write!(out, "{}{} {}", indent, branch, element.label())?;
+            write_line(out, element)?;
+            indent.push_str(if is_last { "    " } else { "│   " });
+            push_children(&mut stack, element, indent.len());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod export_tests {
+    use crate::trie::RadixTrie;
+
+    #[test]
+    fn test_to_json_tree() {
+        let mut trie = RadixTrie::<&str>::new();
+        trie.insert("exe", "3");
+        trie.insert("exec", "4");
+        trie.insert("example", "\"7\"");
+        let json = trie.to_json_tree(|value| value.to_string());
+        let expected = concat!(
+            r#"{"ex":{"children":{"ample":{"value":"7","children":{}},"#,
+            r#""e":{"value":3,"children":{"c":{"value":4,"children":{}}}}}}}"#
+        );
+        assert_eq!(json, expected);
+    }
+
+    #[test]
+    fn test_to_json_tree_escapes_labels() {
+        let mut trie = RadixTrie::<()>::new();
+        trie.insert("a\"b\\c\n", ());
+        let json = trie.to_json_tree(|_| "null".to_owned());
+        assert_eq!(json, r#"{"a\"b\\c\n":{"value":null,"children":{}}}"#);
+        assert_eq!(RadixTrie::<()>::new().to_json_tree(|_| "null".into()), "{}");
+    }
+
+    #[test]
+    fn test_dump() {
+        let mut trie = RadixTrie::<usize>::new();
+        for word in &[
+            "Won",
+            "Wonder",
+            "Wonderful",
+            "World",
+            "Axes",
+            "多倫多",
+            "多",
+        ] {
+            trie.insert(word, word.len());
+        }
+        let expected = "\
+. [3]
+├── Axes = 4
+├── Wo [2]
+│   ├── n = 3 [1]
+│   │   └── der = 6 [1]
+│   │       └── ful = 9
+│   └── rld = 5
+└── 多 = 3 [1]
+    └── 倫多 = 9
+";
+        assert_eq!(trie.dump(), expected);
+        assert_eq!(RadixTrie::<usize>::new().dump(), ".\n");
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let mut trie = RadixTrie::<&str>::new();
+        trie.insert("exe", "3");
+        trie.insert("example", "\"7\"");
+        trie.insert("exec", "4");
+        let expected = concat!(
+            "digraph {\n",
+            "    n0 [shape=point];\n",
+            "    n1 [label=\"\", shape=circle];\n",
+            "    n0 -> n1 [label=\"ex\"];\n",
+            "    n2 [label=\"3\", shape=box];\n",
+            "    n1 -> n2 [label=\"e\"];\n",
+            "    n3 [label=\"\\\"7\\\"\", shape=box];\n",
+            "    n1 -> n3 [label=\"ample\"];\n",
+            "    n4 [label=\"4\", shape=box];\n",
+            "    n2 -> n4 [label=\"c\"];\n",
+            "}"
+        );
+        assert_eq!(trie.to_dot(|value| value.to_string()), expected);
+    }
+
+    #[test]
+    fn test_to_json_structure() {
+        let mut trie = RadixTrie::<&str>::new();
+        trie.insert("exe", "3");
+        trie.insert("example", "\"7\"");
+        trie.insert("exec", "4");
+        let expected = concat!(
+            r#"{"nodes":[{"id":0},{"id":1},{"id":2,"value":3},{"id":3,"value":"7"},{"id":4,"value":4}],"#,
+            r#""edges":[{"from":0,"to":1,"label":"ex"},{"from":1,"to":2,"label":"e"},"#,
+            r#"{"from":1,"to":3,"label":"ample"},{"from":2,"to":4,"label":"c"}]}"#
+        );
+        assert_eq!(trie.to_json_structure(|value| value.to_string()), expected);
+        assert_eq!(
+            RadixTrie::<()>::new().to_json_structure(|_| "null".into()),
+            r#"{"nodes":[{"id":0}],"edges":[]}"#
+        );
+    }
+}
@@ -0,0 +1,102 @@
+use crate::trie::RadixTrie;
+use alloc::sync::Arc;
+use core::ops::Deref;
+
+/// An immutable trie that can be shared across threads.
+///
+/// Cloning only bumps a reference count, and all read APIs of [`RadixTrie`]
+/// are available through `Deref` without any lock.
+/// Created by [`RadixTrie::freeze`], and swapped atomically behind a `SwappableRadixTrie`.
+///
+/// # Example
+/// ```rust
+/// use another_radix_trie::RadixTrie;
+/// use std::thread;
+///
+/// let mut trie = RadixTrie::<usize>::new();
+/// trie.insert("label", 5);
+/// let frozen = trie.freeze();
+/// let reader = frozen.clone();
+/// let found = thread::spawn(move || reader.find("label").copied()).join().unwrap();
+/// assert_eq!(found, Some(5));
+/// ```
+pub struct FrozenRadixTrie<T> {
+    trie: Arc<RadixTrie<T>>,
+}
+
+impl<T> Clone for FrozenRadixTrie<T> {
+    fn clone(&self) -> Self {
+        FrozenRadixTrie {
+            trie: Arc::clone(&self.trie),
+        }
+    }
+}
+
+impl<T> Deref for FrozenRadixTrie<T> {
+    type Target = RadixTrie<T>;
+
+    fn deref(&self) -> &RadixTrie<T> {
+        &self.trie
+    }
+}
+
+impl<T> From<FrozenRadixTrie<T>> for Arc<RadixTrie<T>> {
+    fn from(frozen: FrozenRadixTrie<T>) -> Self {
+        frozen.trie
+    }
+}
+
+impl<T> FrozenRadixTrie<T> {
+    /// Returns the mutable trie if this is the only handle to it,
+    /// otherwise the frozen trie is returned back
+    pub fn try_unfreeze(self) -> Result<RadixTrie<T>, Self> {
+        Arc::try_unwrap(self.trie).map_err(|trie| FrozenRadixTrie { trie })
+    }
+}
+
+impl<T> RadixTrie<T> {
+    /// Freeze the trie into an immutable handle that is cheap to clone and share across threads
+    pub fn freeze(self) -> FrozenRadixTrie<T> {
+        FrozenRadixTrie {
+            trie: Arc::new(self),
+        }
+    }
+}
+
+#[cfg(test)]
+mod frozen_tests {
+    use crate::frozen::FrozenRadixTrie;
+    use crate::trie::RadixTrie;
+    use std::sync::Arc;
+
+    fn assert_send_sync<S: Send + Sync>(_: &S) {}
+
+    #[test]
+    fn test_freeze_and_unfreeze() {
+        let mut trie = RadixTrie::<usize>::new();
+        trie.insert("lab", 3);
+        trie.insert("label", 5);
+        let frozen = trie.freeze();
+        assert_send_sync(&frozen);
+        let shared = frozen.clone();
+        assert_eq!(shared.find("lab"), Some(&3));
+        assert_eq!(shared.start_with("la").len(), 2);
+        let frozen = match frozen.try_unfreeze() {
+            Ok(_) => panic!("Unfreeze with a shared handle alive"),
+            Err(frozen) => frozen,
+        };
+        drop(shared);
+        let mut trie = frozen.try_unfreeze().ok().unwrap();
+        trie.insert("lazy", 4);
+        assert_eq!(trie.find("lazy"), Some(&4));
+    }
+
+    #[test]
+    fn test_frozen_into_arc() {
+        let mut trie = RadixTrie::<usize>::new();
+        trie.insert("label", 5);
+        let frozen: FrozenRadixTrie<usize> = trie.freeze();
+        let arc: Arc<RadixTrie<usize>> = frozen.into();
+        assert_eq!(arc.find("label"), Some(&5));
+    }
+}
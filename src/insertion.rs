@@ -0,0 +1,221 @@
+use crate::entry::Entry;
+use crate::trie::RadixTrie;
+use alloc::collections::btree_map::{self, BTreeMap};
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// A trie that also remembers the order labels were first inserted in.
+///
+/// Lookups and prefix queries work like [`RadixTrie`], and
+/// [`InsertionOrderTrie::iter_insertion_order`] replays the labels in arrival order.
+/// Replacing the value of a label keeps its original position.
+///
+/// # Example
+/// ```rust
+/// use another_radix_trie::InsertionOrderTrie;
+///
+/// let mut trie = InsertionOrderTrie::new();
+/// trie.insert("/var/log", 1);
+/// trie.insert("/tmp", 2);
+/// trie.insert("/var/log", 3);
+/// let res = trie.iter_insertion_order().collect::<Vec<_>>();
+/// assert_eq!(res, vec![("/var/log", &3), ("/tmp", &2)]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct InsertionOrderTrie<T> {
+    /// The values with the sequence number of their label
+    trie: RadixTrie<(u64, T)>,
+    /// The labels by sequence number
+    order: BTreeMap<u64, String>,
+    /// The sequence number of the next new label
+    next: u64,
+}
+
+/// Two tries are equal if they hold the same labels and values in the same insertion order,
+/// whatever sequence numbers the labels were given
+impl<T: PartialEq> PartialEq for InsertionOrderTrie<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter_insertion_order().eq(other.iter_insertion_order())
+    }
+}
+
+impl<T: Eq> Eq for InsertionOrderTrie<T> {}
+
+impl<T> Default for InsertionOrderTrie<T> {
+    fn default() -> Self {
+        InsertionOrderTrie::new()
+    }
+}
+
+impl<T> InsertionOrderTrie<T> {
+    /// Construct an empty trie
+    pub fn new() -> Self {
+        InsertionOrderTrie {
+            trie: RadixTrie::new(),
+            order: BTreeMap::new(),
+            next: 0,
+        }
+    }
+
+    /// Returns the number of labels
+    pub fn len(&self) -> usize {
+        self.trie.len()
+    }
+
+    /// Returns true if the trie holds no label
+    pub fn is_empty(&self) -> bool {
+        self.trie.is_empty()
+    }
+
+    /// Insert label and associated value, returning the replaced value.
+    /// A new label goes after all current labels in insertion order,
+    /// while an existing label keeps its position.
+    /// Inserting the empty label does nothing and returns None
+    pub fn insert(&mut self, label: &str, value: T) -> Option<T> {
+        if label.is_empty() {
+            return None;
+        }
+        match self.trie.entry(label) {
+            Entry::Occupied(mut entry) => Some(core::mem::replace(&mut entry.get_mut().1, value)),
+            Entry::Vacant(entry) => {
+                entry.insert((self.next, value));
+                self.order.insert(self.next, label.into());
+                self.next += 1;
+                None
+            }
+        }
+    }
+
+    /// Returns the borrowed value associated with related label
+    pub fn find(&self, label: &str) -> Option<&T> {
+        self.trie.find(label).map(|(_, value)| value)
+    }
+
+    /// Returns the mutable borrowed value associated with related label
+    pub fn find_mut(&mut self, label: &str) -> Option<&mut T> {
+        self.trie.find_mut(label).map(|(_, value)| value)
+    }
+
+    /// Returns true if the label exists in the trie
+    pub fn contains_key(&self, label: &str) -> bool {
+        self.trie.contains_key(label)
+    }
+
+    /// Removes the label from the trie and from the insertion order, returning its value
+    pub fn remove(&mut self, label: &str) -> Option<T> {
+        let (sequence, value) = self.trie.remove(label)?;
+        self.order.remove(&sequence);
+        Some(value)
+    }
+
+    /// Returns all labels starting with given prefix with their values, in lexicographic order
+    pub fn start_with(&self, prefix: &str) -> Vec<(String, &T)> {
+        self.trie
+            .find_prefix_iter(prefix)
+            .map(|(label, (_, value))| (label, value))
+            .collect()
+    }
+
+    /// Returns a lazy iterator over all labels and borrowed values in the order
+    /// the labels were first inserted
+    pub fn iter_insertion_order(&self) -> InsertionOrder<'_, T> {
+        InsertionOrder {
+            labels: self.order.values(),
+            trie: &self.trie,
+        }
+    }
+}
+
+/// Iterator over the labels and borrowed values of an [`InsertionOrderTrie`]
+/// in the order the labels were first inserted.
+///
+/// Created by [`InsertionOrderTrie::iter_insertion_order`]
+pub struct InsertionOrder<'a, T> {
+    labels: btree_map::Values<'a, u64, String>,
+    trie: &'a RadixTrie<(u64, T)>,
+}
+
+impl<'a, T> Iterator for InsertionOrder<'a, T> {
+    type Item = (&'a str, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let label = self.labels.next()?;
+        let (_, value) = self.trie.find(label)?;
+        Some((label, value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.labels.size_hint()
+    }
+}
+
+impl<'a, T> ExactSizeIterator for InsertionOrder<'a, T> {}
+
+#[cfg(test)]
+mod insertion_tests {
+    use crate::insertion::InsertionOrderTrie;
+
+    #[test]
+    fn test_insertion_order() {
+        let mut trie = InsertionOrderTrie::new();
+        let lines = ["World", "Won", "多倫多", "Axes", "Wonder", "Won", "Wo"];
+        for (index, line) in lines.iter().enumerate() {
+            trie.insert(line, index);
+        }
+        assert_eq!(trie.len(), 6);
+        let res = trie.iter_insertion_order().collect::<Vec<_>>();
+        assert_eq!(
+            res,
+            vec![
+                ("World", &0),
+                ("Won", &5),
+                ("多倫多", &2),
+                ("Axes", &3),
+                ("Wonder", &4),
+                ("Wo", &6),
+            ]
+        );
+        assert_eq!(trie.remove("Won"), Some(5));
+        assert_eq!(trie.remove("Won"), None);
+        trie.insert("Won", 7);
+        *trie.find_mut("Axes").unwrap() += 10;
+        let labels = trie
+            .iter_insertion_order()
+            .map(|(label, _)| label)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            labels,
+            vec!["World", "多倫多", "Axes", "Wonder", "Wo", "Won"]
+        );
+        assert_eq!(trie.iter_insertion_order().len(), trie.len());
+        assert_eq!(trie.find("Axes"), Some(&13));
+        let sorted = trie
+            .start_with("Wo")
+            .into_iter()
+            .map(|(label, _)| label)
+            .collect::<Vec<_>>();
+        assert_eq!(sorted, vec!["Wo", "Won", "Wonder", "World"]);
+        assert!(trie.insert("", 0).is_none());
+        assert!(trie.contains_key("多倫多") && !trie.contains_key("多"));
+    }
+
+    #[test]
+    fn test_equality_ignores_sequence_numbers() {
+        let mut trie = InsertionOrderTrie::new();
+        trie.insert("Axes", 0);
+        trie.insert("Won", 1);
+        trie.insert("World", 2);
+        trie.remove("Axes");
+        let mut other = InsertionOrderTrie::new();
+        other.insert("Won", 1);
+        other.insert("World", 2);
+        assert_eq!(trie, other);
+        other.insert("World", 3);
+        assert_ne!(trie, other);
+        // the same labels and values in another order are not equal
+        let mut reordered = InsertionOrderTrie::new();
+        reordered.insert("World", 2);
+        reordered.insert("Won", 1);
+        assert_ne!(trie, reordered);
+    }
+}
@@ -0,0 +1,221 @@
+use crate::iter::Iter;
+use crate::trie::RadixTrie;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// A routing table mapping IP prefixes to values, with longest prefix matching.
+///
+/// Every prefix is stored as one char per bit after a char for its address family,
+/// so prefix lengths that are not byte aligned share elements like any other labels
+/// and IPv4 and IPv6 prefixes never match each other.
+/// Host bits beyond the prefix length are ignored.
+///
+/// # Example
+/// ```rust
+/// use another_radix_trie::IpPrefixTrie;
+/// use std::net::{IpAddr, Ipv4Addr};
+///
+/// let mut routes = IpPrefixTrie::new();
+/// routes.insert(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 0)), 8, "core");
+/// routes.insert(IpAddr::V4(Ipv4Addr::new(10, 20, 0, 0)), 14, "edge");
+/// let edge = routes.longest_match(IpAddr::V4(Ipv4Addr::new(10, 22, 1, 1)));
+/// assert_eq!(edge, Some((IpAddr::V4(Ipv4Addr::new(10, 20, 0, 0)), 14, &"edge")));
+/// let core = routes.longest_match(IpAddr::V4(Ipv4Addr::new(10, 24, 0, 1)));
+/// assert_eq!(core.map(|(_, prefix_len, route)| (prefix_len, *route)), Some((8, "core")));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IpPrefixTrie<T> {
+    trie: RadixTrie<T>,
+}
+
+impl<T> Default for IpPrefixTrie<T> {
+    fn default() -> Self {
+        IpPrefixTrie::new()
+    }
+}
+
+impl<T> IpPrefixTrie<T> {
+    /// Construct an empty routing table
+    pub fn new() -> Self {
+        IpPrefixTrie {
+            trie: RadixTrie::new(),
+        }
+    }
+
+    /// Returns the number of prefixes stored
+    pub fn len(&self) -> usize {
+        self.trie.len()
+    }
+
+    /// Returns true if no prefix is stored
+    pub fn is_empty(&self) -> bool {
+        self.trie.is_empty()
+    }
+
+    /// Insert the prefix of the given length and its value, returning the replaced value
+    /// # Panics
+    /// Panics if the prefix length is longer than the address
+    pub fn insert(&mut self, network: IpAddr, prefix_len: u8, value: T) -> Option<T> {
+        self.trie.insert(encode(network, prefix_len), value)
+    }
+
+    /// Returns the borrowed value of exactly the given prefix
+    /// # Panics
+    /// Panics if the prefix length is longer than the address
+    pub fn find(&self, network: IpAddr, prefix_len: u8) -> Option<&T> {
+        self.trie.find(encode(network, prefix_len))
+    }
+
+    /// Returns the mutable borrowed value of exactly the given prefix
+    /// # Panics
+    /// Panics if the prefix length is longer than the address
+    pub fn find_mut(&mut self, network: IpAddr, prefix_len: u8) -> Option<&mut T> {
+        self.trie.find_mut(encode(network, prefix_len))
+    }
+
+    /// Removes exactly the given prefix and returns its value
+    /// # Panics
+    /// Panics if the prefix length is longer than the address
+    pub fn remove(&mut self, network: IpAddr, prefix_len: u8) -> Option<T> {
+        self.trie.remove(encode(network, prefix_len))
+    }
+
+    /// Returns the longest stored prefix containing the address,
+    /// as its network address and length, together with its value.
+    /// If no prefix contains the address, return None
+    pub fn longest_match(&self, address: IpAddr) -> Option<(IpAddr, u8, &T)> {
+        let query = encode(address, width(address));
+        let (matched, value) = self.trie.longest_prefix(&query)?;
+        let prefix_len = (matched.len() - 1) as u8;
+        Some((decode(matched), prefix_len, value))
+    }
+
+    /// Returns a lazy iterator over all prefixes, as network addresses and lengths,
+    /// with their borrowed values. Every IPv4 prefix comes before the IPv6 ones,
+    /// and a prefix comes right before the longer prefixes it contains
+    pub fn iter(&self) -> Routes<'_, T> {
+        Routes {
+            iter: self.trie.iter(),
+        }
+    }
+}
+
+/// Iterator over the prefixes and borrowed values of an [`IpPrefixTrie`].
+///
+/// Created by [`IpPrefixTrie::iter`]
+pub struct Routes<'a, T> {
+    iter: Iter<'a, T>,
+}
+
+impl<'a, T> Iterator for Routes<'a, T> {
+    type Item = (IpAddr, u8, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (label, value) = self.iter.next()?;
+        Some((decode(&label), (label.len() - 1) as u8, value))
+    }
+}
+
+const V4: char = '4';
+const V6: char = '6';
+
+/// Number of bits of the address
+fn width(address: IpAddr) -> u8 {
+    match address {
+        IpAddr::V4(_) => 32,
+        IpAddr::V6(_) => 128,
+    }
+}
+
+/// Encode the family of the address and its first `prefix_len` bits as a label
+fn encode(address: IpAddr, prefix_len: u8) -> String {
+    assert!(
+        prefix_len <= width(address),
+        "Prefix length exceeds the address width"
+    );
+    let (family, bits) = match address {
+        IpAddr::V4(address) => (V4, u128::from(u32::from(address)) << 96),
+        IpAddr::V6(address) => (V6, u128::from(address)),
+    };
+    let mut label = String::with_capacity(prefix_len as usize + 1);
+    label.push(family);
+    for bit in 0..prefix_len {
+        let set = (bits >> (127 - bit)) & 1 == 1;
+        label.push(if set { '1' } else { '0' });
+    }
+    label
+}
+
+/// Decode a label back into the network address, with the missing host bits set to zero
+fn decode(label: &str) -> IpAddr {
+    let mut bits = 0u128;
+    for (bit, char) in label[1..].chars().enumerate() {
+        if char == '1' {
+            bits |= 1 << (127 - bit);
+        }
+    }
+    if label.starts_with(V4) {
+        IpAddr::V4(Ipv4Addr::from((bits >> 96) as u32))
+    } else {
+        IpAddr::V6(Ipv6Addr::from(bits))
+    }
+}
+
+#[cfg(test)]
+mod ip_tests {
+    use crate::ip::IpPrefixTrie;
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+    fn v4(a: u8, b: u8, c: u8, d: u8) -> IpAddr {
+        IpAddr::V4(Ipv4Addr::new(a, b, c, d))
+    }
+
+    #[test]
+    fn test_longest_match() {
+        let mut routes = IpPrefixTrie::new();
+        routes.insert(v4(0, 0, 0, 0), 0, "default");
+        routes.insert(v4(192, 168, 0, 0), 16, "lan");
+        // host bits are ignored
+        routes.insert(v4(192, 168, 5, 77), 21, "office");
+        routes.insert(v4(192, 168, 7, 9), 32, "printer");
+        let v6 = IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0));
+        routes.insert(v6, 29, "docs");
+        assert_eq!(routes.len(), 5);
+        assert_eq!(
+            routes.longest_match(v4(192, 168, 7, 9)),
+            Some((v4(192, 168, 7, 9), 32, &"printer"))
+        );
+        assert_eq!(
+            routes.longest_match(v4(192, 168, 7, 10)),
+            Some((v4(192, 168, 0, 0), 21, &"office"))
+        );
+        assert_eq!(routes.longest_match(v4(192, 168, 8, 1)).unwrap().2, &"lan");
+        assert_eq!(routes.longest_match(v4(8, 8, 8, 8)).unwrap().2, &"default");
+        let inside = IpAddr::V6(Ipv6Addr::new(0x2001, 0xdbf, 1, 0, 0, 0, 0, 1));
+        assert_eq!(routes.longest_match(inside), Some((v6, 29, &"docs")));
+        let outside = IpAddr::V6(Ipv6Addr::new(0x2001, 0xdc0, 0, 0, 0, 0, 0, 1));
+        assert_eq!(routes.longest_match(outside), None);
+        assert_eq!(routes.find(v4(192, 168, 0, 0), 21), Some(&"office"));
+        assert_eq!(routes.find(v4(192, 168, 0, 0), 20), None);
+        assert_eq!(routes.remove(v4(192, 168, 0, 0), 21), Some("office"));
+        assert_eq!(routes.longest_match(v4(192, 168, 7, 10)).unwrap().2, &"lan");
+        let all = routes
+            .iter()
+            .map(|(network, prefix_len, _)| (network, prefix_len))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            all,
+            vec![
+                (v4(0, 0, 0, 0), 0),
+                (v4(192, 168, 0, 0), 16),
+                (v4(192, 168, 7, 9), 32),
+                (v6, 29),
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Prefix length exceeds the address width")]
+    fn test_prefix_too_long() {
+        IpPrefixTrie::new().insert(v4(10, 0, 0, 0), 33, ());
+    }
+}
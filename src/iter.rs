@@ -0,0 +1,1030 @@
+use crate::element::Element;
+use crate::trie::RadixTrie;
+use alloc::collections::BinaryHeap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cmp::Reverse;
+
+/// Lazy pre-order traversal of the values below an element, in lexicographic order of labels.
+/// Yields the depth, the full label, and the value of every element holding a value
+pub(crate) struct Traversal<'a, T> {
+    label: String,
+    /// Elements to visit with their depth and the length of their parent label
+    stack: Vec<(usize, usize, &'a Element<T>)>,
+}
+
+impl<'a, T> Traversal<'a, T> {
+    /// Traverse the element and its descendants.
+    /// The prefix is the joined labels of its ancestors
+    pub(crate) fn new(element: &'a Element<T>, prefix: String, depth: usize) -> Self {
+        Traversal {
+            stack: vec![(depth, prefix.len(), element)],
+            label: prefix,
+        }
+    }
+
+    /// A traversal that yields nothing
+    pub(crate) fn empty() -> Self {
+        Traversal {
+            stack: vec![],
+            label: String::new(),
+        }
+    }
+}
+
+impl<'a, T> Traversal<'a, T> {
+    /// Advance to the next value accepted by the predicate.
+    /// The label is only cloned for accepted values
+    pub(crate) fn next_where<F>(&mut self, mut accept: F) -> Option<(usize, String, &'a T)>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        while let Some((depth, value)) = self.advance() {
+            if accept(value) {
+                return Some((depth, self.label.clone(), value));
+            }
+        }
+        None
+    }
+
+    /// Advance to the next value, leaving its full label in the buffer of the traversal
+    fn advance(&mut self) -> Option<(usize, &'a T)> {
+        while let Some((depth, prefix_len, element)) = self.stack.pop() {
+            self.label.truncate(prefix_len);
+            self.label.push_str(element.label());
+            let label_len = self.label.len();
+            let children = element.children().iter().rev();
+            self.stack
+                .extend(children.map(|child| (depth + 1, label_len, child)));
+            if let Some(value) = element.value() {
+                return Some((depth, value));
+            }
+        }
+        None
+    }
+}
+
+impl<'a, T> Iterator for Traversal<'a, T> {
+    type Item = (usize, String, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_where(|_| true)
+    }
+}
+
+/// Iterator over the entries of a trie in lexicographic order,
+/// with the depth of each entry counted in label segments from the root.
+///
+/// Created by [`RadixTrie::iter_with_depth`]
+pub struct IterWithDepth<'a, T> {
+    traversal: Traversal<'a, T>,
+}
+
+impl<'a, T> Iterator for IterWithDepth<'a, T> {
+    type Item = (usize, String, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.traversal.next()
+    }
+}
+
+impl<T> RadixTrie<T> {
+    /// Returns an iterator over all the depths, labels, and values in lexicographic order.
+    /// The depth counts the label segments from the root, so top level labels have depth 1
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("ON", 3);
+    /// trie.insert("ON20", 4);
+    /// let res = trie.iter_with_depth().collect::<Vec<_>>();
+    /// assert_eq!(res, vec![(1, String::from("ON"), &3), (2, String::from("ON20"), &4)]);
+    /// ```
+    pub fn iter_with_depth(&self) -> IterWithDepth<'_, T> {
+        IterWithDepth {
+            traversal: Traversal::new(&self.entry, String::new(), 0),
+        }
+    }
+}
+
+/// Iterator over the labels and borrowed values of a trie in lexicographic order.
+///
+/// Created by [`RadixTrie::iter`]
+pub struct Iter<'a, T> {
+    pub(crate) traversal: Traversal<'a, T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = (String, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.traversal
+            .next()
+            .map(|(_, label, value)| (label, value))
+    }
+}
+
+/// Iterator over the labels and mutable borrowed values of a trie in lexicographic order.
+///
+/// Created by [`RadixTrie::iter_mut`]
+pub struct IterMut<'a, T> {
+    pub(crate) label: String,
+    /// Elements to visit with the length of their parent label
+    pub(crate) stack: Vec<(usize, &'a mut Element<T>)>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = (String, &'a mut T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((prefix_len, element)) = self.stack.pop() {
+            self.label.truncate(prefix_len);
+            self.label.push_str(element.label());
+            let label_len = self.label.len();
+            let (value, children) = element.value_and_children_mut();
+            self.stack
+                .extend(children.iter_mut().rev().map(|child| (label_len, child)));
+            if let Some(value) = value {
+                return Some((self.label.clone(), value));
+            }
+        }
+        None
+    }
+}
+
+/// Iterator over the labels and owned values of a trie in lexicographic order.
+///
+/// Created by [`RadixTrie::into_iter`]
+pub struct IntoIter<T> {
+    label: String,
+    /// Elements to visit with the length of their parent label
+    stack: Vec<(usize, Element<T>)>,
+}
+
+impl<T> IntoIter<T> {
+    /// Consume the element and its descendants.
+    /// The prefix is the joined labels of its ancestors
+    pub(crate) fn new(element: Element<T>, prefix: String) -> Self {
+        IntoIter {
+            stack: vec![(prefix.len(), element)],
+            label: prefix,
+        }
+    }
+
+    /// An iterator that yields nothing
+    pub(crate) fn empty() -> Self {
+        IntoIter {
+            stack: vec![],
+            label: String::new(),
+        }
+    }
+}
+
+impl<T> Drop for IntoIter<T> {
+    fn drop(&mut self) {
+        Element::drop_all(&mut self.stack.drain(..).map(|(_, element)| element).collect())
+    }
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = (String, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((prefix_len, element)) = self.stack.pop() {
+            let (label, value, children) = element.unpack();
+            self.label.truncate(prefix_len);
+            self.label.push_str(&label);
+            let label_len = self.label.len();
+            self.stack
+                .extend(children.into_iter().rev().map(|child| (label_len, child)));
+            if let Some(value) = value {
+                return Some((self.label.clone(), value));
+            }
+        }
+        None
+    }
+}
+
+/// Iterator over the labels and owned values removed from a trie, in lexicographic order.
+/// The entries are detached from the trie before the first one is yielded,
+/// so dropping the iterator early leaves the trie valid and drops the remaining values.
+///
+/// Created by [`RadixTrie::drain`] and [`RadixTrie::drain_prefix`]
+pub struct Drain<T> {
+    iter: IntoIter<T>,
+}
+
+impl<T> Iterator for Drain<T> {
+    type Item = (String, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+}
+
+impl<T> RadixTrie<T> {
+    /// Empties the trie and returns an iterator over the removed labels and values
+    /// in lexicographic order. The trie keeps no allocation of the removed elements
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("lab", 3);
+    /// trie.insert("label", 5);
+    /// let batch = trie.drain().collect::<Vec<_>>();
+    /// assert_eq!(batch, vec![(String::from("lab"), 3), (String::from("label"), 5)]);
+    /// assert!(trie.is_empty());
+    /// ```
+    pub fn drain(&mut self) -> Drain<T> {
+        Drain {
+            iter: self.split_off("").into_iter(),
+        }
+    }
+
+    /// Removes all labels starting with given prefix and returns an iterator over them
+    /// and their values in lexicographic order. The rest of the trie is compacted immediately
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("/tmp/a", 1);
+    /// trie.insert("/tmp/b", 2);
+    /// trie.insert("/usr", 3);
+    /// let mut drained = trie.drain_prefix("/tmp/");
+    /// assert_eq!(drained.next(), Some((String::from("/tmp/a"), 1)));
+    /// drop(drained);
+    /// assert_eq!(trie.len(), 1);
+    /// assert_eq!(trie.find("/tmp/b"), None);
+    /// ```
+    pub fn drain_prefix(&mut self, prefix: &str) -> Drain<T> {
+        if prefix.is_empty() {
+            return self.drain();
+        }
+        let iter = match self.detach_prefix(prefix) {
+            Some((existing_prefix, detached)) => IntoIter::new(detached, existing_prefix),
+            None => IntoIter::empty(),
+        };
+        Drain { iter }
+    }
+}
+
+impl<T> RadixTrie<T> {
+    /// Returns a lazy iterator over all labels and borrowed values in lexicographic order
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("label", 5);
+    /// trie.insert("lab", 3);
+    /// let res = trie.iter().collect::<Vec<_>>();
+    /// assert_eq!(res, vec![(String::from("lab"), &3), (String::from("label"), &5)]);
+    /// ```
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            traversal: Traversal::new(&self.entry, String::new(), 0),
+        }
+    }
+
+    /// Returns a lazy iterator over the labels and borrowed values where the labels start with given prefix,
+    /// in lexicographic order
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("lab", 3);
+    /// trie.insert("label", 5);
+    /// trie.insert("lazy", 4);
+    /// let res = trie.find_prefix_iter("la").take(2).collect::<Vec<_>>();
+    /// assert_eq!(res, vec![(String::from("lab"), &3), (String::from("label"), &5)]);
+    /// ```
+    pub fn find_prefix_iter(&self, prefix: &str) -> Iter<'_, T> {
+        let mut existing_prefix = String::new();
+        let traversal =
+            match self.find_prefix_element(prefix, |label| existing_prefix.push_str(label)) {
+                Some(element) => Traversal::new(element, existing_prefix, 0),
+                None => Traversal::empty(),
+            };
+        Iter { traversal }
+    }
+
+    /// Returns a lazy iterator over the labels and mutable borrowed values where the labels start with given prefix,
+    /// in lexicographic order
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("lab", 3);
+    /// trie.insert("label", 5);
+    /// trie.insert("lazy", 4);
+    /// for (_, value) in trie.find_prefix_mut("lab") {
+    ///     *value += 1;
+    /// }
+    /// assert_eq!(trie.find("label"), Some(&6));
+    /// assert_eq!(trie.find("lazy"), Some(&4));
+    /// ```
+    pub fn find_prefix_mut(&mut self, prefix: &str) -> IterMut<'_, T> {
+        let mut label = String::new();
+        let stack = match self.find_prefix_element_mut(prefix, &mut label) {
+            Some(element) => vec![(label.len(), element)],
+            None => vec![],
+        };
+        IterMut { label, stack }
+    }
+
+    /// Returns a lazy iterator over all labels and mutable borrowed values in lexicographic order
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("label", 5);
+    /// trie.insert("lab", 3);
+    /// for (_, value) in trie.iter_mut() {
+    ///     *value += 1;
+    /// }
+    /// assert_eq!(trie.find("lab"), Some(&4));
+    /// ```
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            label: String::new(),
+            stack: vec![(0, &mut self.entry)],
+        }
+    }
+}
+
+impl<T> IntoIterator for RadixTrie<T> {
+    type Item = (String, T);
+    type IntoIter = IntoIter<T>;
+
+    /// Consumes the trie into an iterator over all labels and owned values in lexicographic order
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter::new(self.into_entry(), String::new())
+    }
+}
+
+impl<'a, T> IntoIterator for &'a RadixTrie<T> {
+    type Item = (String, &'a T);
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut RadixTrie<T> {
+    type Item = (String, &'a mut T);
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+/// Iterator over the labels and borrowed values of a trie in descending lexicographic order.
+///
+/// Created by [`RadixTrie::iter_rev`] and [`RadixTrie::find_prefix_rev`]
+pub struct RevIter<'a, T> {
+    label: String,
+    /// Elements with the length of their parent label, and whether their children are already queued.
+    /// Children are queued front to back, so the last child is visited first,
+    /// and an element is yielded after all its descendants
+    stack: Vec<(usize, bool, &'a Element<T>)>,
+}
+
+impl<'a, T> RevIter<'a, T> {
+    fn new(element: Option<&'a Element<T>>, prefix: String) -> Self {
+        RevIter {
+            stack: element
+                .map(|element| (prefix.len(), false, element))
+                .into_iter()
+                .collect(),
+            label: prefix,
+        }
+    }
+}
+
+impl<'a, T> Iterator for RevIter<'a, T> {
+    type Item = (String, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((prefix_len, expanded, element)) = self.stack.pop() {
+            self.label.truncate(prefix_len);
+            self.label.push_str(element.label());
+            if expanded {
+                if let Some(value) = element.value() {
+                    return Some((self.label.clone(), value));
+                }
+                continue;
+            }
+            self.stack.push((prefix_len, true, element));
+            let label_len = self.label.len();
+            let children = element.children().iter();
+            self.stack
+                .extend(children.map(|child| (label_len, false, child)));
+        }
+        None
+    }
+}
+
+impl<T> RadixTrie<T> {
+    /// Returns a lazy iterator over all labels and borrowed values in descending lexicographic order,
+    /// walking the children back to front
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("2021-05-01", 1);
+    /// trie.insert("2021-05-03", 3);
+    /// trie.insert("2021-05", 0);
+    /// let res = trie.iter_rev().map(|(_, value)| *value).collect::<Vec<_>>();
+    /// assert_eq!(res, vec![3, 1, 0]);
+    /// ```
+    pub fn iter_rev(&self) -> RevIter<'_, T> {
+        RevIter::new(Some(&self.entry), String::new())
+    }
+
+    /// Returns a lazy iterator over the labels and borrowed values where the labels start with given prefix,
+    /// in descending lexicographic order
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("lab", 3);
+    /// trie.insert("label", 5);
+    /// trie.insert("lazy", 4);
+    /// let res = trie.find_prefix_rev("lab").collect::<Vec<_>>();
+    /// assert_eq!(res, vec![(String::from("label"), &5), (String::from("lab"), &3)]);
+    /// ```
+    pub fn find_prefix_rev(&self, prefix: &str) -> RevIter<'_, T> {
+        let mut existing_prefix = String::new();
+        let element = self.find_prefix_element(prefix, |label| existing_prefix.push_str(label));
+        RevIter::new(element, existing_prefix)
+    }
+}
+
+/// Iterator over the labels of a trie in lexicographic order.
+///
+/// Created by [`RadixTrie::keys`]
+pub struct Keys<'a, T> {
+    pub(crate) traversal: Traversal<'a, T>,
+}
+
+impl<'a, T> Iterator for Keys<'a, T> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.traversal.next().map(|(_, label, _)| label)
+    }
+}
+
+/// Lending iterator over the labels of a trie as bytes, with their values, in lexicographic order.
+/// Every label is rebuilt in one buffer that is borrowed until the next call,
+/// so no `String` is built per label and no UTF-8 has to be checked again.
+///
+/// Created by [`RadixTrie::key_bytes`]
+pub struct KeyBytes<'a, T> {
+    traversal: Traversal<'a, T>,
+}
+
+impl<'a, T> KeyBytes<'a, T> {
+    /// Advance to the next label and return its bytes with the borrowed value
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("label", 5);
+    /// trie.insert("lab", 3);
+    /// let mut keys = trie.key_bytes();
+    /// assert_eq!(keys.next_key(), Some((&b"lab"[..], &3)));
+    /// assert_eq!(keys.next_key(), Some((&b"label"[..], &5)));
+    /// assert_eq!(keys.next_key(), None);
+    /// ```
+    pub fn next_key(&mut self) -> Option<(&[u8], &'a T)> {
+        let (_, value) = self.traversal.advance()?;
+        Some((self.traversal.label.as_bytes(), value))
+    }
+}
+
+/// Iterator over the borrowed values of a trie in lexicographic order of their labels.
+///
+/// Created by [`RadixTrie::values`]
+pub struct Values<'a, T> {
+    pub(crate) stack: Vec<&'a Element<T>>,
+}
+
+impl<'a, T> Iterator for Values<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(element) = self.stack.pop() {
+            self.stack.extend(element.children().iter().rev());
+            if let Some(value) = element.value() {
+                return Some(value);
+            }
+        }
+        None
+    }
+}
+
+/// Iterator over the mutable borrowed values of a trie in lexicographic order of their labels.
+///
+/// Created by [`RadixTrie::values_mut`]
+pub struct ValuesMut<'a, T> {
+    pub(crate) stack: Vec<&'a mut Element<T>>,
+}
+
+impl<'a, T> Iterator for ValuesMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(element) = self.stack.pop() {
+            let (value, children) = element.value_and_children_mut();
+            self.stack.extend(children.iter_mut().rev());
+            if let Some(value) = value {
+                return Some(value);
+            }
+        }
+        None
+    }
+}
+
+/// Iterator over the labels and borrowed values whose values satisfy a predicate,
+/// in lexicographic order.
+///
+/// Created by [`RadixTrie::find_by_value`] and [`RadixTrie::find_prefix_by_value`]
+pub struct FindByValue<'a, T, F> {
+    traversal: Traversal<'a, T>,
+    predicate: F,
+}
+
+impl<'a, T, F> Iterator for FindByValue<'a, T, F>
+where
+    F: FnMut(&T) -> bool,
+{
+    type Item = (String, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.traversal
+            .next_where(&mut self.predicate)
+            .map(|(_, label, value)| (label, value))
+    }
+}
+
+impl<T> RadixTrie<T> {
+    /// Returns a lending iterator over all labels as bytes with their values in lexicographic order,
+    /// for consumers that hash or copy the bytes of every label without keeping them
+    pub fn key_bytes(&self) -> KeyBytes<'_, T> {
+        KeyBytes {
+            traversal: Traversal::new(&self.entry, String::new(), 0),
+        }
+    }
+
+    /// Returns a lazy iterator over all labels in lexicographic order
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("label", 5);
+    /// trie.insert("lab", 3);
+    /// assert_eq!(trie.keys().collect::<Vec<_>>(), vec!["lab", "label"]);
+    /// ```
+    pub fn keys(&self) -> Keys<'_, T> {
+        Keys {
+            traversal: Traversal::new(&self.entry, String::new(), 0),
+        }
+    }
+
+    /// Returns a lazy iterator over all borrowed values in lexicographic order of their labels.
+    /// No label is built
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("label", 5);
+    /// trie.insert("lab", 3);
+    /// assert_eq!(trie.values().collect::<Vec<_>>(), vec![&3, &5]);
+    /// ```
+    pub fn values(&self) -> Values<'_, T> {
+        Values {
+            stack: vec![&self.entry],
+        }
+    }
+
+    /// Returns a lazy iterator over all mutable borrowed values in lexicographic order of their labels.
+    /// No label is built
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("label", 5);
+    /// trie.insert("lab", 3);
+    /// trie.values_mut().for_each(|value| *value *= 2);
+    /// assert_eq!(trie.find("lab"), Some(&6));
+    /// ```
+    pub fn values_mut(&mut self) -> ValuesMut<'_, T> {
+        ValuesMut {
+            stack: vec![&mut self.entry],
+        }
+    }
+
+    /// Returns a lazy iterator over the labels and borrowed values whose values satisfy the predicate,
+    /// in lexicographic order.
+    /// Labels are only built for the values accepted
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("lab", 3);
+    /// trie.insert("label", 5);
+    /// trie.insert("lazy", 4);
+    /// let res = trie.find_by_value(|value| value % 2 == 1).collect::<Vec<_>>();
+    /// assert_eq!(res, vec![(String::from("lab"), &3), (String::from("label"), &5)]);
+    /// ```
+    pub fn find_by_value<F>(&self, predicate: F) -> FindByValue<'_, T, F>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        FindByValue {
+            traversal: Traversal::new(&self.entry, String::new(), 0),
+            predicate,
+        }
+    }
+
+    /// Returns a lazy iterator over the labels and borrowed values where the labels start with given prefix
+    /// and the values satisfy the predicate, in lexicographic order
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<bool>::new();
+    /// trie.insert("/api/v1/users", true);
+    /// trie.insert("/api/v2/users", false);
+    /// trie.insert("/internal/jobs", true);
+    /// let deprecated = trie
+    ///     .find_prefix_by_value("/api/", |deprecated| *deprecated)
+    ///     .map(|(route, _)| route)
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(deprecated, vec!["/api/v1/users"]);
+    /// ```
+    pub fn find_prefix_by_value<F>(&self, prefix: &str, predicate: F) -> FindByValue<'_, T, F>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        FindByValue {
+            traversal: self.find_prefix_iter(prefix).traversal,
+            predicate,
+        }
+    }
+}
+
+/// Iterator over the entries of a trie in ascending order of values.
+/// Entries with equal values are yielded in lexicographic order of their labels.
+///
+/// Created by [`RadixTrie::iter_by_value`]
+pub struct IterByValue<'a, T> {
+    /// The parent index and the label of every visited element.
+    /// The root has index 0
+    labels: Vec<(usize, &'a str)>,
+    heap: BinaryHeap<Reverse<(&'a T, usize)>>,
+}
+
+impl<'a, T: Ord> IterByValue<'a, T> {
+    fn new(root: &'a Element<T>) -> Self {
+        let mut labels = vec![];
+        let mut values = vec![];
+        let mut stack = vec![(0, root)];
+        while let Some((parent, element)) = stack.pop() {
+            let index = labels.len();
+            labels.push((parent, element.label()));
+            if let Some(value) = element.value() {
+                values.push(Reverse((value, index)));
+            }
+            stack.extend(element.children().iter().rev().map(|child| (index, child)));
+        }
+        IterByValue {
+            labels,
+            heap: BinaryHeap::from(values),
+        }
+    }
+
+    /// Join the labels from the root down to the element at the given index
+    fn full_label(&self, mut index: usize) -> String {
+        let mut segments = vec![];
+        while index != 0 {
+            let (parent, label) = self.labels[index];
+            segments.push(label);
+            index = parent;
+        }
+        segments.reverse();
+        segments.concat()
+    }
+}
+
+impl<'a, T: Ord> Iterator for IterByValue<'a, T> {
+    type Item = (String, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Reverse((value, index)) = self.heap.pop()?;
+        Some((self.full_label(index), value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.heap.len(), Some(self.heap.len()))
+    }
+}
+
+impl<'a, T: Ord> ExactSizeIterator for IterByValue<'a, T> {}
+
+impl<T: Ord> RadixTrie<T> {
+    /// Returns an iterator over all labels and values in ascending order of values.
+    /// The values are heapified in one traversal,
+    /// and a label is only built when its entry is yielded
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("lab", 3);
+    /// trie.insert("label", 5);
+    /// trie.insert("lazy", 1);
+    /// let top = trie.iter_by_value().take(2).collect::<Vec<_>>();
+    /// assert_eq!(top, vec![(String::from("lazy"), &1), (String::from("lab"), &3)]);
+    /// ```
+    pub fn iter_by_value(&self) -> IterByValue<'_, T> {
+        IterByValue::new(&self.entry)
+    }
+}
+
+#[cfg(test)]
+mod iter_tests {
+    use crate::trie::RadixTrie;
+
+    #[test]
+    fn test_iter_by_value() {
+        let mut trie = RadixTrie::<usize>::new();
+        let words = ["Wonderful", "World", "Won", "Axes", "Wonder", "Wo"];
+        for word in &words {
+            trie.insert(word, word.len());
+        }
+        let res = trie.iter_by_value().collect::<Vec<_>>();
+        let expected: Vec<(String, &usize)> = vec![
+            ("Wo".into(), &2),
+            ("Won".into(), &3),
+            ("Axes".into(), &4),
+            ("World".into(), &5),
+            ("Wonder".into(), &6),
+            ("Wonderful".into(), &9),
+        ];
+        assert_eq!(res, expected);
+        assert_eq!(trie.iter_by_value().len(), words.len());
+        assert_eq!(RadixTrie::<usize>::new().iter_by_value().next(), None);
+    }
+
+    #[test]
+    fn test_iter_by_value_ties_in_label_order() {
+        let mut trie = RadixTrie::<usize>::new();
+        for word in &["b", "ab", "a", "abc"] {
+            trie.insert(word, 0);
+        }
+        let labels = trie
+            .iter_by_value()
+            .map(|(label, _)| label)
+            .collect::<Vec<_>>();
+        assert_eq!(labels, vec!["a", "ab", "abc", "b"]);
+    }
+
+    #[test]
+    fn test_iter_with_depth() {
+        let mut trie = RadixTrie::<()>::new();
+        for word in &["exe", "execute", "exec", "example", "Axes"] {
+            trie.insert(word, ());
+        }
+        let res = trie
+            .iter_with_depth()
+            .map(|(depth, label, _)| (depth, label))
+            .collect::<Vec<_>>();
+        let expected: Vec<(usize, String)> = vec![
+            (1, "Axes".into()),
+            (2, "example".into()),
+            (2, "exe".into()),
+            (3, "exec".into()),
+            (4, "execute".into()),
+        ];
+        assert_eq!(res, expected);
+    }
+
+    fn get_test_trie() -> RadixTrie<usize> {
+        let mut trie = RadixTrie::new();
+        let words = [
+            "Wonderful",
+            "World",
+            "Won",
+            "Axes",
+            "Wonder",
+            "Toronto多倫多",
+            "Toronto",
+        ];
+        for word in &words {
+            trie.insert(word, word.len());
+        }
+        trie
+    }
+
+    #[test]
+    fn test_iter() {
+        let trie = get_test_trie();
+        let labels = trie.iter().map(|(label, _)| label).collect::<Vec<_>>();
+        let expected = vec![
+            "Axes",
+            "Toronto",
+            "Toronto多倫多",
+            "Won",
+            "Wonder",
+            "Wonderful",
+            "World",
+        ];
+        assert_eq!(labels, expected);
+        assert!((&trie)
+            .into_iter()
+            .all(|(label, value)| label.len() == *value));
+        assert_eq!(RadixTrie::<()>::new().iter().next(), None);
+    }
+
+    #[test]
+    fn test_iter_mut_and_into_iter() {
+        let mut trie = get_test_trie();
+        for (label, value) in &mut trie {
+            *value = label.chars().count();
+        }
+        assert_eq!(trie.find("Toronto多倫多"), Some(&10));
+        let owned = trie.into_iter().collect::<Vec<_>>();
+        assert_eq!(owned.len(), 7);
+        assert_eq!(owned[2], (String::from("Toronto多倫多"), 10));
+        assert_eq!(owned[6], (String::from("World"), 5));
+    }
+
+    #[test]
+    fn test_iter_rev() {
+        let trie = get_test_trie();
+        let mut expected = trie.iter().collect::<Vec<_>>();
+        expected.reverse();
+        assert_eq!(trie.iter_rev().collect::<Vec<_>>(), expected);
+        let mut expected = trie.find_prefix_iter("Wo").collect::<Vec<_>>();
+        expected.reverse();
+        assert_eq!(trie.find_prefix_rev("Wo").collect::<Vec<_>>(), expected);
+        let res = trie.find_prefix_rev("Toronto多").collect::<Vec<_>>();
+        assert_eq!(res, vec![("Toronto多倫多".to_owned(), &16)]);
+        assert_eq!(trie.find_prefix_rev("Z").next(), None);
+        assert!(trie.find_prefix_rev("").eq(trie.iter_rev()));
+        assert_eq!(RadixTrie::<()>::new().iter_rev().next(), None);
+    }
+
+    #[test]
+    fn test_find_prefix_iter() {
+        let trie = get_test_trie();
+        let res = trie.find_prefix_iter("Wo").collect::<Vec<_>>();
+        let expected: Vec<(String, &usize)> = vec![
+            ("Won".into(), &3),
+            ("Wonder".into(), &6),
+            ("Wonderful".into(), &9),
+            ("World".into(), &5),
+        ];
+        assert_eq!(res, expected);
+        let res = trie.find_prefix_iter("Toronto多").collect::<Vec<_>>();
+        assert_eq!(res, vec![("Toronto多倫多".to_owned(), &16)]);
+        assert_eq!(trie.find_prefix_iter("Wonders").next(), None);
+        assert_eq!(trie.find_prefix_iter("Z").next(), None);
+        assert!(trie.find_prefix_iter("").eq(trie.iter()));
+    }
+
+    #[test]
+    fn test_keys_and_values() {
+        let mut trie = get_test_trie();
+        let keys = trie.keys().collect::<Vec<_>>();
+        let expected = trie.iter().map(|(label, _)| label).collect::<Vec<_>>();
+        assert_eq!(keys, expected);
+        let values = trie.values().copied().collect::<Vec<_>>();
+        let expected = trie.iter().map(|(_, value)| *value).collect::<Vec<_>>();
+        assert_eq!(values, expected);
+        trie.values_mut().for_each(|value| *value += 1);
+        let lens = keys.iter().map(|key| key.len() + 1).collect::<Vec<_>>();
+        assert_eq!(trie.values().copied().collect::<Vec<_>>(), lens);
+        let mut key_bytes = trie.key_bytes();
+        let mut entries = trie.iter();
+        while let Some((label, value)) = key_bytes.next_key() {
+            let (expected_label, expected_value) = entries.next().unwrap();
+            assert_eq!(label, expected_label.as_bytes());
+            assert_eq!(value, expected_value);
+        }
+        assert_eq!(entries.next(), None);
+        let empty = RadixTrie::<usize>::new();
+        assert_eq!(empty.keys().next(), None);
+        assert_eq!(empty.values().next(), None);
+        assert_eq!(empty.key_bytes().next_key(), None);
+    }
+
+    #[test]
+    fn test_drain() {
+        let mut trie = get_test_trie();
+        let expected = trie
+            .iter()
+            .map(|(label, value)| (label, *value))
+            .collect::<Vec<_>>();
+        assert_eq!(trie.drain().collect::<Vec<_>>(), expected);
+        assert!(trie.is_empty());
+        assert_eq!(trie.iter().next(), None);
+        trie.insert("Won", 3);
+        assert_eq!(trie.find("Won"), Some(&3));
+    }
+
+    #[test]
+    fn test_drain_prefix_dropped_early() {
+        let mut trie = get_test_trie();
+        let mut drained = trie.drain_prefix("Wo");
+        assert_eq!(drained.next(), Some((String::from("Won"), 3)));
+        drop(drained);
+        assert_eq!(trie.len(), 3);
+        assert_eq!(trie.find("Wonder"), None);
+        assert_eq!(trie.find("Toronto"), Some(&7));
+        assert_eq!(trie.drain_prefix("Wo").next(), None);
+        let rest = trie.drain_prefix("Toronto").count();
+        assert_eq!(rest, 2);
+        assert_eq!(trie.keys().collect::<Vec<_>>(), vec!["Axes"]);
+        let mut trie = get_test_trie();
+        let expected = trie.clone().into_iter().collect::<Vec<_>>();
+        assert_eq!(trie.drain_prefix("").collect::<Vec<_>>(), expected);
+        assert!(trie.is_empty());
+    }
+
+    #[test]
+    fn test_find_prefix_mut() {
+        let mut trie = get_test_trie();
+        let expected = trie
+            .find_prefix_iter("Wo")
+            .map(|(label, value)| (label, *value))
+            .collect::<Vec<_>>();
+        let res = trie
+            .find_prefix_mut("Wo")
+            .map(|(label, value)| {
+                *value *= 10;
+                (label, *value / 10)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(res, expected);
+        assert_eq!(trie.find("Wonderful"), Some(&90));
+        assert_eq!(trie.find("Toronto"), Some(&7));
+        assert_eq!(trie.find_prefix_mut("Toronto多").count(), 1);
+        assert_eq!(trie.find_prefix_mut("Wx").count(), 0);
+        assert_eq!(trie.find_prefix_mut("").count(), trie.len());
+    }
+
+    #[test]
+    fn test_find_by_value() {
+        let mut trie = RadixTrie::<usize>::new();
+        for word in &[
+            "Won",
+            "Wonder",
+            "Wonderful",
+            "World",
+            "Axes",
+            "多倫多",
+            "多",
+        ] {
+            trie.insert(word, word.len());
+        }
+        let odd = |value: &usize| value % 2 == 1;
+        let expected = trie
+            .iter()
+            .filter(|(_, value)| odd(value))
+            .collect::<Vec<_>>();
+        assert_eq!(trie.find_by_value(odd).collect::<Vec<_>>(), expected);
+        for prefix in &["W", "Wonder", "多", "Z", ""] {
+            let expected = trie
+                .find_prefix_iter(prefix)
+                .filter(|(_, value)| odd(value))
+                .collect::<Vec<_>>();
+            let res = trie.find_prefix_by_value(prefix, odd).collect::<Vec<_>>();
+            assert_eq!(res, expected, "{}", prefix);
+        }
+        let mut calls = 0;
+        let none = trie.find_by_value(|_| {
+            calls += 1;
+            false
+        });
+        assert_eq!(none.count(), 0);
+        assert_eq!(calls, trie.len());
+    }
+}
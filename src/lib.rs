@@ -1,4 +1,116 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+#[macro_use]
+extern crate alloc;
+
+mod analysis;
+mod augment;
+#[cfg(feature = "std")]
+mod binary;
+mod build;
+mod cache;
+mod compiled;
+mod complete;
+#[cfg(feature = "std")]
+mod concurrent;
+#[cfg(feature = "std")]
+mod concurrent_cache;
+mod cursor;
+mod diff;
 mod element;
+mod entry;
+mod error;
+mod explain;
+mod export;
+mod frozen;
+mod insertion;
+#[cfg(feature = "std")]
+mod ip;
+mod iter;
+mod limits;
+#[cfg(feature = "std")]
+mod loader;
+mod map;
+mod matcher;
+mod memory;
+mod merge;
+mod metric;
+mod multimap;
+mod normalized;
+mod observed;
+mod order;
+mod overlay;
+mod packed;
+mod pattern;
+mod persistent;
+mod range;
+mod ranked;
+mod reserve;
+mod retain;
+mod scan;
+mod search;
+mod set;
+mod shared;
+mod subtrie;
+mod suffix;
+#[cfg(feature = "std")]
+mod swap;
+#[cfg(test)]
+mod test_util;
+mod tombstone;
+mod tracked;
 mod trie;
 mod util;
+mod validate;
+mod versioned;
+mod walk;
+mod weak;
+pub use analysis::Analysis;
+pub use augment::{Augment, AugmentedTrie};
+#[cfg(feature = "std")]
+pub use binary::BinaryValue;
+pub use cache::ReadThroughTrie;
+pub use compiled::CompiledRadixTrie;
+#[cfg(feature = "std")]
+pub use concurrent::ConcurrentRadixTrie;
+#[cfg(feature = "std")]
+pub use concurrent_cache::ConcurrentReadThroughTrie;
+pub use cursor::{Cursor, CursorStatus};
+pub use diff::{Diff, DiffEntry};
+pub use entry::{Entry, OccupiedEntry, OccupiedError, TryInsertError, VacantEntry};
+pub use error::TrieError;
+pub use explain::Explanation;
+pub use frozen::FrozenRadixTrie;
+pub use insertion::{InsertionOrder, InsertionOrderTrie};
+#[cfg(feature = "std")]
+pub use ip::{IpPrefixTrie, Routes};
+pub use iter::{
+    Drain, FindByValue, IntoIter, Iter, IterByValue, IterMut, IterWithDepth, KeyBytes, Keys,
+    RevIter, Values, ValuesMut,
+};
+pub use limits::LimitedRadixTrie;
+#[cfg(feature = "std")]
+pub use loader::LoadError;
+pub use matcher::{FindIter, Matcher};
+pub use memory::MemoryStats;
+pub use multimap::RadixMultiMap;
+pub use normalized::NormalizedTrie;
+pub use observed::{ChangeEvent, ObservedTrie};
+pub use overlay::OverlayTrie;
+pub use packed::{PackedIter, PackedMatches};
+pub use pattern::PatternTrie;
+pub use persistent::{PersistentDiff, PersistentIter, PersistentRadixTrie};
+pub use range::Range;
+pub use ranked::RankedTrie;
+pub use reserve::ReserveError;
+pub use scan::{Match, Scan};
+pub use search::SearchOptions;
+pub use set::RadixSet;
+pub use subtrie::{Layered, SubIter, SubKeys, SubTrie, SubTrieMut};
+#[cfg(feature = "std")]
+pub use swap::SwappableRadixTrie;
+pub use tombstone::TombstoneTrie;
+pub use tracked::{Epoch, TrackedTrie};
 pub use trie::RadixTrie;
+pub use validate::InvariantViolation;
+pub use versioned::VersionedRadixTrie;
+pub use walk::Walk;
@@ -0,0 +1,410 @@
+use crate::entry::{Entry, OccupiedError, TryInsertError};
+use crate::error::TrieError;
+use crate::trie::RadixTrie;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::Deref;
+
+/// Refuses a label with the reason.
+/// Shared, so the tries split off or cloned from a limited trie call the same validator
+type KeyValidator = Arc<dyn Fn(&str) -> Result<(), &'static str> + Send + Sync>;
+
+/// The checks applied to every label written to a [`LimitedRadixTrie`]
+#[derive(Clone, Default)]
+struct KeyLimits {
+    /// The maximum length of a label in bytes
+    max_len: Option<usize>,
+    validator: Option<KeyValidator>,
+}
+
+impl KeyLimits {
+    fn check(&self, label: &str) -> Result<(), TrieError> {
+        match self.max_len {
+            _ if label.is_empty() => Err(TrieError::EmptyLabel),
+            Some(max_len) if label.len() > max_len => Err(TrieError::KeyTooLong {
+                len: label.len(),
+                max_len,
+            }),
+            _ => match &self.validator {
+                Some(validator) => {
+                    validator(label).map_err(|reason| TrieError::InvalidKey { reason })
+                }
+                None => Ok(()),
+            },
+        }
+    }
+}
+
+impl fmt::Debug for KeyLimits {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyLimits")
+            .field("max_len", &self.max_len)
+            .field("validator", &self.validator.is_some())
+            .finish()
+    }
+}
+
+/// A trie checking every label written to it against its limits,
+/// such as a maximum length, and refusing the labels that break them with a [`TrieError`].
+///
+/// Every write goes through a checked method, including the bulk ones
+/// [`LimitedRadixTrie::extend`], [`LimitedRadixTrie::merge`] and [`LimitedRadixTrie::import_sorted`],
+/// and a trie loaded from a reader is merged in to be checked.
+/// All read APIs of [`RadixTrie`] are available through `Deref`,
+/// and the tries split off or cloned from a limited trie keep its limits.
+///
+/// # Example
+/// ```rust
+/// use another_radix_trie::{LimitedRadixTrie, TrieError};
+///
+/// let mut trie = LimitedRadixTrie::<usize>::new().with_max_key_len(8);
+/// assert_eq!(trie.insert("label", 5), Ok(None));
+/// assert_eq!(
+///     trie.insert("a much longer label", 6),
+///     Err(TrieError::KeyTooLong { len: 19, max_len: 8 })
+/// );
+/// assert_eq!(trie.find("label"), Some(&5));
+/// assert_eq!(trie.len(), 1);
+/// ```
+#[derive(Debug)]
+pub struct LimitedRadixTrie<T> {
+    trie: RadixTrie<T>,
+    limits: KeyLimits,
+}
+
+impl<T> Default for LimitedRadixTrie<T> {
+    fn default() -> Self {
+        LimitedRadixTrie::new()
+    }
+}
+
+impl<T: Clone> Clone for LimitedRadixTrie<T> {
+    fn clone(&self) -> Self {
+        LimitedRadixTrie {
+            trie: self.trie.clone(),
+            limits: self.limits.clone(),
+        }
+    }
+}
+
+impl<T> Deref for LimitedRadixTrie<T> {
+    type Target = RadixTrie<T>;
+
+    fn deref(&self) -> &RadixTrie<T> {
+        &self.trie
+    }
+}
+
+impl<T> LimitedRadixTrie<T> {
+    /// Construct an empty trie refusing only the empty label
+    pub fn new() -> Self {
+        LimitedRadixTrie {
+            trie: RadixTrie::new(),
+            limits: KeyLimits::default(),
+        }
+    }
+
+    /// Refuses the labels longer than the given number of bytes with [`TrieError::KeyTooLong`]
+    pub fn with_max_key_len(mut self, max_len: usize) -> Self {
+        self.limits.max_len = Some(max_len);
+        self
+    }
+
+    /// Sets the validator called on every label within the length limit.
+    /// A label it refuses is reported as [`TrieError::InvalidKey`] with the returned reason,
+    /// so a registry can refuse bad charsets, patterns, or reserved prefixes before storing them.
+    /// The validator can capture configuration loaded at runtime
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::{LimitedRadixTrie, TrieError};
+    ///
+    /// let reserved = vec![String::from("/internal/"), String::from("/admin/")];
+    /// let mut trie = LimitedRadixTrie::<usize>::new().with_key_validator(move |label| {
+    ///     if reserved.iter().any(|prefix| label.starts_with(prefix.as_str())) {
+    ///         return Err("reserved prefix");
+    ///     }
+    ///     Ok(())
+    /// });
+    /// assert_eq!(trie.insert("/api/users", 1), Ok(None));
+    /// assert_eq!(
+    ///     trie.insert("/internal/admin", 2),
+    ///     Err(TrieError::InvalidKey { reason: "reserved prefix" })
+    /// );
+    /// ```
+    pub fn with_key_validator<F>(mut self, validator: F) -> Self
+    where
+        F: Fn(&str) -> Result<(), &'static str> + Send + Sync + 'static,
+    {
+        self.limits.validator = Some(Arc::new(validator));
+        self
+    }
+
+    /// Checks the label against the limits without writing it.
+    /// The empty label is always refused
+    pub fn check(&self, label: &str) -> Result<(), TrieError> {
+        self.limits.check(label)
+    }
+
+    /// Insert label and associated value into the trie like [`RadixTrie::insert`],
+    /// returning the replaced value, or an error if the label is refused
+    pub fn insert<K: AsRef<str>>(&mut self, label: K, value: T) -> Result<Option<T>, TrieError> {
+        let label = label.as_ref();
+        self.check(label)?;
+        Ok(self.trie.insert(label, value))
+    }
+
+    /// Returns the entry of the label like [`RadixTrie::entry`], or an error if the label is refused
+    pub fn entry<K: AsRef<str>>(&mut self, label: K) -> Result<Entry<'_, T>, TrieError> {
+        let label = label.as_ref();
+        self.check(label)?;
+        Ok(self.trie.locate_entry(label))
+    }
+
+    /// Returns the mutable value of the label, inserting the default value first if it is missing,
+    /// or an error if the label is refused
+    pub fn get_or_default<K: AsRef<str>>(&mut self, label: K) -> Result<&mut T, TrieError>
+    where
+        T: Default,
+    {
+        self.entry(label).map(Entry::or_default)
+    }
+
+    /// Inserts the value if the label holds no value yet like [`RadixTrie::try_insert`].
+    /// A refused label gives the value back in [`TryInsertError::Invalid`]
+    pub fn try_insert<K: AsRef<str>>(
+        &mut self,
+        label: K,
+        value: T,
+    ) -> Result<&mut T, TryInsertError<'_, T>> {
+        match self.entry(label) {
+            Ok(Entry::Occupied(entry)) => {
+                Err(TryInsertError::Occupied(OccupiedError { entry, value }))
+            }
+            Ok(Entry::Vacant(entry)) => Ok(entry.insert(value)),
+            Err(error) => Err(TryInsertError::Invalid { error, value }),
+        }
+    }
+
+    /// Inserts the labels and values one by one, stopping at the first refused label.
+    /// The labels before it are kept
+    pub fn extend<K, I>(&mut self, entries: I) -> Result<(), TrieError>
+    where
+        K: AsRef<str>,
+        I: IntoIterator<Item = (K, T)>,
+    {
+        for (label, value) in entries {
+            self.insert(label, value)?;
+        }
+        Ok(())
+    }
+
+    /// Moves all labels and values of the other trie into this one like [`RadixTrie::merge`].
+    /// Every label of the other trie is checked first, so nothing is merged if one is refused
+    pub fn merge<F>(&mut self, other: RadixTrie<T>, resolve: F) -> Result<(), TrieError>
+    where
+        F: FnMut(T, T) -> T,
+    {
+        let mut refused = Ok(());
+        other.for_each_key_with_buf("", &mut String::new(), |label, _| {
+            if refused.is_ok() {
+                refused = self.check(label);
+            }
+        });
+        refused?;
+        self.trie.merge(other, resolve);
+        Ok(())
+    }
+
+    /// Rebuilds the trie from labels and values sorted by label without duplicates
+    /// like [`RadixTrie::import_sorted`], replacing its contents.
+    /// The pairs are checked as they arrive, and the trie is left unchanged on an error
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::{LimitedRadixTrie, TrieError};
+    ///
+    /// let mut trie = LimitedRadixTrie::new().with_max_key_len(5);
+    /// assert_eq!(trie.import_sorted(vec![("lab", 3), ("label", 5)]), Ok(()));
+    /// assert_eq!(trie.len(), 2);
+    /// let refused = trie.import_sorted(vec![("lab", 3), ("labels", 6)]);
+    /// assert_eq!(refused, Err(TrieError::KeyTooLong { len: 6, max_len: 5 }));
+    /// assert_eq!(trie.find("label"), Some(&5));
+    /// ```
+    pub fn import_sorted<K, I>(&mut self, entries: I) -> Result<(), TrieError>
+    where
+        K: AsRef<str>,
+        I: IntoIterator<Item = (K, T)>,
+    {
+        let limits = &self.limits;
+        let mut refused = Ok(());
+        let checked =
+            entries
+                .into_iter()
+                .map_while(|(label, value)| match limits.check(label.as_ref()) {
+                    Ok(()) => Some((label, value)),
+                    Err(error) => {
+                        refused = Err(error);
+                        None
+                    }
+                });
+        let trie = RadixTrie::import_sorted(checked)?;
+        refused?;
+        self.trie = trie;
+        Ok(())
+    }
+
+    /// Returns the mutable borrowed value associated with related label.
+    /// If the label does not exist, return None
+    pub fn find_mut<K: AsRef<str>>(&mut self, label: K) -> Option<&mut T> {
+        self.trie.find_mut(label)
+    }
+
+    /// Removes the value of the label and returns it
+    pub fn remove<K: AsRef<str>>(&mut self, label: K) -> Option<T> {
+        self.trie.remove(label)
+    }
+
+    /// Removes all labels starting with given prefix and returns them with their values
+    pub fn remove_prefix(&mut self, prefix: &str) -> Vec<(String, T)> {
+        self.trie.remove_prefix(prefix)
+    }
+
+    /// Moves all labels starting with given prefix into a new trie with the same limits,
+    /// like [`RadixTrie::split_off`]
+    pub fn split_off(&mut self, prefix: &str) -> Self {
+        LimitedRadixTrie {
+            trie: self.trie.split_off(prefix),
+            limits: self.limits.clone(),
+        }
+    }
+
+    /// Removes all labels and values, keeping the limits
+    pub fn clear(&mut self) {
+        self.trie.clear()
+    }
+
+    /// Consumes the wrapper and returns the unchecked trie
+    pub fn into_trie(self) -> RadixTrie<T> {
+        self.trie
+    }
+}
+
+#[cfg(test)]
+mod limits_tests {
+    use crate::error::TrieError;
+    use crate::limits::LimitedRadixTrie;
+    use crate::test_util::words_trie;
+    use crate::trie::RadixTrie;
+
+    #[test]
+    fn test_max_key_len() {
+        let mut trie = LimitedRadixTrie::<usize>::new().with_max_key_len(6);
+        assert_eq!(trie.insert("Wonder", 1), Ok(None));
+        let too_long = TrieError::KeyTooLong { len: 9, max_len: 6 };
+        assert_eq!(trie.insert("Wonderful", 2), Err(too_long));
+        assert_eq!(trie.entry("Wonderful").err(), Some(too_long));
+        assert_eq!(trie.get_or_default("Wonderful").err(), Some(too_long));
+        assert_eq!(trie.try_insert("Wonderful", 2).unwrap_err().into_value(), 2);
+        // the length is in bytes, so three chars of three bytes are too long
+        assert!(trie.insert("多倫多", 3).is_err());
+        assert_eq!(trie.insert("", 3), Err(TrieError::EmptyLabel));
+        assert_eq!(trie.len(), 1);
+        assert_eq!(trie.find("Wonderful"), None);
+        assert_eq!(
+            too_long.to_string(),
+            "label of 9 bytes is longer than the maximum of 6"
+        );
+    }
+
+    #[test]
+    fn test_bulk_writes_are_checked() {
+        let mut trie = LimitedRadixTrie::<usize>::new().with_max_key_len(6);
+        let refused = trie.extend(vec![("Won", 3), ("Wonderful", 9), ("Axes", 4)]);
+        assert_eq!(refused, Err(TrieError::KeyTooLong { len: 9, max_len: 6 }));
+        assert_eq!(trie.keys().collect::<Vec<_>>(), vec!["Won"]);
+        // a loaded trie is checked as a whole before it is merged
+        assert!(trie.merge(words_trie(), |mine, _| mine).is_err());
+        assert_eq!(trie.len(), 1);
+        let mut short = RadixTrie::new();
+        short.insert("World", 5);
+        short.insert("Won", 0);
+        assert_eq!(trie.merge(short, |mine, theirs| mine + theirs), Ok(()));
+        assert_eq!(trie.find("Won"), Some(&3));
+        assert_eq!(trie.len(), 2);
+        let words = words_trie();
+        assert!(trie
+            .import_sorted(words.export().map(|(label, value)| (label, *value)))
+            .is_err());
+        assert_eq!(trie.len(), 2);
+    }
+
+    #[test]
+    fn test_derived_tries_keep_limits() {
+        let mut trie = LimitedRadixTrie::<usize>::new().with_max_key_len(6);
+        trie.extend(vec![("Won", 3), ("Wonder", 6), ("World", 5)])
+            .unwrap();
+        let mut cloned = trie.clone();
+        assert!(cloned.insert("Wonderful", 9).is_err());
+        let mut split = trie.split_off("Won");
+        assert_eq!(split.len(), 2);
+        assert!(split.insert("Wonderful", 9).is_err());
+        assert_eq!(split.insert("Wont", 4), Ok(None));
+        *trie.find_mut("World").unwrap() += 1;
+        assert_eq!(trie.remove("World"), Some(6));
+        trie.clear();
+        assert!(trie.insert("Wonderful", 9).is_err());
+        assert_eq!(split.into_trie().len(), 3);
+    }
+
+    #[test]
+    fn test_key_validator() {
+        let ascii = |label: &str| {
+            if label.is_ascii() {
+                Ok(())
+            } else {
+                Err("not ascii")
+            }
+        };
+        let mut trie = LimitedRadixTrie::<usize>::new()
+            .with_max_key_len(6)
+            .with_key_validator(ascii);
+        let invalid = TrieError::InvalidKey {
+            reason: "not ascii",
+        };
+        assert_eq!(trie.insert("Won", 1), Ok(None));
+        assert_eq!(trie.insert("多", 2), Err(invalid));
+        assert_eq!(trie.entry("Wo多").err(), Some(invalid));
+        // the length is checked before the validator is called
+        assert!(matches!(
+            trie.insert("Wonderful多", 3),
+            Err(TrieError::KeyTooLong { .. })
+        ));
+        assert_eq!(trie.len(), 1);
+        assert_eq!(invalid.to_string(), "invalid label: not ascii");
+    }
+
+    #[test]
+    fn test_key_validator_captures_config() {
+        // as if loaded from a config file
+        let config = "/internal/ /admin";
+        let reserved = config.split(' ').map(String::from).collect::<Vec<_>>();
+        let trie = LimitedRadixTrie::<usize>::new().with_key_validator(move |label| match reserved
+            .iter()
+            .any(|prefix| label.starts_with(prefix.as_str()))
+        {
+            true => Err("reserved prefix"),
+            false => Ok(()),
+        });
+        let mut split = trie.clone().split_off("/");
+        let refused = TrieError::InvalidKey {
+            reason: "reserved prefix",
+        };
+        assert_eq!(split.insert("/administrator", 1), Err(refused));
+        let mut short = RadixTrie::new();
+        short.insert("/internal/metrics", 2);
+        assert_eq!(split.merge(short, |mine, _| mine), Err(refused));
+        assert_eq!(split.insert("/api", 3), Ok(None));
+        assert!(format!("{:?}", trie).contains("validator: true"));
+    }
+}
@@ -0,0 +1,129 @@
+use crate::build::SortedBuilder;
+use crate::trie::RadixTrie;
+use std::fmt;
+use std::io::{self, BufRead};
+
+/// Errors produced while loading a trie from a `key<TAB>value` source
+#[derive(Debug)]
+pub enum LoadError<E> {
+    /// The underlying reader failed
+    Io(io::Error),
+    /// The line has no tab separator or the key is empty.
+    /// The line number starts from 1
+    Malformed { line: usize },
+    /// The value parser rejected the value of a line.
+    /// The line number starts from 1
+    InvalidValue { line: usize, error: E },
+}
+
+impl<E: fmt::Display> fmt::Display for LoadError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Io(error) => write!(f, "failed to read input: {}", error),
+            LoadError::Malformed { line } => {
+                write!(f, "line {}: expected `key<TAB>value`", line)
+            }
+            LoadError::InvalidValue { line, error } => {
+                write!(f, "line {}: invalid value: {}", line, error)
+            }
+        }
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> std::error::Error for LoadError<E> {}
+
+impl<E> From<io::Error> for LoadError<E> {
+    fn from(error: io::Error) -> Self {
+        LoadError::Io(error)
+    }
+}
+
+impl<T> RadixTrie<T> {
+    /// Build a trie from a stream of `key<TAB>value` lines.
+    /// Each value is converted with the given parser. Empty lines are skipped,
+    /// and the first malformed line stops the loading with its line number.
+    /// Lines sorted by key are built bottom up without searching from the root,
+    /// and only the lines arriving out of order are inserted one by one.
+    /// A repeated key keeps the value of its last line
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let input = "ON\t647\nON2\t416\n";
+    /// let trie = RadixTrie::<usize>::from_tsv(input.as_bytes(), |value| value.parse()).unwrap();
+    /// assert_eq!(trie.find("ON2"), Some(&416));
+    /// ```
+    pub fn from_tsv<R, F, E>(reader: R, mut parse: F) -> Result<Self, LoadError<E>>
+    where
+        R: BufRead,
+        F: FnMut(&str) -> Result<T, E>,
+    {
+        let mut sorted = SortedBuilder::new();
+        let mut unsorted = RadixTrie::new();
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            let line = line.strip_suffix('\r').unwrap_or(&line);
+            if line.is_empty() {
+                continue;
+            }
+            let line_number = index + 1;
+            let (key, value) = match line.split_once('\t') {
+                Some((key, value)) if !key.is_empty() => (key, value),
+                _ => return Err(LoadError::Malformed { line: line_number }),
+            };
+            let value = parse(value).map_err(|error| LoadError::InvalidValue {
+                line: line_number,
+                error,
+            })?;
+            if let Err(value) = sorted.push(key, value) {
+                unsorted.insert(key, value);
+            }
+        }
+        let mut trie = sorted.finish();
+        // an unsorted line always comes after the sorted line with the same key
+        trie.merge(unsorted, |_, later| later);
+        Ok(trie)
+    }
+}
+
+#[cfg(test)]
+mod loader_tests {
+    use crate::loader::LoadError;
+    use crate::trie::RadixTrie;
+
+    #[test]
+    fn test_from_tsv() {
+        let input = "Won\t3\r\n\nWonder\t6\nWorld\t5\n";
+        let trie = RadixTrie::<usize>::from_tsv(input.as_bytes(), |value| value.parse()).unwrap();
+        assert_eq!(trie.find("Won"), Some(&3));
+        assert_eq!(trie.find("Wonder"), Some(&6));
+        assert_eq!(trie.find("World"), Some(&5));
+    }
+
+    #[test]
+    fn test_from_tsv_out_of_order() {
+        let input = "Wo\t1\nWon\t2\nAxes\t3\nWonder\t4\nWon\t5\nWorld\t6\nAx\t7\nAxes\t8\n";
+        let trie = RadixTrie::<usize>::from_tsv(input.as_bytes(), |value| value.parse()).unwrap();
+        let mut expected = RadixTrie::new();
+        for line in input.lines() {
+            let (key, value) = line.split_once('\t').unwrap();
+            expected.insert(key, value.parse::<usize>().unwrap());
+        }
+        assert_eq!(trie, expected);
+        assert_eq!(format!("{:?}", trie.entry), format!("{:?}", expected.entry));
+        assert_eq!(trie.validate(), Ok(()));
+        assert_eq!(trie.find("Won"), Some(&5));
+        assert_eq!(trie.find("Axes"), Some(&8));
+    }
+
+    #[test]
+    fn test_from_tsv_reports_line_numbers() {
+        let missing_tab = "Won\t3\nWonder 6\n";
+        let res = RadixTrie::<usize>::from_tsv(missing_tab.as_bytes(), |value| value.parse());
+        assert!(matches!(res, Err(LoadError::Malformed { line: 2 })));
+
+        let bad_value = "Won\t3\n\nWorld\tfive\n";
+        let res = RadixTrie::<usize>::from_tsv(bad_value.as_bytes(), |value| value.parse());
+        assert!(matches!(res, Err(LoadError::InvalidValue { line: 3, .. })));
+    }
+}
@@ -0,0 +1,150 @@
+use crate::element::Element;
+use crate::trie::RadixTrie;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::Infallible;
+
+/// An element being converted on the current path of `try_map`
+struct Frame<T, U> {
+    label: String,
+    value: Option<U>,
+    /// The children not converted yet
+    children: alloc::vec::IntoIter<Element<T>>,
+    /// The converted children
+    mapped: Vec<Element<U>>,
+}
+
+impl<T, U> Frame<T, U> {
+    fn new(label: String, value: Option<U>, children: Vec<Element<T>>) -> Self {
+        Frame {
+            label,
+            value,
+            mapped: Vec::with_capacity(children.len()),
+            children: children.into_iter(),
+        }
+    }
+}
+
+impl<T> RadixTrie<T> {
+    /// Consumes the trie and converts every value with the closure, keeping the labels and the structure.
+    /// The closure is called in lexicographic order of labels
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("lab", 3);
+    /// trie.insert("label", 5);
+    /// let trie = trie.map(|label, value| format!("{}={}", label, value));
+    /// assert_eq!(trie.find("label"), Some(&String::from("label=5")));
+    /// ```
+    pub fn map<U, F>(self, mut convert: F) -> RadixTrie<U>
+    where
+        F: FnMut(&str, T) -> U,
+    {
+        match self.try_map(|label, value| Ok::<_, Infallible>(convert(label, value))) {
+            Ok(trie) => trie,
+            Err(never) => match never {},
+        }
+    }
+
+    /// Consumes the trie and converts every value with the fallible closure, keeping the labels and the structure.
+    /// The closure is called in lexicographic order of labels,
+    /// and the first error stops the conversion and is returned
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<&str>::new();
+    /// trie.insert("lab", "3");
+    /// trie.insert("label", "5");
+    /// let parsed = trie.clone().try_map(|_, value| value.parse::<usize>());
+    /// assert_eq!(parsed.unwrap().find("label"), Some(&5));
+    /// trie.insert("lazy", "four");
+    /// assert!(trie.try_map(|_, value| value.parse::<usize>()).is_err());
+    /// ```
+    pub fn try_map<U, E, F>(self, mut convert: F) -> Result<RadixTrie<U>, E>
+    where
+        F: FnMut(&str, T) -> Result<U, E>,
+    {
+        let len = self.len;
+        let (_, _, children) = self.into_entry().unpack();
+        let mut label = String::new();
+        let mut stack = vec![Frame::new(String::new(), None, children)];
+        while let Some(top) = stack.last_mut() {
+            if let Some(child) = top.children.next() {
+                let (child_label, value, children) = child.unpack();
+                label.push_str(&child_label);
+                let value = match value {
+                    Some(value) => Some(convert(&label, value)?),
+                    None => None,
+                };
+                stack.push(Frame::new(child_label, value, children));
+                continue;
+            }
+            let finished = stack.pop().unwrap();
+            match stack.last_mut() {
+                Some(parent) => {
+                    label.truncate(label.len() - finished.label.len());
+                    let element = Element::pack(finished.label, finished.value, finished.mapped);
+                    parent.mapped.push(element);
+                }
+                None => {
+                    let mut trie = RadixTrie::new();
+                    *trie.entry.children_mut() = finished.mapped;
+                    trie.len = len;
+                    return Ok(trie);
+                }
+            }
+        }
+        unreachable!("The root frame is always finished last")
+    }
+}
+
+#[cfg(test)]
+mod map_tests {
+    use crate::test_util::mixed_words_trie;
+    use crate::trie::RadixTrie;
+
+    #[test]
+    fn test_map_keeps_structure() {
+        let trie = mixed_words_trie();
+        let node_count = trie.node_count();
+        let expected = trie
+            .iter()
+            .map(|(label, value)| (label.clone(), format!("{}{}", label, value)))
+            .collect::<Vec<_>>();
+        let mut visited = vec![];
+        let mapped = trie.map(|label, value| {
+            visited.push(label.to_owned());
+            format!("{}{}", label, value)
+        });
+        let res = mapped
+            .iter()
+            .map(|(label, value)| (label, value.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(res, expected);
+        assert_eq!(mapped.keys().collect::<Vec<_>>(), visited);
+        assert_eq!(mapped.len(), expected.len());
+        assert_eq!(mapped.node_count(), node_count);
+    }
+
+    #[test]
+    fn test_try_map_stops_at_error() {
+        let trie = mixed_words_trie();
+        let mut calls = 0;
+        let res = trie.try_map(|label, value| {
+            calls += 1;
+            if label == "Wonder" {
+                Err(value)
+            } else {
+                Ok(value * 2)
+            }
+        });
+        assert_eq!(res.err(), Some(6));
+        // Axes, Won, then Wonder
+        assert_eq!(calls, 3);
+        let empty = RadixTrie::<usize>::new().try_map(|_, value| Ok::<_, ()>(value));
+        assert!(empty.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,247 @@
+use crate::scan::Match;
+use crate::trie::RadixTrie;
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+
+const ROOT: usize = 0;
+
+/// A state of the automaton, which is a prefix of one or more labels
+struct State {
+    /// Transitions sorted by byte
+    next: Vec<(u8, usize)>,
+    /// The state of the longest proper suffix of this prefix that is also a prefix of a label
+    fail: usize,
+    /// Index of the label ending at this state
+    output: Option<usize>,
+    /// The nearest state on the failure chain with an output
+    output_link: Option<usize>,
+}
+
+impl State {
+    fn new() -> Self {
+        State {
+            next: vec![],
+            fail: ROOT,
+            output: None,
+            output_link: None,
+        }
+    }
+
+    fn goto(&self, byte: u8) -> Option<usize> {
+        self.next
+            .binary_search_by_key(&byte, |(byte, _)| *byte)
+            .ok()
+            .map(|index| self.next[index].1)
+    }
+}
+
+/// An Aho-Corasick automaton over the labels of a trie,
+/// finding all labels occurring anywhere in a text in a single pass.
+///
+/// Created by [`RadixTrie::compile_matcher`]
+pub struct Matcher<'a, T> {
+    states: Vec<State>,
+    /// Byte length and value of every label
+    labels: Vec<(usize, &'a T)>,
+}
+
+impl<'a, T> Matcher<'a, T> {
+    fn new(trie: &'a RadixTrie<T>) -> Self {
+        let mut states = vec![State::new()];
+        let mut labels = Vec::with_capacity(trie.len());
+        for (label, value) in trie.iter() {
+            let mut state = ROOT;
+            for &byte in label.as_bytes() {
+                state = match states[state].goto(byte) {
+                    Some(next) => next,
+                    None => {
+                        let next = states.len();
+                        states.push(State::new());
+                        let transitions = &mut states[state].next;
+                        let index = transitions.partition_point(|(other, _)| *other < byte);
+                        transitions.insert(index, (byte, next));
+                        next
+                    }
+                };
+            }
+            states[state].output = Some(labels.len());
+            labels.push((label.len(), value));
+        }
+        // breadth first, so the failure of a state is resolved before its children
+        let mut queue = states[ROOT]
+            .next
+            .iter()
+            .map(|(_, child)| *child)
+            .collect::<VecDeque<_>>();
+        while let Some(state) = queue.pop_front() {
+            for index in 0..states[state].next.len() {
+                let (byte, child) = states[state].next[index];
+                let fail = Self::transit(&states, states[state].fail, byte);
+                states[child].fail = fail;
+                states[child].output_link = match states[fail].output {
+                    Some(_) => Some(fail),
+                    None => states[fail].output_link,
+                };
+                queue.push_back(child);
+            }
+        }
+        Matcher { states, labels }
+    }
+
+    /// Follow the byte from the state, falling back along the failure links
+    fn transit(states: &[State], mut state: usize, byte: u8) -> usize {
+        loop {
+            if let Some(next) = states[state].goto(byte) {
+                return next;
+            }
+            if state == ROOT {
+                return ROOT;
+            }
+            state = states[state].fail;
+        }
+    }
+
+    /// Returns a lazy iterator over all occurrences of the labels in the text, including overlapping ones.
+    /// Occurrences are ordered by where they end,
+    /// and the longer one comes first when several end at the same byte
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("he", 0);
+    /// trie.insert("she", 1);
+    /// trie.insert("hers", 2);
+    /// let matcher = trie.compile_matcher();
+    /// let res = matcher
+    ///     .find_iter("ushers")
+    ///     .map(|found| (found.start, found.text))
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(res, vec![(1, "she"), (2, "he"), (2, "hers")]);
+    /// ```
+    pub fn find_iter<'b>(&'b self, text: &'b str) -> FindIter<'b, T> {
+        FindIter {
+            states: &self.states,
+            labels: &self.labels,
+            text,
+            position: 0,
+            state: ROOT,
+            pending: None,
+        }
+    }
+
+    /// Returns true if any label occurs in the text
+    pub fn is_match(&self, text: &str) -> bool {
+        self.find_iter(text).next().is_some()
+    }
+}
+
+/// Iterator over the occurrences of labels in a text.
+///
+/// Created by [`Matcher::find_iter`]
+pub struct FindIter<'a, T> {
+    states: &'a [State],
+    labels: &'a [(usize, &'a T)],
+    text: &'a str,
+    /// Number of bytes consumed
+    position: usize,
+    state: usize,
+    /// The next state on the output chain to report before consuming more bytes
+    pending: Option<usize>,
+}
+
+impl<'a, T> Iterator for FindIter<'a, T> {
+    type Item = Match<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(state) = self.pending {
+                self.pending = self.states[state].output_link;
+                let (len, value) = self.labels[self.states[state].output.unwrap()];
+                let start = self.position - len;
+                return Some(Match {
+                    start,
+                    end: self.position,
+                    text: &self.text[start..self.position],
+                    value,
+                });
+            }
+            let byte = *self.text.as_bytes().get(self.position)?;
+            self.position += 1;
+            self.state = Matcher::<T>::transit(self.states, self.state, byte);
+            let state = &self.states[self.state];
+            self.pending = match state.output {
+                Some(_) => Some(self.state),
+                None => state.output_link,
+            };
+        }
+    }
+}
+
+impl<T> RadixTrie<T> {
+    /// Compile the labels into a matcher finding all of them inside a text in a single pass.
+    /// The matcher borrows the values of the trie
+    pub fn compile_matcher(&self) -> Matcher<'_, T> {
+        Matcher::new(self)
+    }
+}
+
+#[cfg(test)]
+mod matcher_tests {
+    use crate::trie::RadixTrie;
+
+    /// Find all occurrences by checking every label at every offset
+    fn naive(labels: &[&str], text: &str) -> Vec<(usize, usize)> {
+        let mut res = vec![];
+        for end in (1..=text.len()).filter(|end| text.is_char_boundary(*end)) {
+            let mut found = labels
+                .iter()
+                .filter(|label| text[..end].ends_with(*label))
+                .map(|label| (end - label.len(), end))
+                .collect::<Vec<_>>();
+            found.sort();
+            res.extend(found);
+        }
+        res
+    }
+
+    #[test]
+    fn test_matches_naive_search() {
+        let labels = [
+            "a",
+            "ab",
+            "bab",
+            "bc",
+            "bca",
+            "c",
+            "caa",
+            "多",
+            "倫多",
+            "多倫多",
+        ];
+        let mut trie = RadixTrie::<usize>::new();
+        for (index, label) in labels.iter().enumerate() {
+            trie.insert(label, index);
+        }
+        let matcher = trie.compile_matcher();
+        for text in &["abccab", "bcaab", "xx", "", "多倫多倫多", "a多倫bca多"] {
+            let res = matcher
+                .find_iter(text)
+                .map(|found| {
+                    assert_eq!(labels[*found.value], found.text);
+                    (found.start, found.end)
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(res, naive(&labels, text), "{}", text);
+        }
+        assert!(matcher.is_match("xxbcxx"));
+        assert!(!matcher.is_match("xyz"));
+    }
+
+    #[test]
+    fn test_empty_matcher() {
+        let trie = RadixTrie::<usize>::new();
+        let matcher = trie.compile_matcher();
+        assert_eq!(matcher.find_iter("abc").next(), None);
+    }
+}
@@ -0,0 +1,172 @@
+use crate::element::Element;
+use crate::trie::RadixTrie;
+use core::mem::size_of;
+
+/// Memory used by a trie, returned by [`RadixTrie::memory_usage`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryStats {
+    /// Number of elements holding a value
+    pub value_elements: usize,
+    /// Number of elements without value, excluding the root
+    pub node_elements: usize,
+    /// Bytes used by the labels of all elements
+    pub label_bytes: usize,
+    /// Bytes allocated for the labels of all elements
+    pub label_capacity: usize,
+    /// Number of children stored by all elements
+    pub children_len: usize,
+    /// Number of children the allocated children vectors can hold
+    pub children_capacity: usize,
+    /// Estimated bytes allocated on the heap, covering the labels, the children vectors,
+    /// and the heap bytes reported for the values.
+    /// Allocator overhead is not included
+    pub heap_bytes: usize,
+}
+
+impl<T> RadixTrie<T> {
+    /// Reports the element counts, the label and children allocations,
+    /// and an estimate of the heap bytes used by the trie.
+    /// Values are counted by their inline size only; use
+    /// [`RadixTrie::memory_usage_with`] when they own heap memory
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("ON", 3);
+    /// trie.insert("ON20", 4);
+    /// let stats = trie.memory_usage();
+    /// assert_eq!((stats.value_elements, stats.node_elements), (2, 0));
+    /// assert_eq!(stats.label_bytes, 4);
+    /// assert!(stats.heap_bytes >= stats.label_capacity);
+    /// ```
+    pub fn memory_usage(&self) -> MemoryStats {
+        self.memory_usage_with(|_| 0)
+    }
+
+    /// Same as [`RadixTrie::memory_usage`], with the heap bytes owned by every value
+    /// reported by the closure and added to the estimate
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<String>::new();
+    /// trie.insert("lab", String::from("laboratory"));
+    /// let inline = trie.memory_usage().heap_bytes;
+    /// let total = trie.memory_usage_with(|value| value.capacity()).heap_bytes;
+    /// assert_eq!(total - inline, 10);
+    /// ```
+    pub fn memory_usage_with<F>(&self, mut value_heap_bytes: F) -> MemoryStats
+    where
+        F: FnMut(&T) -> usize,
+    {
+        let mut stats = MemoryStats::default();
+        let mut stack = vec![&self.entry];
+        while let Some(element) = stack.pop() {
+            match element {
+                Element::Value { value, .. } => {
+                    stats.value_elements += 1;
+                    stats.heap_bytes += value_heap_bytes(value);
+                }
+                Element::Node { .. } => stats.node_elements += 1,
+                Element::Base { .. } => (),
+            }
+            let (label, children) = (element.label(), element.children());
+            stats.label_bytes += label.len();
+            stats.label_capacity += element.label_capacity();
+            stats.children_len += children.len();
+            stats.children_capacity += children.capacity();
+            stack.extend(children);
+        }
+        stats.heap_bytes +=
+            stats.label_capacity + stats.children_capacity * size_of::<Element<T>>();
+        stats
+    }
+
+    /// Releases the unused capacity of every label and children vector,
+    /// for example once a trie built by many inserts is only going to be read.
+    /// Later inserts allocate again as needed
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// for (index, word) in ["a", "b", "c", "d", "e"].iter().enumerate() {
+    ///     trie.insert(word, index);
+    /// }
+    /// trie.shrink_to_fit();
+    /// let stats = trie.memory_usage();
+    /// assert_eq!(stats.children_capacity, stats.children_len);
+    /// assert_eq!(stats.label_capacity, stats.label_bytes);
+    /// ```
+    pub fn shrink_to_fit(&mut self) {
+        let mut stack = vec![&mut self.entry];
+        while let Some(element) = stack.pop() {
+            element.shrink_to_fit();
+            stack.extend(element.children_mut());
+        }
+    }
+}
+
+#[cfg(test)]
+mod memory_tests {
+    use crate::element::Element;
+    use crate::trie::RadixTrie;
+    use core::mem::size_of;
+
+    #[test]
+    fn test_memory_usage() {
+        let mut trie = RadixTrie::<String>::new();
+        assert_eq!(trie.memory_usage().heap_bytes, 0);
+        for word in &["Won", "Wonder", "World", "Axes", "多倫多"] {
+            trie.insert(word, word.to_string());
+        }
+        let stats = trie.memory_usage();
+        assert_eq!(stats.value_elements, trie.len());
+        // "Wo" is shared by "Won" and "World"
+        assert_eq!(stats.node_elements, 1);
+        assert_eq!(
+            stats.value_elements + stats.node_elements,
+            trie.node_count()
+        );
+        assert_eq!(stats.children_len, trie.node_count());
+        assert_eq!(
+            stats.label_bytes,
+            ["Wo", "n", "der", "rld", "Axes", "多倫多"]
+                .iter()
+                .map(|label| label.len())
+                .sum::<usize>()
+        );
+        assert!(stats.label_capacity >= stats.label_bytes);
+        assert!(stats.children_capacity >= stats.children_len);
+        assert_eq!(
+            stats.heap_bytes,
+            stats.label_capacity + stats.children_capacity * size_of::<Element<String>>()
+        );
+        let with_values = trie.memory_usage_with(|value| value.capacity());
+        let value_bytes = trie.values().map(|value| value.capacity()).sum::<usize>();
+        assert_eq!(with_values.heap_bytes, stats.heap_bytes + value_bytes);
+    }
+
+    #[test]
+    fn test_shrink_to_fit() {
+        let mut trie = RadixTrie::<usize>::new();
+        for index in 0..500 {
+            trie.insert(format!("{:x}", index * 7919), index);
+        }
+        for index in 0..250 {
+            trie.remove(format!("{:x}", index * 7919));
+        }
+        let before = trie.memory_usage();
+        let expected = trie.to_sorted_vec();
+        trie.shrink_to_fit();
+        let after = trie.memory_usage();
+        assert_eq!(after.children_capacity, after.children_len);
+        assert_eq!(after.label_capacity, after.label_bytes);
+        assert!(after.heap_bytes < before.heap_bytes);
+        assert_eq!(trie.to_sorted_vec(), expected);
+        assert_eq!(trie.validate(), Ok(()));
+        trie.insert("ffff", 0);
+        assert_eq!(trie.find("ffff"), Some(&0));
+    }
+}
@@ -0,0 +1,243 @@
+use crate::element::Element;
+use crate::trie::RadixTrie;
+use crate::util;
+use alloc::borrow::ToOwned;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+impl<T> RadixTrie<T> {
+    /// Moves all labels and values of the other trie into this one.
+    /// Children lists are merged level by level, so shared prefixes are joined
+    /// once instead of reinserting every label.
+    /// When both tries hold a value for a label, `resolve` is called with
+    /// the value of this trie and the value of the other trie
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("lab", 3);
+    /// trie.insert("label", 5);
+    /// let mut other = RadixTrie::<usize>::new();
+    /// other.insert("label", 1);
+    /// other.insert("lazy", 4);
+    /// trie.merge(other, |mine, theirs| mine + theirs);
+    /// assert_eq!(trie.find("label"), Some(&6));
+    /// assert_eq!(trie.find("lazy"), Some(&4));
+    /// assert_eq!(trie.len(), 3);
+    /// ```
+    pub fn merge<F>(&mut self, other: RadixTrie<T>, mut resolve: F)
+    where
+        F: FnMut(T, T) -> T,
+    {
+        let mut collisions = 0;
+        let mut resolve = |mine: Option<T>, theirs: Option<T>| match (mine, theirs) {
+            (Some(mine), Some(theirs)) => {
+                collisions += 1;
+                Some(resolve(mine, theirs))
+            }
+            (mine, theirs) => mine.or(theirs),
+        };
+        let other_len = other.len;
+        let (_, _, incoming) = other.into_entry().unpack();
+        let mut entry = core::mem::take(self.entry.children_mut());
+        let pending = merge_level(&mut entry, incoming, &mut resolve);
+        let mut stack = vec![MergeFrame {
+            entry,
+            pending,
+            descended: 0,
+        }];
+        loop {
+            let top = stack.last_mut().unwrap();
+            if let Some((target_index, incoming)) = top.pending.pop() {
+                let mut entry = core::mem::take(top.entry[target_index].children_mut());
+                let pending = merge_level(&mut entry, incoming, &mut resolve);
+                top.descended = target_index;
+                stack.push(MergeFrame {
+                    entry,
+                    pending,
+                    descended: 0,
+                });
+                continue;
+            }
+            let finished = stack.pop().unwrap();
+            match stack.last_mut() {
+                Some(parent) => {
+                    // the counts below are final once the children are merged
+                    let target = &mut parent.entry[parent.descended];
+                    *target.children_mut() = finished.entry;
+                    target.recount();
+                }
+                None => {
+                    *self.entry.children_mut() = finished.entry;
+                    break;
+                }
+            }
+        }
+        self.len += other_len;
+        self.len -= collisions;
+    }
+}
+
+/// An entry taken out of the trie by `merge` while elements are merged below it
+struct MergeFrame<T> {
+    entry: Vec<Element<T>>,
+    /// The indices of the children still to be merged with the paired elements
+    pending: Vec<(usize, Vec<Element<T>>)>,
+    /// The index of the child whose children are in the frame above
+    descended: usize,
+}
+
+/// Merge the incoming elements into the entry, both sorted by the first char of their labels.
+/// Returns the indices of the elements whose children still have to be merged with
+/// the returned elements, in ascending order
+fn merge_level<T, F>(
+    entry: &mut Vec<Element<T>>,
+    incoming: Vec<Element<T>>,
+    resolve: &mut F,
+) -> Vec<(usize, Vec<Element<T>>)>
+where
+    F: FnMut(Option<T>, Option<T>) -> Option<T>,
+{
+    let existing = core::mem::take(entry);
+    let mut pending = vec![];
+    let mut existing = existing.into_iter().peekable();
+    let mut incoming = incoming.into_iter().peekable();
+    loop {
+        let order = match (existing.peek(), incoming.peek()) {
+            (Some(mine), Some(theirs)) => {
+                util::first_char(mine.label()).cmp(&util::first_char(theirs.label()))
+            }
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => break,
+        };
+        let (mine, theirs) = match order {
+            Ordering::Less => {
+                entry.extend(existing.next());
+                continue;
+            }
+            Ordering::Greater => {
+                entry.extend(incoming.next());
+                continue;
+            }
+            Ordering::Equal => (existing.next().unwrap(), incoming.next().unwrap()),
+        };
+        let (mine_label, mine_value, mine_children) = mine.unpack();
+        let (theirs_label, theirs_value, theirs_children) = theirs.unpack();
+        let shared_len = util::longest_shared_prefix(&mine_label, &theirs_label).len();
+        let merged = if shared_len == mine_label.len() && shared_len == theirs_label.len() {
+            pending.push((entry.len(), theirs_children));
+            let value = resolve(mine_value, theirs_value);
+            Element::pack(mine_label, value, mine_children)
+        } else if shared_len == mine_label.len() {
+            let theirs = Element::pack(
+                theirs_label[shared_len..].to_owned(),
+                theirs_value,
+                theirs_children,
+            );
+            pending.push((entry.len(), vec![theirs]));
+            Element::pack(mine_label, mine_value, mine_children)
+        } else if shared_len == theirs_label.len() {
+            let mine = Element::pack(
+                mine_label[shared_len..].to_owned(),
+                mine_value,
+                mine_children,
+            );
+            pending.push((entry.len(), theirs_children));
+            Element::pack(theirs_label, theirs_value, vec![mine])
+        } else {
+            let mine = Element::pack(
+                mine_label[shared_len..].to_owned(),
+                mine_value,
+                mine_children,
+            );
+            let theirs = Element::pack(
+                theirs_label[shared_len..].to_owned(),
+                theirs_value,
+                theirs_children,
+            );
+            let mut children = vec![mine, theirs];
+            children.sort_by(|e1, e2| e1.label().cmp(e2.label()));
+            Element::pack(mine_label[..shared_len].to_owned(), None, children)
+        };
+        entry.push(merged);
+    }
+    pending
+}
+
+#[cfg(test)]
+mod merge_tests {
+    use crate::test_util::Lcg;
+    use crate::trie::RadixTrie;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_merge_matches_insertion() {
+        let mine = ["Won", "Wonderful", "World", "Axes", "Toronto"];
+        let theirs = ["Wonder", "Wo", "Axes", "Toronto多倫多", "Ax", "Brampton"];
+        let mut trie = RadixTrie::<usize>::new();
+        let mut expected = RadixTrie::<usize>::new();
+        for word in &mine {
+            trie.insert(word, 1);
+            expected.insert(word, 1);
+        }
+        let mut other = RadixTrie::<usize>::new();
+        for word in &theirs {
+            other.insert(word, 2);
+            let value = expected.find(word).map_or(2, |value| value + 2);
+            expected.insert(word, value);
+        }
+        trie.merge(other, |mine, theirs| mine + theirs);
+        assert_eq!(format!("{:?}", trie.entry), format!("{:?}", expected.entry));
+        assert_eq!(trie.len(), expected.len());
+        assert_eq!(trie.find("Axes"), Some(&3));
+    }
+
+    #[test]
+    fn test_random_merge_matches_btree_map() {
+        let mut rng = Lcg(7);
+        for _ in 0..50 {
+            let mut tries = vec![];
+            let mut model = BTreeMap::new();
+            for shard in 0..2 {
+                let mut trie = RadixTrie::new();
+                for _ in 0..rng.next(40) {
+                    let key = rng.label();
+                    trie.insert(&key, vec![shard]);
+                }
+                tries.push(trie);
+            }
+            for (key, value) in tries[0].iter().chain(tries[1].iter()) {
+                model
+                    .entry(key)
+                    .or_insert_with(Vec::new)
+                    .extend(value.iter().copied());
+            }
+            let other = tries.pop().unwrap();
+            let mut trie = tries.pop().unwrap();
+            trie.merge(other, |mut mine, theirs| {
+                mine.extend(theirs);
+                mine
+            });
+            let mut expected = RadixTrie::new();
+            for (key, value) in &model {
+                expected.insert(key, value.clone());
+            }
+            assert_eq!(format!("{:?}", trie.entry), format!("{:?}", expected.entry));
+            assert_eq!(trie.len(), model.len());
+        }
+    }
+
+    #[test]
+    fn test_merge_empty() {
+        let mut trie = RadixTrie::<usize>::new();
+        trie.merge(RadixTrie::new(), |mine, _| mine);
+        assert!(trie.is_empty());
+        let mut other = RadixTrie::new();
+        other.insert("lab", 3);
+        trie.merge(other, |mine, _| mine);
+        assert_eq!(trie.find("lab"), Some(&3));
+        assert_eq!(trie.len(), 1);
+    }
+}
@@ -0,0 +1,67 @@
+use crate::trie::RadixTrie;
+use alloc::vec::Vec;
+
+impl<M: Ord, T> RadixTrie<Vec<(M, T)>> {
+    /// Insert a value with its metric under the label.
+    /// A label can hold several values, kept in ascending order of their metrics.
+    /// Inserting under the empty label does nothing, like [`RadixTrie::insert`]
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut routes = RadixTrie::<Vec<(u32, &str)>>::new();
+    /// routes.insert_with_metric("10.1", 20, "static");
+    /// routes.insert_with_metric("10.1", 10, "ospf");
+    /// routes.insert_with_metric("10", 1, "default");
+    /// assert_eq!(routes.lookup_best("10.1.2.3"), Some(("10.1", &10, &"ospf")));
+    /// ```
+    pub fn insert_with_metric(&mut self, label: &str, metric: M, value: T) {
+        if label.is_empty() {
+            return;
+        }
+        let values = self.get_or_insert_with(label, Vec::new);
+        let index = values.partition_point(|(existing, _)| *existing <= metric);
+        values.insert(index, (metric, value));
+    }
+
+    /// Returns the value with the lowest metric under the longest label that is a prefix of the query,
+    /// together with the matched part of the query and the metric.
+    /// Labels without any value are skipped
+    pub fn lookup_best<'a>(&self, query: &'a str) -> Option<(&'a str, &M, &T)> {
+        let (matched, values) =
+            self.find_longest_prefix_where(query, |values| !values.is_empty())?;
+        let (metric, value) = &values[0];
+        Some((matched, metric, value))
+    }
+}
+
+#[cfg(test)]
+mod metric_tests {
+    use crate::trie::RadixTrie;
+
+    #[test]
+    fn test_lookup_best() {
+        let mut routes = RadixTrie::<Vec<(u32, &str)>>::new();
+        routes.insert_with_metric("192.168", 5, "lan");
+        routes.insert_with_metric("192.168.1", 30, "rip");
+        routes.insert_with_metric("192.168.1", 10, "ospf-a");
+        routes.insert_with_metric("192.168.1", 10, "ospf-b");
+        assert_eq!(
+            routes.lookup_best("192.168.1.7"),
+            Some(("192.168.1", &10, &"ospf-a"))
+        );
+        assert_eq!(
+            routes.lookup_best("192.168.2.7"),
+            Some(("192.168", &5, &"lan"))
+        );
+        assert_eq!(routes.lookup_best("10.0.0.1"), None);
+        routes.insert_with_metric("", 0, "everything");
+        assert_eq!(routes.lookup_best("10.0.0.1"), None);
+        assert_eq!(routes.len(), 2);
+        routes.find_mut("192.168.1").unwrap().clear();
+        assert_eq!(
+            routes.lookup_best("192.168.1.7"),
+            Some(("192.168", &5, &"lan"))
+        );
+    }
+}
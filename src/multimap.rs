@@ -0,0 +1,149 @@
+use crate::trie::RadixTrie;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// A trie holding any number of values under each label.
+///
+/// The values of a label keep their insertion order.
+///
+/// # Example
+/// ```rust
+/// use another_radix_trie::RadixMultiMap;
+///
+/// let mut map = RadixMultiMap::new();
+/// map.insert("/var/log", 1);
+/// map.insert("/var/log", 2);
+/// map.insert("/var/lib", 3);
+/// assert_eq!(map.get_all("/var/log"), &[1, 2]);
+/// assert_eq!(map.len(), 3);
+/// assert_eq!(map.key_count(), 2);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RadixMultiMap<T> {
+    trie: RadixTrie<Vec<T>>,
+    /// The number of values under all labels
+    len: usize,
+}
+
+impl<T> Default for RadixMultiMap<T> {
+    fn default() -> Self {
+        RadixMultiMap::new()
+    }
+}
+
+impl<T> RadixMultiMap<T> {
+    /// Construct an empty map
+    pub fn new() -> Self {
+        RadixMultiMap {
+            trie: RadixTrie::new(),
+            len: 0,
+        }
+    }
+
+    /// Returns the number of values under all labels
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the number of labels holding at least one value
+    pub fn key_count(&self) -> usize {
+        self.trie.len()
+    }
+
+    /// Returns true if the map holds no value
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Appends the value to the values of the label in one traversal.
+    /// Like [`RadixTrie::insert`], inserting the empty label does nothing
+    pub fn insert(&mut self, label: &str, value: T) {
+        if let Ok(values) = self.trie.try_get_or_default(label) {
+            values.push(value);
+            self.len += 1;
+        }
+    }
+
+    /// Returns the values of the label in insertion order.
+    /// If the label does not exist, the slice is empty
+    pub fn get_all(&self, label: &str) -> &[T] {
+        self.trie.find(label).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns the mutable values of the label in insertion order.
+    /// If the label does not exist, the slice is empty
+    pub fn get_all_mut(&mut self, label: &str) -> &mut [T] {
+        match self.trie.find_mut(label) {
+            Some(values) => values,
+            None => &mut [],
+        }
+    }
+
+    /// Removes the label and returns all its values in insertion order
+    pub fn remove_all(&mut self, label: &str) -> Vec<T> {
+        let values = self.trie.remove(label).unwrap_or_default();
+        self.len -= values.len();
+        values
+    }
+
+    /// Returns all labels starting with given prefix with their values,
+    /// in lexicographic order of labels
+    pub fn start_with(&self, prefix: &str) -> Vec<(String, &[T])> {
+        self.trie
+            .find_prefix_iter(prefix)
+            .map(|(label, values)| (label, values.as_slice()))
+            .collect()
+    }
+
+    /// Returns the trie holding the values of each label
+    pub fn trie(&self) -> &RadixTrie<Vec<T>> {
+        &self.trie
+    }
+
+    /// Consumes the map and returns the trie holding the values of each label
+    pub fn into_trie(self) -> RadixTrie<Vec<T>> {
+        self.trie
+    }
+}
+
+#[cfg(test)]
+mod multimap_tests {
+    use crate::multimap::RadixMultiMap;
+
+    #[test]
+    fn test_multiple_values() {
+        let mut map = RadixMultiMap::new();
+        let lines = [
+            ("/var/log", 1),
+            ("/var", 2),
+            ("/var/log", 3),
+            ("/多倫多", 4),
+            ("/var/log", 5),
+        ];
+        for (path, line) in &lines {
+            map.insert(path, *line);
+        }
+        map.insert("", 6);
+        assert_eq!(map.len(), lines.len());
+        assert_eq!(map.key_count(), 3);
+        assert_eq!(map.get_all("/var/log"), &[1, 3, 5]);
+        assert!(map.get_all("/var/lo").is_empty());
+        map.get_all_mut("/var/log")
+            .iter_mut()
+            .for_each(|line| *line *= 10);
+        assert!(map.get_all_mut("/usr").is_empty());
+        assert_eq!(
+            map.start_with("/var"),
+            vec![
+                (String::from("/var"), &[2][..]),
+                (String::from("/var/log"), &[10, 30, 50][..])
+            ]
+        );
+        assert_eq!(map.remove_all("/var/log"), vec![10, 30, 50]);
+        assert!(map.remove_all("/var/log").is_empty());
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.key_count(), 2);
+        assert!(!map.is_empty());
+        assert!(RadixMultiMap::<usize>::default().is_empty());
+    }
+}
@@ -0,0 +1,115 @@
+use crate::trie::RadixTrie;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// A trie that passes every label through a normalizer before using it.
+///
+/// Inserts and lookups agree on the normalized form, so callers can use
+/// labels in any casing or Unicode form the normalizer folds together.
+/// Labels returned by the trie are the normalized ones.
+///
+/// # Example
+/// ```rust
+/// use another_radix_trie::NormalizedTrie;
+///
+/// let mut trie = NormalizedTrie::new(|label: &str| label.to_lowercase());
+/// trie.insert("Toronto", 416);
+/// assert_eq!(trie.find("TORONTO"), Some(&416));
+/// assert_eq!(trie.start_with("TOR"), vec![(String::from("toronto"), &416)]);
+/// ```
+pub struct NormalizedTrie<T, F> {
+    trie: RadixTrie<T>,
+    normalizer: F,
+}
+
+impl<T, F> NormalizedTrie<T, F>
+where
+    F: Fn(&str) -> String,
+{
+    /// Construct an empty trie with the given normalizer
+    pub fn new(normalizer: F) -> Self {
+        NormalizedTrie {
+            trie: RadixTrie::new(),
+            normalizer,
+        }
+    }
+
+    /// Insert the value under the normalized label.
+    /// An existing value of an equivalent label is replaced and returned
+    pub fn insert(&mut self, label: &str, value: T) -> Option<T> {
+        let label = (self.normalizer)(label);
+        self.trie.insert(&label, value)
+    }
+
+    /// Returns the borrowed value of the normalized label.
+    /// If the label does not exist, return None
+    pub fn find(&self, label: &str) -> Option<&T> {
+        self.trie.find((self.normalizer)(label))
+    }
+
+    /// Returns the mutable borrowed value of the normalized label.
+    /// If the label does not exist, return None
+    pub fn find_mut(&mut self, label: &str) -> Option<&mut T> {
+        let label = (self.normalizer)(label);
+        self.trie.find_mut(&label)
+    }
+
+    /// Removes the value of the normalized label and returns it
+    pub fn remove(&mut self, label: &str) -> Option<T> {
+        let label = (self.normalizer)(label);
+        self.trie.remove(&label)
+    }
+
+    /// Returns all values with their normalized labels where the labels start with the normalized prefix
+    pub fn start_with(&self, prefix: &str) -> Vec<(String, &T)> {
+        self.trie.start_with(&(self.normalizer)(prefix))
+    }
+
+    /// Returns the trie holding the normalized labels
+    pub fn trie(&self) -> &RadixTrie<T> {
+        &self.trie
+    }
+
+    /// Consumes the wrapper and returns the trie holding the normalized labels
+    pub fn into_trie(self) -> RadixTrie<T> {
+        self.trie
+    }
+}
+
+#[cfg(test)]
+mod normalized_tests {
+    use crate::normalized::NormalizedTrie;
+
+    #[test]
+    fn test_case_insensitive() {
+        let mut trie = NormalizedTrie::new(|label: &str| label.to_lowercase());
+        trie.insert("Wonder", 1);
+        trie.insert("WONDERFUL", 2);
+        trie.insert("wonder", 3);
+        assert_eq!(trie.find("wOnDeR"), Some(&3));
+        assert_eq!(trie.find("Wonderful"), Some(&2));
+        *trie.find_mut("WonderFul").unwrap() += 1;
+        assert_eq!(trie.start_with("WON").len(), 2);
+        assert_eq!(trie.remove("WONDER"), Some(3));
+        assert_eq!(trie.find("wonder"), None);
+        assert_eq!(trie.trie().find("wonderful"), Some(&3));
+        assert_eq!(trie.into_trie().find("WONDERFUL"), None);
+    }
+
+    #[test]
+    fn test_custom_normalizer() {
+        // fold full width latin letters to ascii
+        let mut trie = NormalizedTrie::new(|label: &str| {
+            label
+                .chars()
+                .map(|char| match char {
+                    'Ａ'..='Ｚ' => (char as u32 - 'Ａ' as u32 + 'A' as u32) as u8 as char,
+                    _ => char,
+                })
+                .collect()
+        });
+        trie.insert("ＯＮ", 647);
+        assert_eq!(trie.find("ON"), Some(&647));
+        assert_eq!(trie.find("ＯＮ"), Some(&647));
+    }
+}
@@ -0,0 +1,180 @@
+use crate::entry::Entry;
+use crate::trie::RadixTrie;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// A change made to an [`ObservedTrie`], passed to its observer after the change is applied
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeEvent<'a, T> {
+    /// The label did not hold a value and now holds the given one
+    Inserted { label: &'a str, value: &'a T },
+    /// The value of the label was replaced
+    Replaced {
+        label: &'a str,
+        old: &'a T,
+        new: &'a T,
+    },
+    /// The label and its value were removed
+    Removed { label: &'a str, value: &'a T },
+}
+
+/// A trie that reports every insert, replace, and remove to an observer.
+///
+/// The observer runs right after each change with the affected label,
+/// so an external index can be kept in sync without wrapping every call site.
+/// Values changed through [`ObservedTrie::trie`] are not visible to it, since only shared access is given out.
+///
+/// # Example
+/// ```rust
+/// use another_radix_trie::{ChangeEvent, ObservedTrie};
+///
+/// let mut log = vec![];
+/// let mut trie = ObservedTrie::new(|event: ChangeEvent<'_, usize>| match event {
+///     ChangeEvent::Inserted { label, .. } => log.push(format!("+{}", label)),
+///     ChangeEvent::Replaced { label, .. } => log.push(format!("~{}", label)),
+///     ChangeEvent::Removed { label, .. } => log.push(format!("-{}", label)),
+/// });
+/// trie.insert("lab", 3);
+/// trie.insert("lab", 4);
+/// trie.remove("lab");
+/// drop(trie);
+/// assert_eq!(log, vec!["+lab", "~lab", "-lab"]);
+/// ```
+pub struct ObservedTrie<T, F> {
+    trie: RadixTrie<T>,
+    observer: F,
+}
+
+impl<T, F> ObservedTrie<T, F>
+where
+    F: FnMut(ChangeEvent<'_, T>),
+{
+    /// Construct an empty trie reporting to the given observer
+    pub fn new(observer: F) -> Self {
+        Self::with_trie(RadixTrie::new(), observer)
+    }
+
+    /// Wrap an existing trie. Its current values are not reported
+    pub fn with_trie(trie: RadixTrie<T>, observer: F) -> Self {
+        ObservedTrie { trie, observer }
+    }
+
+    /// Returns the number of values stored
+    pub fn len(&self) -> usize {
+        self.trie.len()
+    }
+
+    /// Returns true if no value is stored
+    pub fn is_empty(&self) -> bool {
+        self.trie.is_empty()
+    }
+
+    /// Insert label and associated value, reporting it as inserted or replaced.
+    /// The replaced value is returned.
+    /// Inserting the empty label does nothing, reports nothing, and returns None
+    pub fn insert(&mut self, label: &str, value: T) -> Option<T> {
+        if label.is_empty() {
+            return None;
+        }
+        match self.trie.entry(label) {
+            Entry::Occupied(mut entry) => {
+                let old = entry.insert(value);
+                (self.observer)(ChangeEvent::Replaced {
+                    label,
+                    old: &old,
+                    new: entry.get(),
+                });
+                Some(old)
+            }
+            Entry::Vacant(entry) => {
+                let value = entry.insert(value);
+                (self.observer)(ChangeEvent::Inserted { label, value });
+                None
+            }
+        }
+    }
+
+    /// Removes the value of the label and returns it, reporting the removal if there was one
+    pub fn remove(&mut self, label: &str) -> Option<T> {
+        let value = self.trie.remove(label)?;
+        (self.observer)(ChangeEvent::Removed {
+            label,
+            value: &value,
+        });
+        Some(value)
+    }
+
+    /// Removes all labels starting with given prefix and returns them with their values
+    /// in lexicographic order, reporting each removal in the same order
+    pub fn remove_prefix(&mut self, prefix: &str) -> Vec<(String, T)> {
+        let removed = self.trie.remove_prefix(prefix);
+        for (label, value) in &removed {
+            (self.observer)(ChangeEvent::Removed { label, value });
+        }
+        removed
+    }
+
+    /// Returns the borrowed value associated with related label
+    pub fn find(&self, label: &str) -> Option<&T> {
+        self.trie.find(label)
+    }
+
+    /// Returns all values with their labels where the labels start with given prefix
+    pub fn start_with(&self, prefix: &str) -> Vec<(String, &T)> {
+        self.trie.start_with(prefix)
+    }
+
+    /// Returns the observed trie
+    pub fn trie(&self) -> &RadixTrie<T> {
+        &self.trie
+    }
+
+    /// Consumes the wrapper and returns the trie, dropping the observer
+    pub fn into_trie(self) -> RadixTrie<T> {
+        self.trie
+    }
+}
+
+#[cfg(test)]
+mod observed_tests {
+    use crate::observed::{ChangeEvent, ObservedTrie};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_observer_mirrors_trie() {
+        let mut mirror = BTreeMap::new();
+        let mut events = 0;
+        let mut trie = ObservedTrie::new(|event: ChangeEvent<'_, usize>| {
+            events += 1;
+            match event {
+                ChangeEvent::Inserted { label, value } => {
+                    assert!(mirror.insert(label.to_owned(), *value).is_none());
+                }
+                ChangeEvent::Replaced { label, old, new } => {
+                    assert_eq!(mirror.insert(label.to_owned(), *new), Some(*old));
+                }
+                ChangeEvent::Removed { label, value } => {
+                    assert_eq!(mirror.remove(label), Some(*value));
+                }
+            }
+        });
+        for (index, word) in ["Won", "Wonder", "World", "Won", "多倫多", ""]
+            .iter()
+            .enumerate()
+        {
+            trie.insert(word, index);
+        }
+        assert_eq!(trie.remove("Wonder"), Some(1));
+        assert_eq!(trie.remove("Wonder"), None);
+        trie.insert("Wonderful", 6);
+        assert_eq!(trie.remove_prefix("Wo").len(), 3);
+        assert_eq!(trie.len(), 1);
+        let trie = trie.into_trie();
+        assert_eq!(events, 10);
+        let expected = trie
+            .iter()
+            .map(|(label, value)| (label, *value))
+            .collect::<BTreeMap<_, _>>();
+        assert_eq!(mirror, expected);
+    }
+}
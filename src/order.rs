@@ -0,0 +1,438 @@
+use crate::element::Element;
+use crate::trie::RadixTrie;
+use alloc::string::String;
+use core::ops::Bound;
+
+impl<T> RadixTrie<T> {
+    /// Returns the label and value at the given position in lexicographic order, starting from 0.
+    /// Subtrees before the position are skipped by the value counts stored on the elements,
+    /// so it descends once and only the returned label is built
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("lab", 3);
+    /// trie.insert("label", 5);
+    /// trie.insert("lazy", 4);
+    /// assert_eq!(trie.nth(1), Some((String::from("label"), &5)));
+    /// assert_eq!(trie.nth(3), None);
+    /// ```
+    pub fn nth(&self, n: usize) -> Option<(String, &T)> {
+        if n >= self.len {
+            return None;
+        }
+        Some(nth_below(&self.entry, String::new(), n))
+    }
+
+    /// Returns a label picked uniformly at random with its value, found in one descent like [`RadixTrie::nth`].
+    /// The closure is given the number of labels and must return a uniformly random number below it,
+    /// such as `|n| rng.gen_range(0..n)`. It is not called on an empty trie
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("lab", 3);
+    /// trie.insert("label", 5);
+    /// assert_eq!(trie.sample(|n| n - 1), Some((String::from("label"), &5)));
+    /// assert_eq!(RadixTrie::<usize>::new().sample(|_| unreachable!()), None);
+    /// ```
+    pub fn sample<F>(&self, random_below: F) -> Option<(String, &T)>
+    where
+        F: FnOnce(usize) -> usize,
+    {
+        if self.is_empty() {
+            return None;
+        }
+        self.nth(random_below(self.len))
+    }
+
+    /// Returns a label starting with given prefix picked uniformly at random, with its value.
+    /// The number of such labels is stored on the element below the prefix, so it descends once.
+    /// The closure is given the number of such labels and must return a uniformly random number below it.
+    /// It is not called if no label starts with the prefix
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("lab", 3);
+    /// trie.insert("label", 5);
+    /// trie.insert("tab", 4);
+    /// let sampled = trie.sample_prefix("la", |n| {
+    ///     assert_eq!(n, 2);
+    ///     0
+    /// });
+    /// assert_eq!(sampled, Some((String::from("lab"), &3)));
+    /// ```
+    pub fn sample_prefix<F>(&self, prefix: &str, random_below: F) -> Option<(String, &T)>
+    where
+        F: FnOnce(usize) -> usize,
+    {
+        let mut label = String::new();
+        let element = self.find_prefix_element(prefix, |parent| label.push_str(parent))?;
+        let count = element.value_count();
+        if count == 0 {
+            return None;
+        }
+        let n = random_below(count);
+        if n >= count {
+            return None;
+        }
+        label.push_str(element.label());
+        Some(nth_below(element, label, n))
+    }
+
+    /// Returns the number of labels sorting strictly before the given label.
+    /// It is the position of the label in lexicographic order if the label exists
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("lab", 3);
+    /// trie.insert("label", 5);
+    /// trie.insert("lazy", 4);
+    /// assert_eq!(trie.rank("label"), 1);
+    /// assert_eq!(trie.rank("lb"), 3);
+    /// ```
+    pub fn rank(&self, mut label: &str) -> usize {
+        let mut rank = 0;
+        let mut element: &Element<T> = &self.entry;
+        loop {
+            // labels of the current element and its descendants start with the consumed part
+            if element.value().is_some() && !label.is_empty() {
+                rank += 1;
+            }
+            let mut next = None;
+            for child in element.children() {
+                let child_label = child.label();
+                if label.starts_with(child_label) && !label.is_empty() {
+                    next = Some(child);
+                    break;
+                } else if child_label < label {
+                    rank += child.value_count();
+                } else {
+                    break;
+                }
+            }
+            match next {
+                Some(child) => {
+                    label = &label[child.label().len()..];
+                    element = child;
+                }
+                None => return rank,
+            }
+        }
+    }
+
+    /// Returns the entry with the smallest label sorting strictly after the given label.
+    /// The given label does not need to exist in the trie
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("lab", 3);
+    /// trie.insert("label", 5);
+    /// trie.insert("lazy", 4);
+    /// assert_eq!(trie.next_key_after("lab"), Some((String::from("label"), &5)));
+    /// assert_eq!(trie.next_key_after("lac"), Some((String::from("lazy"), &4)));
+    /// assert_eq!(trie.next_key_after("lazy"), None);
+    /// ```
+    pub fn next_key_after(&self, label: &str) -> Option<(String, &T)> {
+        self.range(Bound::Excluded(label), Bound::Unbounded).next()
+    }
+
+    /// Returns the entry with the largest label sorting strictly before the given label.
+    /// The given label does not need to exist in the trie
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("lab", 3);
+    /// trie.insert("label", 5);
+    /// trie.insert("lazy", 4);
+    /// assert_eq!(trie.prev_key_before("lazy"), Some((String::from("label"), &5)));
+    /// assert_eq!(trie.prev_key_before("labe"), Some((String::from("lab"), &3)));
+    /// assert_eq!(trie.prev_key_before("lab"), None);
+    /// ```
+    pub fn prev_key_before(&self, mut label: &str) -> Option<(String, &T)> {
+        let mut prefix = String::new();
+        let mut element: &Element<T> = &self.entry;
+        // the joined label of the parent of the best element so far, and whether it is
+        // the best itself or the root of a subtree whose last value is the best
+        let mut best: Option<(usize, &Element<T>, bool)> = None;
+        loop {
+            if element.value().is_some() && !label.is_empty() {
+                let parent_len = prefix.len() - element.label().len();
+                best = Some((parent_len, element, false));
+            }
+            let mut next = None;
+            for child in element.children() {
+                let child_label = child.label();
+                if label.starts_with(child_label) && !label.is_empty() {
+                    next = Some(child);
+                    break;
+                } else if child_label < label {
+                    best = Some((prefix.len(), child, true));
+                } else {
+                    break;
+                }
+            }
+            match next {
+                Some(child) => {
+                    label = &label[child.label().len()..];
+                    prefix.push_str(child.label());
+                    element = child;
+                }
+                None => break,
+            }
+        }
+        let (parent_len, mut element, last_in_subtree) = best?;
+        prefix.truncate(parent_len);
+        prefix.push_str(element.label());
+        if last_in_subtree {
+            // the last value of a subtree is on its rightmost leaf
+            while let Some(child) = element.children().last() {
+                prefix.push_str(child.label());
+                element = child;
+            }
+        }
+        Some((prefix, element.value()?))
+    }
+
+    /// Returns the entry with the smallest label.
+    /// Only the path to the first value is walked
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("label", 5);
+    /// trie.insert("lab", 3);
+    /// assert_eq!(trie.first_key_value(), Some((String::from("lab"), &3)));
+    /// ```
+    pub fn first_key_value(&self) -> Option<(String, &T)> {
+        let mut label = String::new();
+        let mut element = self.entry.children().first()?;
+        loop {
+            label.push_str(element.label());
+            if let Some(value) = element.value() {
+                return Some((label, value));
+            }
+            element = element.children().first()?;
+        }
+    }
+
+    /// Returns the entry with the largest label.
+    /// Only the path to the rightmost leaf is walked
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("lab", 3);
+    /// trie.insert("label", 5);
+    /// assert_eq!(trie.last_key_value(), Some((String::from("label"), &5)));
+    /// ```
+    pub fn last_key_value(&self) -> Option<(String, &T)> {
+        let mut label = String::new();
+        let mut element = self.entry.children().last()?;
+        label.push_str(element.label());
+        // the last value of a subtree is on its rightmost leaf
+        while let Some(child) = element.children().last() {
+            label.push_str(child.label());
+            element = child;
+        }
+        Some((label, element.value()?))
+    }
+
+    /// Removes and returns the entry with the smallest label
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("/b", 2);
+    /// trie.insert("/a", 1);
+    /// assert_eq!(trie.pop_first(), Some((String::from("/a"), 1)));
+    /// assert_eq!(trie.pop_first(), Some((String::from("/b"), 2)));
+    /// assert_eq!(trie.pop_first(), None);
+    /// ```
+    pub fn pop_first(&mut self) -> Option<(String, T)> {
+        let (label, _) = self.first_key_value()?;
+        let value = self.remove(&label)?;
+        Some((label, value))
+    }
+
+    /// Removes and returns the entry with the largest label
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("/b", 2);
+    /// trie.insert("/a", 1);
+    /// assert_eq!(trie.pop_last(), Some((String::from("/b"), 2)));
+    /// assert_eq!(trie.len(), 1);
+    /// ```
+    pub fn pop_last(&mut self) -> Option<(String, T)> {
+        let (label, _) = self.last_key_value()?;
+        let value = self.remove(&label)?;
+        Some((label, value))
+    }
+}
+
+/// Returns the label and value at the given position in lexicographic order below the element.
+/// The label holds the joined labels from the root to the element,
+/// and the position must be below the number of values of the element
+fn nth_below<T>(mut element: &Element<T>, mut label: String, mut n: usize) -> (String, &T) {
+    'elements: loop {
+        if let Some(value) = element.value() {
+            if n == 0 {
+                return (label, value);
+            }
+            n -= 1;
+        }
+        for child in element.children() {
+            let count = child.value_count();
+            if n < count {
+                label.push_str(child.label());
+                element = child;
+                continue 'elements;
+            }
+            n -= count;
+        }
+        unreachable!();
+    }
+}
+
+#[cfg(test)]
+mod order_tests {
+    use crate::test_util::mixed_words_trie;
+    use crate::trie::RadixTrie;
+
+    #[test]
+    fn test_nth() {
+        let trie = mixed_words_trie();
+        let expected = trie.iter().collect::<Vec<_>>();
+        let res = (0..trie.len())
+            .flat_map(|n| trie.nth(n))
+            .collect::<Vec<_>>();
+        assert_eq!(res, expected);
+        assert_eq!(trie.nth(trie.len()), None);
+        assert_eq!(RadixTrie::<usize>::new().nth(0), None);
+    }
+
+    #[test]
+    fn test_rank() {
+        let trie = mixed_words_trie();
+        let labels = trie.keys().collect::<Vec<_>>();
+        for (position, label) in labels.iter().enumerate() {
+            assert_eq!(trie.rank(label), position);
+        }
+        for query in &[
+            "",
+            "A",
+            "B",
+            "Wo",
+            "Wonde",
+            "Wonderfully",
+            "Wz",
+            "多倫",
+            "多倫多多",
+            "龍",
+        ] {
+            let expected = labels
+                .iter()
+                .filter(|label| label.as_str() < *query)
+                .count();
+            assert_eq!(trie.rank(query), expected, "{}", query);
+        }
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let trie = mixed_words_trie();
+        let entries = trie.iter().collect::<Vec<_>>();
+        for (index, (label, _)) in entries.iter().enumerate() {
+            assert_eq!(trie.next_key_after(label), entries.get(index + 1).cloned());
+            let previous = index.checked_sub(1).map(|index| entries[index].clone());
+            assert_eq!(trie.prev_key_before(label), previous);
+        }
+        for query in &[
+            "",
+            "A",
+            "B",
+            "Wo",
+            "Wonde",
+            "Wonderfully",
+            "Wz",
+            "多倫",
+            "多倫多多",
+            "龍",
+        ] {
+            let after = entries.iter().find(|(label, _)| label.as_str() > *query);
+            assert_eq!(trie.next_key_after(query).as_ref(), after, "{}", query);
+            let before = entries
+                .iter()
+                .rev()
+                .find(|(label, _)| label.as_str() < *query);
+            assert_eq!(trie.prev_key_before(query).as_ref(), before, "{}", query);
+        }
+    }
+
+    #[test]
+    fn test_first_and_last() {
+        let mut trie = mixed_words_trie();
+        let mut entries = trie
+            .iter()
+            .map(|(label, value)| (label, *value))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            trie.first_key_value().map(|(label, value)| (label, *value)),
+            entries.first().cloned()
+        );
+        assert_eq!(
+            trie.last_key_value().map(|(label, value)| (label, *value)),
+            entries.last().cloned()
+        );
+        let mut popped = vec![];
+        while let Some(first) = trie.pop_first() {
+            popped.push(first);
+            if let Some(last) = trie.pop_last() {
+                assert_eq!(Some(last), entries.pop());
+            }
+        }
+        entries.truncate(popped.len());
+        assert_eq!(popped, entries);
+        assert!(trie.is_empty());
+        assert_eq!(trie.first_key_value(), None);
+        assert_eq!(trie.last_key_value(), None);
+        assert_eq!(trie.pop_last(), None);
+    }
+
+    #[test]
+    fn test_sample() {
+        let trie = mixed_words_trie();
+        let entries = trie.iter().collect::<Vec<_>>();
+        for (index, entry) in entries.iter().enumerate() {
+            let sampled = trie.sample(|n| {
+                assert_eq!(n, entries.len());
+                index
+            });
+            assert_eq!(sampled.as_ref(), Some(entry));
+        }
+        for prefix in &["W", "Won", "Wonderf", "多", "Z"] {
+            let expected = trie.find_prefix_iter(prefix).collect::<Vec<_>>();
+            let sampled = (0..expected.len())
+                .flat_map(|index| trie.sample_prefix(prefix, |_| index))
+                .collect::<Vec<_>>();
+            assert_eq!(sampled, expected, "{}", prefix);
+            assert_eq!(trie.sample_prefix(prefix, |n| n), None);
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use crate::frozen::FrozenRadixTrie;
+use crate::trie::RadixTrie;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// A writable trie layered over read-only base tries.
+///
+/// Lookups fall through the top layer and then the bases in order,
+/// writes always go to the top layer, and removals of labels stored in a base are
+/// recorded as tombstones in the top layer.
+///
+/// # Example
+/// ```rust
+/// use another_radix_trie::{OverlayTrie, RadixTrie};
+///
+/// let mut defaults = RadixTrie::<&str>::new();
+/// defaults.insert("color", "blue");
+/// defaults.insert("font", "serif");
+/// let mut config = OverlayTrie::new(vec![defaults.freeze()]);
+/// config.insert("color", "red");
+/// config.remove("font");
+/// assert_eq!(config.find("color"), Some(&"red"));
+/// assert_eq!(config.find("font"), None);
+/// ```
+pub struct OverlayTrie<T> {
+    /// None marks a tombstone hiding the label of the bases
+    top: RadixTrie<Option<T>>,
+    bases: Vec<FrozenRadixTrie<T>>,
+}
+
+impl<T> OverlayTrie<T> {
+    /// Construct an overlay with an empty top layer.
+    /// Earlier bases take precedence over later ones
+    pub fn new(bases: Vec<FrozenRadixTrie<T>>) -> Self {
+        OverlayTrie {
+            top: RadixTrie::new(),
+            bases,
+        }
+    }
+
+    /// Insert label and associated value into the top layer
+    pub fn insert(&mut self, label: &str, value: T) {
+        self.top.insert(label, Some(value));
+    }
+
+    /// Returns the borrowed value of the first layer holding the label.
+    /// If the label was removed from the overlay, return None
+    pub fn find(&self, label: &str) -> Option<&T> {
+        match self.top.find(label) {
+            Some(value) => value.as_ref(),
+            None => self.bases.iter().find_map(|base| base.find(label)),
+        }
+    }
+
+    /// Returns the mutable borrowed value of the top layer.
+    /// Values only stored in the bases are not mutable
+    pub fn find_mut(&mut self, label: &str) -> Option<&mut T> {
+        self.top.find_mut(label).and_then(Option::as_mut)
+    }
+
+    /// Hide the label in every layer.
+    /// If the top layer held a value for the label, return it
+    pub fn remove(&mut self, label: &str) -> Option<T> {
+        if self.bases.iter().any(|base| base.find(label).is_some()) {
+            self.top.get_or_insert_with(label, || None).take()
+        } else {
+            self.top.remove(label).flatten()
+        }
+    }
+
+    /// Returns the visible values with their labels where the labels start with given prefix,
+    /// in lexicographic order
+    pub fn start_with(&self, prefix: &str) -> Vec<(String, &T)> {
+        let mut visible = BTreeMap::new();
+        for base in self.bases.iter().rev() {
+            visible.extend(
+                base.start_with(prefix)
+                    .into_iter()
+                    .map(|(label, value)| (label, Some(value))),
+            );
+        }
+        visible.extend(
+            self.top
+                .start_with(prefix)
+                .into_iter()
+                .map(|(label, value)| (label, value.as_ref())),
+        );
+        visible
+            .into_iter()
+            .filter_map(|(label, value)| value.map(|value| (label, value)))
+            .collect()
+    }
+
+    /// The read-only base tries, in order of precedence
+    pub fn bases(&self) -> &[FrozenRadixTrie<T>] {
+        &self.bases
+    }
+}
+
+#[cfg(test)]
+mod overlay_tests {
+    use crate::overlay::OverlayTrie;
+    use crate::trie::RadixTrie;
+
+    fn get_test_overlay() -> OverlayTrie<usize> {
+        let mut system = RadixTrie::new();
+        system.insert("net.timeout", 30);
+        system.insert("net.retries", 3);
+        system.insert("ui.theme", 0);
+        let mut user = RadixTrie::new();
+        user.insert("net.timeout", 10);
+        OverlayTrie::new(vec![user.freeze(), system.freeze()])
+    }
+
+    #[test]
+    fn test_overlay_fall_through() {
+        let mut overlay = get_test_overlay();
+        assert_eq!(overlay.find("net.timeout"), Some(&10));
+        assert_eq!(overlay.find("net.retries"), Some(&3));
+        assert_eq!(overlay.find_mut("net.retries"), None);
+        overlay.insert("net.retries", 5);
+        *overlay.find_mut("net.retries").unwrap() += 1;
+        assert_eq!(overlay.find("net.retries"), Some(&6));
+        assert_eq!(overlay.bases()[1].find("net.retries"), Some(&3));
+    }
+
+    #[test]
+    fn test_overlay_tombstones() {
+        let mut overlay = get_test_overlay();
+        overlay.insert("net.proxy", 8080);
+        overlay.insert("net.retries", 5);
+        assert_eq!(overlay.remove("net.proxy"), Some(8080));
+        assert_eq!(overlay.remove("net.retries"), Some(5));
+        assert_eq!(overlay.remove("net.timeout"), None);
+        assert_eq!(overlay.find("net.retries"), None);
+        assert_eq!(overlay.find("net.timeout"), None);
+        assert!(overlay.start_with("net").is_empty());
+        overlay.insert("net.timeout", 1);
+        assert_eq!(overlay.find("net.timeout"), Some(&1));
+    }
+
+    #[test]
+    fn test_overlay_start_with() {
+        let mut overlay = get_test_overlay();
+        overlay.insert("net.proxy", 8080);
+        overlay.remove("net.retries");
+        let expected: Vec<(String, &usize)> =
+            vec![("net.proxy".into(), &8080), ("net.timeout".into(), &10)];
+        assert_eq!(overlay.start_with("net"), expected);
+    }
+}
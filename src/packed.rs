@@ -0,0 +1,154 @@
+use crate::trie::RadixTrie;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Labels and borrowed values found under a prefix,
+/// with all labels packed back to back into a single buffer.
+///
+/// Created by [`RadixTrie::find_prefix_ref`]
+pub struct PackedMatches<'a, T> {
+    labels: String,
+    /// Byte offset where each label ends in the buffer, with its value
+    entries: Vec<(usize, &'a T)>,
+}
+
+impl<'a, T> PackedMatches<'a, T> {
+    /// Returns the number of matches
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if nothing matched
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the label and value of the match at the given position in lexicographic order
+    pub fn get(&self, index: usize) -> Option<(&str, &'a T)> {
+        let (end, value) = *self.entries.get(index)?;
+        let start = match index {
+            0 => 0,
+            index => self.entries[index - 1].0,
+        };
+        Some((&self.labels[start..end], value))
+    }
+
+    /// Returns an iterator over the labels and values in lexicographic order
+    pub fn iter(&self) -> PackedIter<'_, 'a, T> {
+        PackedIter {
+            matches: self,
+            index: 0,
+        }
+    }
+}
+
+/// Iterator over the labels borrowed from [`PackedMatches`] and their values.
+///
+/// Created by [`PackedMatches::iter`]
+pub struct PackedIter<'b, 'a, T> {
+    matches: &'b PackedMatches<'a, T>,
+    index: usize,
+}
+
+impl<'b, 'a, T> Iterator for PackedIter<'b, 'a, T> {
+    type Item = (&'b str, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let res = self.matches.get(self.index)?;
+        self.index += 1;
+        Some(res)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let rest = self.matches.len() - self.index;
+        (rest, Some(rest))
+    }
+}
+
+impl<'b, 'a, T> IntoIterator for &'b PackedMatches<'a, T> {
+    type Item = (&'b str, &'a T);
+    type IntoIter = PackedIter<'b, 'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T> RadixTrie<T> {
+    /// Returns all labels and borrowed values where the labels start with given prefix,
+    /// in lexicographic order.
+    /// Unlike [`RadixTrie::find_prefix_iter`], the labels are borrowed from one shared buffer
+    /// instead of being allocated one by one
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("lab", 3);
+    /// trie.insert("label", 5);
+    /// trie.insert("lazy", 4);
+    /// let matches = trie.find_prefix_ref("lab");
+    /// assert_eq!(matches.iter().collect::<Vec<_>>(), vec![("lab", &3), ("label", &5)]);
+    /// assert_eq!(matches.get(1), Some(("label", &5)));
+    /// ```
+    pub fn find_prefix_ref(&self, prefix: &str) -> PackedMatches<'_, T> {
+        let mut label = String::new();
+        let mut matches = PackedMatches {
+            labels: String::new(),
+            entries: vec![],
+        };
+        let element = match self.find_prefix_element(prefix, |part| label.push_str(part)) {
+            Some(element) => element,
+            None => return matches,
+        };
+        element.for_each_value(&mut label, |label, value| {
+            matches.labels.push_str(label);
+            matches.entries.push((matches.labels.len(), value));
+        });
+        matches
+    }
+}
+
+#[cfg(test)]
+mod packed_tests {
+    use crate::trie::RadixTrie;
+
+    #[test]
+    fn test_find_prefix_ref_matches_prefix_iter() {
+        let mut trie = RadixTrie::<usize>::new();
+        for word in &[
+            "Won",
+            "Wonder",
+            "Wonderful",
+            "World",
+            "Axes",
+            "多倫多",
+            "多",
+        ] {
+            trie.insert(word, word.len());
+        }
+        for prefix in &[
+            "",
+            "W",
+            "Wo",
+            "Wonde",
+            "Wonderful",
+            "多",
+            "多倫",
+            "A",
+            "B",
+            "Worlds",
+        ] {
+            let matches = trie.find_prefix_ref(prefix);
+            let expected = trie.find_prefix_iter(prefix).collect::<Vec<_>>();
+            assert_eq!(matches.len(), expected.len(), "{}", prefix);
+            assert_eq!(matches.is_empty(), expected.is_empty());
+            let res = matches
+                .iter()
+                .map(|(label, value)| (label.to_owned(), value))
+                .collect::<Vec<_>>();
+            assert_eq!(res, expected, "{}", prefix);
+            assert_eq!(matches.get(matches.len()), None);
+        }
+    }
+}
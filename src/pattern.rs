@@ -0,0 +1,216 @@
+use crate::trie::RadixTrie;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// A pattern stored in a [`PatternTrie`]
+enum Rule<T> {
+    Include(T),
+    /// A negated pattern, excluding the paths it matches
+    Exclude,
+}
+
+/// A trie of wildcard patterns, resolving a path to the most specific pattern matching it.
+///
+/// The patterns use the wildcards of [`RadixTrie::find_pattern`]:
+/// `*` matches any run of chars, including none and including `/`, and `?` matches exactly one char.
+/// A pattern is more specific than another if it has more chars that are not wildcards,
+/// and equally specific patterns are ordered lexicographically.
+/// An excluded pattern takes the paths it matches away from the less specific patterns.
+/// Patterns sharing a prefix share their elements, so the chars of the path are only compared once
+/// against that prefix, and a branch is skipped as soon as its patterns cannot match the path.
+///
+/// # Example
+/// ```rust
+/// use another_radix_trie::PatternTrie;
+///
+/// let mut ignore = PatternTrie::new();
+/// ignore.insert("*.log", "logs");
+/// ignore.insert("build/*", "build output");
+/// ignore.exclude("build/keep.*");
+/// assert_eq!(ignore.matches("debug.log"), Some((String::from("*.log"), &"logs")));
+/// assert_eq!(ignore.matches("build/app"), Some((String::from("build/*"), &"build output")));
+/// assert_eq!(ignore.matches("build/keep.txt"), None);
+/// assert_eq!(ignore.matches("src/main.rs"), None);
+/// ```
+pub struct PatternTrie<T> {
+    rules: RadixTrie<Rule<T>>,
+}
+
+impl<T> Default for PatternTrie<T> {
+    fn default() -> Self {
+        PatternTrie::new()
+    }
+}
+
+impl<T> PatternTrie<T> {
+    /// Construct a trie without patterns
+    pub fn new() -> Self {
+        PatternTrie {
+            rules: RadixTrie::new(),
+        }
+    }
+
+    /// Insert the pattern with the value returned for the paths it resolves.
+    /// The pattern replaces an excluded pattern that is written the same
+    pub fn insert(&mut self, pattern: &str, value: T) {
+        self.rules.insert(pattern, Rule::Include(value));
+    }
+
+    /// Insert the negated pattern, so the paths it resolves match no pattern.
+    /// The pattern replaces an included pattern that is written the same
+    pub fn exclude(&mut self, pattern: &str) {
+        self.rules.insert(pattern, Rule::Exclude);
+    }
+
+    /// Removes the pattern, included or excluded.
+    /// Returns true if the pattern was stored
+    pub fn remove(&mut self, pattern: &str) -> bool {
+        self.rules.remove(pattern).is_some()
+    }
+
+    /// Returns the number of patterns stored, included or excluded
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// Returns true if no pattern is stored
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Returns the most specific pattern matching the whole path with its value.
+    /// If no pattern matches, or the most specific one is excluded, return None
+    pub fn matches(&self, path: &str) -> Option<(String, &T)> {
+        let path = path.chars().collect::<Vec<_>>();
+        // the most specific matching pattern so far with its number of chars that are not wildcards
+        let mut best: Option<(usize, String, &Rule<T>)> = None;
+        let mut pattern = String::new();
+        // each element is paired with the length of its parent pattern, the number of chars
+        // that are not wildcards in it, and the path positions reachable after it
+        let mut stack = self
+            .rules
+            .entry
+            .children()
+            .iter()
+            .rev()
+            .map(|child| (0, 0, child, vec![0]))
+            .collect::<Vec<_>>();
+        'elements: while let Some((prefix_len, mut literals, element, mut positions)) = stack.pop()
+        {
+            pattern.truncate(prefix_len);
+            for char in element.label().chars() {
+                pattern.push(char);
+                literals += (char != '*' && char != '?') as usize;
+                positions = next_positions(&path, &positions, char);
+                if positions.is_empty() {
+                    continue 'elements;
+                }
+            }
+            if let Some(rule) = element.value() {
+                let more_specific = best.as_ref().is_none_or(|best| best.0 < literals);
+                if more_specific && positions.last() == Some(&path.len()) {
+                    best = Some((literals, pattern.clone(), rule));
+                }
+            }
+            let children = element.children().iter().rev();
+            stack.extend(children.map(|child| (pattern.len(), literals, child, positions.clone())));
+        }
+        match best? {
+            (_, pattern, Rule::Include(value)) => Some((pattern, value)),
+            (_, _, Rule::Exclude) => None,
+        }
+    }
+}
+
+/// Advance the reachable path positions, sorted without duplicates, by one char of the pattern
+fn next_positions(path: &[char], positions: &[usize], char: char) -> Vec<usize> {
+    match char {
+        // a run of chars from the first reachable position on, including none
+        '*' => (positions[0]..=path.len()).collect(),
+        '?' => positions
+            .iter()
+            .filter(|position| **position < path.len())
+            .map(|position| position + 1)
+            .collect(),
+        _ => positions
+            .iter()
+            .filter(|position| path.get(**position) == Some(&char))
+            .map(|position| position + 1)
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod pattern_tests {
+    use crate::pattern::PatternTrie;
+    use crate::trie::RadixTrie;
+
+    #[test]
+    fn test_matches_agrees_with_find_pattern() {
+        let patterns = [
+            "*", "W*", "Wo?", "Won*", "*der*", "W*l?", "?xes", "多*多", "World",
+        ];
+        let mut trie = PatternTrie::new();
+        for (index, pattern) in patterns.iter().enumerate() {
+            trie.insert(pattern, index);
+        }
+        for path in &["Won", "Wonder", "Wonderful", "World", "Axes", "多倫多", "x"] {
+            let mut paths = RadixTrie::new();
+            paths.insert(path, ());
+            // the patterns matching a path as a label, the most specific first
+            let mut matching = patterns
+                .iter()
+                .filter(|pattern| !paths.find_pattern(pattern).is_empty())
+                .collect::<Vec<_>>();
+            let literals =
+                |pattern: &str| pattern.chars().filter(|c| *c != '*' && *c != '?').count();
+            matching.sort_by_key(|pattern| (usize::MAX - literals(pattern), **pattern));
+            let expected = matching.first().map(|pattern| pattern.to_string());
+            let res = trie.matches(path).map(|(pattern, _)| pattern);
+            assert_eq!(res, expected, "{}", path);
+        }
+        assert_eq!(trie.matches("World"), Some((String::from("World"), &8)));
+        assert_eq!(trie.matches("Wonderful"), Some((String::from("*der*"), &4)));
+        assert_eq!(trie.matches("Axes"), Some((String::from("?xes"), &6)));
+        assert_eq!(trie.matches(""), Some((String::from("*"), &0)));
+    }
+
+    #[test]
+    fn test_exclusions() {
+        let mut trie = PatternTrie::new();
+        trie.insert("target/*", 1);
+        trie.insert("*.rs", 2);
+        trie.exclude("target/*.keep");
+        trie.exclude("src/*");
+        assert_eq!(trie.len(), 4);
+        assert_eq!(
+            trie.matches("target/debug"),
+            Some((String::from("target/*"), &1))
+        );
+        assert_eq!(trie.matches("target/a.keep"), None);
+        // the excluded pattern is more specific than "*.rs"
+        assert_eq!(trie.matches("src/main.rs"), None);
+        assert_eq!(
+            trie.matches("tests/util.rs"),
+            Some((String::from("*.rs"), &2))
+        );
+        // a more specific pattern includes the path again
+        trie.insert("src/bin/*.rs", 3);
+        assert_eq!(
+            trie.matches("src/bin/tool.rs"),
+            Some((String::from("src/bin/*.rs"), &3))
+        );
+        assert!(trie.remove("src/*"));
+        assert!(!trie.remove("src/*"));
+        assert_eq!(
+            trie.matches("src/main.rs"),
+            Some((String::from("*.rs"), &2))
+        );
+        trie.insert("target/*.keep", 4);
+        assert_eq!(
+            trie.matches("target/a.keep"),
+            Some((String::from("target/*.keep"), &4))
+        );
+        assert_eq!(PatternTrie::<usize>::new().matches("a"), None);
+    }
+}
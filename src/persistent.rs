@@ -0,0 +1,566 @@
+use crate::diff::{DiffElement, DiffEntry, DiffWalk};
+use crate::util;
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+/// An immutable trie sharing unchanged subtrees between versions.
+///
+/// `insert` and `remove` return a new trie and leave the original untouched.
+/// Only the elements on the path to the changed label are copied, so
+/// snapshots are cheap and clones can be shared across threads without a lock.
+///
+/// # Example
+/// ```rust
+/// use another_radix_trie::PersistentRadixTrie;
+///
+/// let v1 = PersistentRadixTrie::new().insert("ON", 647);
+/// let v2 = v1.insert("ON2", 416);
+/// assert_eq!(v1.find("ON2"), None);
+/// assert_eq!(v2.find("ON2"), Some(&416));
+/// assert_eq!(v2.remove("ON").find("ON"), None);
+/// assert_eq!(v2.find("ON"), Some(&647));
+/// ```
+pub struct PersistentRadixTrie<T> {
+    root: Arc<Node<T>>,
+}
+
+struct Node<T> {
+    label: String,
+    value: Option<T>,
+    children: Vec<Arc<Node<T>>>,
+}
+
+impl<T: Clone> Node<T> {
+    /// Copy the element itself while sharing its children
+    fn copy(&self) -> Self {
+        Node {
+            label: self.label.clone(),
+            value: self.value.clone(),
+            children: self.children.clone(),
+        }
+    }
+
+    /// Copy the element with the first bytes of its label removed
+    fn copy_without_prefix(&self, prefix_len: usize) -> Self {
+        Node {
+            label: self.label[prefix_len..].to_owned(),
+            ..self.copy()
+        }
+    }
+
+    /// A valueless element with a single child merges into that child
+    fn compact(self) -> Self {
+        if self.value.is_some() || self.children.len() != 1 {
+            return self;
+        }
+        let child = &self.children[0];
+        Node {
+            label: self.label + &child.label,
+            ..child.copy()
+        }
+    }
+}
+
+impl<T> Node<T> {
+    fn leaf(label: &str, value: T) -> Self {
+        Node {
+            label: label.to_owned(),
+            value: Some(value),
+            children: vec![],
+        }
+    }
+
+    /// The index of the child starting with the char, or where it would be inserted
+    fn search(&self, target: char) -> Result<usize, usize> {
+        self.children
+            .binary_search_by(|child| util::first_char(&child.label).cmp(&target))
+    }
+}
+
+impl<T> Clone for PersistentRadixTrie<T> {
+    fn clone(&self) -> Self {
+        PersistentRadixTrie {
+            root: Arc::clone(&self.root),
+        }
+    }
+}
+
+impl<T> Default for PersistentRadixTrie<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Drop the nodes no other version shares one by one,
+/// so deep chains do not recurse through the nested children
+impl<T> Drop for PersistentRadixTrie<T> {
+    fn drop(&mut self) {
+        let mut stack = match Arc::get_mut(&mut self.root) {
+            Some(root) => core::mem::take(&mut root.children),
+            None => return,
+        };
+        while let Some(node) = stack.pop() {
+            if let Ok(mut node) = Arc::try_unwrap(node) {
+                stack.append(&mut node.children);
+            }
+        }
+    }
+}
+
+impl<T> PersistentRadixTrie<T> {
+    /// Construct an empty trie
+    pub fn new() -> Self {
+        PersistentRadixTrie {
+            root: Arc::new(Node {
+                label: String::new(),
+                value: None,
+                children: vec![],
+            }),
+        }
+    }
+
+    /// Returns true if no value is stored
+    pub fn is_empty(&self) -> bool {
+        self.root.children.is_empty()
+    }
+
+    /// Returns the borrowed value associated with related label.
+    /// If the label does not exist, return None
+    pub fn find(&self, mut label: &str) -> Option<&T> {
+        let mut node = &*self.root;
+        while !label.is_empty() {
+            let index = node.search(util::first_char(label)).ok()?;
+            node = &node.children[index];
+            label = label.strip_prefix(node.label.as_str())?;
+        }
+        node.value.as_ref()
+    }
+
+    /// Returns all values with their labels where the labels start with given prefix,
+    /// in lexicographic order
+    pub fn start_with(&self, mut prefix: &str) -> Vec<(String, &T)> {
+        let mut node = &*self.root;
+        let mut existing_prefix = String::new();
+        while !prefix.is_empty() {
+            let index = match node.search(util::first_char(prefix)) {
+                Ok(index) => index,
+                Err(_) => return vec![],
+            };
+            node = &node.children[index];
+            if node.label.starts_with(prefix) {
+                break;
+            }
+            prefix = match prefix.strip_prefix(node.label.as_str()) {
+                Some(rest) => rest,
+                None => return vec![],
+            };
+            existing_prefix.push_str(&node.label);
+        }
+        let mut res = vec![];
+        let mut stack = vec![(existing_prefix.len(), node)];
+        while let Some((prefix_len, node)) = stack.pop() {
+            existing_prefix.truncate(prefix_len);
+            existing_prefix.push_str(&node.label);
+            if let Some(value) = &node.value {
+                res.push((existing_prefix.clone(), value));
+            }
+            let label_len = existing_prefix.len();
+            let children = node.children.iter().rev();
+            stack.extend(children.map(|child| (label_len, &**child)));
+        }
+        res
+    }
+}
+
+/// Iterator over the labels and borrowed values of a [`PersistentRadixTrie`] in lexicographic order.
+///
+/// Created by [`PersistentRadixTrie::iter`]
+pub struct PersistentIter<'a, T> {
+    /// Nodes still to visit with the length of the label of their parent
+    stack: Vec<(usize, &'a Node<T>)>,
+    label: String,
+}
+
+impl<'a, T> Iterator for PersistentIter<'a, T> {
+    type Item = (String, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((parent_len, node)) = self.stack.pop() {
+            self.label.truncate(parent_len);
+            self.label.push_str(&node.label);
+            let label_len = self.label.len();
+            let children = node.children.iter().rev();
+            self.stack
+                .extend(children.map(|child| (label_len, &**child)));
+            if let Some(value) = &node.value {
+                return Some((self.label.clone(), value));
+            }
+        }
+        None
+    }
+}
+
+impl<T> PersistentRadixTrie<T> {
+    /// Returns a lazy iterator over all labels and borrowed values in lexicographic order
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::PersistentRadixTrie;
+    ///
+    /// let trie = PersistentRadixTrie::new().insert("label", 5).insert("lab", 3);
+    /// let labels = trie.iter().map(|(label, _)| label).collect::<Vec<_>>();
+    /// assert_eq!(labels, vec!["lab", "label"]);
+    /// ```
+    pub fn iter(&self) -> PersistentIter<'_, T> {
+        PersistentIter {
+            stack: vec![(0, &*self.root)],
+            label: String::new(),
+        }
+    }
+}
+
+impl<T: Clone> PersistentRadixTrie<T> {
+    /// Returns a new trie with the value inserted under the label.
+    /// An existing value of the label is replaced in the new trie.
+    /// Empty labels are ignored
+    pub fn insert(&self, label: &str, value: T) -> Self {
+        if label.is_empty() {
+            return self.clone();
+        }
+        let mut path = vec![];
+        let mut node = &*self.root;
+        let mut label = label;
+        let changed = loop {
+            let index = match node.search(util::first_char(label)) {
+                Ok(index) => index,
+                Err(index) => {
+                    let mut copy = node.copy();
+                    copy.children
+                        .insert(index, Arc::new(Node::leaf(label, value)));
+                    break copy;
+                }
+            };
+            let child = &node.children[index];
+            let shared_len = util::longest_shared_prefix(&child.label, label).len();
+            let replacement = if shared_len == child.label.len() && shared_len == label.len() {
+                Node {
+                    value: Some(value),
+                    ..child.copy()
+                }
+            } else if shared_len == child.label.len() {
+                path.push((node, index));
+                node = child;
+                label = &label[shared_len..];
+                continue;
+            } else if shared_len == label.len() {
+                Node {
+                    children: vec![Arc::new(child.copy_without_prefix(shared_len))],
+                    ..Node::leaf(label, value)
+                }
+            } else {
+                let original = Arc::new(child.copy_without_prefix(shared_len));
+                let new = Arc::new(Node::leaf(&label[shared_len..], value));
+                let children = if original.label < new.label {
+                    vec![original, new]
+                } else {
+                    vec![new, original]
+                };
+                Node {
+                    label: label[..shared_len].to_owned(),
+                    value: None,
+                    children,
+                }
+            };
+            let mut copy = node.copy();
+            copy.children[index] = Arc::new(replacement);
+            break copy;
+        };
+        Self::rebuild(path, changed)
+    }
+
+    /// Returns a new trie without the value of the label.
+    /// If the label does not exist, the new trie shares everything with this one
+    pub fn remove(&self, mut label: &str) -> Self {
+        let mut path = vec![];
+        let mut node = &*self.root;
+        while !label.is_empty() {
+            let index = match node.search(util::first_char(label)) {
+                Ok(index) => index,
+                Err(_) => return self.clone(),
+            };
+            let child = &node.children[index];
+            label = match label.strip_prefix(child.label.as_str()) {
+                Some(rest) => rest,
+                None => return self.clone(),
+            };
+            path.push((node, index));
+            node = child;
+        }
+        if node.value.is_none() {
+            return self.clone();
+        }
+        let (parent, index) = path.pop().expect("Root never holds a value");
+        let mut copy = parent.copy();
+        if node.children.is_empty() {
+            copy.children.remove(index);
+        } else {
+            let removed = Node {
+                value: None,
+                ..node.copy()
+            };
+            copy.children[index] = Arc::new(removed.compact());
+        }
+        let changed = if path.is_empty() {
+            copy
+        } else {
+            copy.compact()
+        };
+        Self::rebuild(path, changed)
+    }
+
+    /// Copy the ancestors along the path, bottom up, pointing them at the changed element
+    fn rebuild(path: Vec<(&Node<T>, usize)>, mut changed: Node<T>) -> Self {
+        for (parent, index) in path.into_iter().rev() {
+            let mut copy = parent.copy();
+            copy.children[index] = Arc::new(changed);
+            changed = copy;
+        }
+        PersistentRadixTrie {
+            root: Arc::new(changed),
+        }
+    }
+}
+
+impl<T> DiffElement for Arc<Node<T>> {
+    type Value = T;
+
+    fn label(&self) -> &str {
+        &self.label
+    }
+
+    fn value(&self) -> Option<&T> {
+        self.value.as_ref()
+    }
+
+    fn children(&self) -> &[Self] {
+        &self.children
+    }
+
+    fn same(&self, other: &Self) -> bool {
+        Arc::ptr_eq(self, other)
+    }
+}
+
+/// Iterator over the differences between two versions of a persistent trie,
+/// in lexicographic order of labels.
+///
+/// Created by [`PersistentRadixTrie::diff`]
+pub struct PersistentDiff<'a, T> {
+    walk: DiffWalk<'a, Arc<Node<T>>>,
+}
+
+impl<'a, T: PartialEq> Iterator for PersistentDiff<'a, T> {
+    type Item = DiffEntry<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.walk.next()
+    }
+}
+
+impl<T: PartialEq> PersistentRadixTrie<T> {
+    /// Returns a lazy iterator over the changes turning this version into the other one,
+    /// in lexicographic order of labels, like [`RadixTrie::diff`](crate::RadixTrie::diff).
+    /// Subtrees shared by both versions are skipped without visiting them,
+    /// so comparing a version with the one it was derived from only walks the copied paths
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::{DiffEntry, PersistentRadixTrie};
+    ///
+    /// let old = PersistentRadixTrie::new().insert("lab", 3).insert("label", 5);
+    /// let new = old.insert("label", 6).insert("lazy", 4).remove("lab");
+    /// let changes = old.diff(&new).collect::<Vec<_>>();
+    /// assert_eq!(changes, vec![
+    ///     DiffEntry::Removed(String::from("lab"), &3),
+    ///     DiffEntry::Changed(String::from("label"), &5, &6),
+    ///     DiffEntry::Added(String::from("lazy"), &4),
+    /// ]);
+    /// ```
+    pub fn diff<'a>(&'a self, other: &'a PersistentRadixTrie<T>) -> PersistentDiff<'a, T> {
+        PersistentDiff {
+            walk: DiffWalk::new(&self.root, &other.root),
+        }
+    }
+}
+
+#[cfg(test)]
+mod persistent_tests {
+    use crate::persistent::{Node, PersistentRadixTrie};
+    use crate::test_util::{Lcg, WORDS};
+    use crate::trie::RadixTrie;
+    use std::collections::BTreeMap;
+    use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+    use std::sync::Arc;
+    use std::thread;
+
+    fn assert_invariants<T>(node: &Node<T>, is_root: bool) {
+        for pair in node.children.windows(2) {
+            assert!(pair[0].label.chars().next() < pair[1].label.chars().next());
+        }
+        for child in &node.children {
+            assert!(!child.label.is_empty());
+            if child.value.is_none() {
+                assert!(child.children.len() >= 2, "{} is not compact", child.label);
+            }
+            assert_invariants(child, false);
+        }
+        assert!(is_root || node.value.is_some() || !node.children.is_empty());
+    }
+
+    #[test]
+    fn test_versions_are_independent() {
+        let mut versions = vec![PersistentRadixTrie::new()];
+        for word in &WORDS {
+            let next = versions.last().unwrap().insert(word, word.len());
+            versions.push(next);
+        }
+        for (count, version) in versions.iter().enumerate() {
+            for (index, word) in WORDS.iter().enumerate() {
+                let expected = if index < count {
+                    Some(&word.len())
+                } else {
+                    None
+                };
+                assert_eq!(version.find(word), expected);
+            }
+        }
+        let full = versions.last().unwrap();
+        let removed = full.remove("Wonder");
+        assert_eq!(removed.find("Wonder"), None);
+        assert_eq!(full.find("Wonder"), Some(&6));
+        assert_eq!(removed.start_with("Won").len(), 2);
+        assert_eq!(full.start_with("Won").len(), 3);
+        assert_invariants(&removed.root, true);
+    }
+
+    #[test]
+    fn test_unchanged_subtrees_are_shared() {
+        let trie = PersistentRadixTrie::new()
+            .insert("Axes", 1)
+            .insert("Won", 2)
+            .insert("Wonder", 3);
+        let next = trie.insert("Wonderful", 4);
+        assert!(Arc::ptr_eq(&trie.root.children[0], &next.root.children[0]));
+        assert!(!Arc::ptr_eq(&trie.root.children[1], &next.root.children[1]));
+        let missing = next.remove("Wo");
+        assert!(Arc::ptr_eq(&missing.root, &next.root));
+    }
+
+    #[test]
+    fn test_random_operations_match_btree_map() {
+        let mut rng = Lcg(42);
+        let mut trie = PersistentRadixTrie::new();
+        let mut model = BTreeMap::new();
+        for round in 0..2000 {
+            let key = rng.label();
+            if rng.next(3) == 0 {
+                trie = trie.remove(&key);
+                model.remove(&key);
+            } else {
+                trie = trie.insert(&key, round);
+                model.insert(key, round);
+            }
+            assert_invariants(&trie.root, true);
+        }
+        for (key, value) in &model {
+            assert_eq!(trie.find(key), Some(value));
+        }
+        for prefix in &["", "a", "b", "多", "ab", "多a"] {
+            let expected = model
+                .iter()
+                .filter(|(key, _)| key.starts_with(prefix))
+                .map(|(key, value)| (key.clone(), value))
+                .collect::<Vec<_>>();
+            assert_eq!(trie.start_with(prefix), expected);
+        }
+        for key in model.keys() {
+            trie = trie.remove(key);
+        }
+        assert!(trie.is_empty());
+    }
+
+    #[test]
+    fn test_share_across_threads() {
+        let trie = PersistentRadixTrie::new().insert("ON", 647);
+        let snapshot = trie.clone();
+        let handle = thread::spawn(move || snapshot.find("ON").copied());
+        let trie = trie.insert("ON", 416);
+        assert_eq!(handle.join().unwrap(), Some(647));
+        assert_eq!(trie.find("ON"), Some(&416));
+    }
+
+    #[test]
+    fn test_diff_skips_shared_subtrees() {
+        /// A value counting how often it is compared
+        #[derive(Debug, Clone)]
+        struct Compared(usize, Arc<AtomicUsize>);
+
+        impl PartialEq for Compared {
+            fn eq(&self, other: &Self) -> bool {
+                self.1.fetch_add(1, AtomicOrdering::Relaxed);
+                self.0 == other.0
+            }
+        }
+
+        let comparisons = Arc::new(AtomicUsize::new(0));
+        let compared = |value| Compared(value, Arc::clone(&comparisons));
+        let mut old = PersistentRadixTrie::new();
+        let mut model = RadixTrie::new();
+        for index in 0..1000 {
+            let label = format!("{:x}", index * 7919);
+            old = old.insert(&label, compared(index));
+            model.insert(&label, compared(index));
+        }
+        let new = old
+            .insert("1f0f", compared(1))
+            .insert("abc", compared(2))
+            .remove("0");
+        let mut new_model = model.clone();
+        new_model.insert("1f0f", compared(1));
+        new_model.insert("abc", compared(2));
+        new_model.remove("0");
+        comparisons.store(0, AtomicOrdering::Relaxed);
+        let changes = old.diff(&new).collect::<Vec<_>>();
+        // only the values on the copied paths are compared
+        assert!(comparisons.load(AtomicOrdering::Relaxed) < 10);
+        assert_eq!(changes, model.diff(&new_model).collect::<Vec<_>>());
+        assert_eq!(changes.len(), 3);
+        assert_eq!(old.diff(&old).next(), None);
+    }
+
+    #[test]
+    fn test_deep_chain_drop_without_recursion() {
+        // "a", "aa", "aaa", ... as one node per char
+        const DEPTH: usize = 5_000;
+        let mut trie = PersistentRadixTrie::new();
+        let mut half = None;
+        for depth in 1..=DEPTH {
+            trie = trie.insert(&"a".repeat(depth), depth);
+            if depth == DEPTH / 2 {
+                half = Some(trie.clone());
+            }
+        }
+        let half = half.unwrap();
+        let dropped = thread::Builder::new()
+            .stack_size(64 * 1024)
+            .spawn(move || {
+                // the older version keeps its nodes after the newer one is dropped
+                drop(trie);
+                assert_eq!(half.find(&"a".repeat(DEPTH / 2)), Some(&(DEPTH / 2)));
+                drop(half);
+            })
+            .unwrap()
+            .join();
+        assert!(dropped.is_ok());
+    }
+}
@@ -0,0 +1,179 @@
+use crate::element::Element;
+use crate::trie::RadixTrie;
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops::Bound;
+
+/// Iterator over the entries of a trie whose labels fall in a range, in lexicographic order.
+///
+/// Created by [`RadixTrie::range`]
+pub struct Range<'a, T> {
+    label: String,
+    /// Elements to visit with the length of their parent label
+    stack: Vec<(usize, &'a Element<T>)>,
+    start: Bound<String>,
+    end: Bound<String>,
+}
+
+impl<'a, T> Range<'a, T> {
+    /// Returns true if the current label and all labels below it sort before the start bound
+    fn before_start(&self) -> bool {
+        match &self.start {
+            Bound::Included(start) | Bound::Excluded(start) => {
+                self.label.as_str() < start.as_str() && !start.starts_with(&self.label)
+            }
+            Bound::Unbounded => false,
+        }
+    }
+
+    /// Returns true if the current label and all labels after it sort beyond the end bound
+    fn after_end(&self) -> bool {
+        match &self.end {
+            Bound::Included(end) => self.label.as_str() > end.as_str(),
+            Bound::Excluded(end) => self.label.as_str() >= end.as_str(),
+            Bound::Unbounded => false,
+        }
+    }
+
+    fn in_start(&self) -> bool {
+        match &self.start {
+            Bound::Included(start) => self.label.as_str() >= start.as_str(),
+            Bound::Excluded(start) => self.label.as_str() > start.as_str(),
+            Bound::Unbounded => true,
+        }
+    }
+}
+
+impl<'a, T> Iterator for Range<'a, T> {
+    type Item = (String, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((prefix_len, element)) = self.stack.pop() {
+            self.label.truncate(prefix_len);
+            self.label.push_str(element.label());
+            if self.after_end() {
+                // the remaining elements have even larger labels
+                self.stack.clear();
+                return None;
+            }
+            if self.before_start() {
+                continue;
+            }
+            let label_len = self.label.len();
+            let children = element.children().iter().rev();
+            self.stack.extend(children.map(|child| (label_len, child)));
+            match element.value() {
+                Some(value) if self.in_start() => return Some((self.label.clone(), value)),
+                _ => (),
+            }
+        }
+        None
+    }
+}
+
+impl<T> RadixTrie<T> {
+    /// Returns a lazy iterator over the labels and borrowed values within the bounds,
+    /// in lexicographic order. Subtrees entirely outside the bounds are never visited
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    /// use std::ops::Bound;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("lab", 3);
+    /// trie.insert("label", 5);
+    /// trie.insert("lazy", 4);
+    /// let res = trie
+    ///     .range(Bound::Excluded("lab"), Bound::Included("lazy"))
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(res, vec![(String::from("label"), &5), (String::from("lazy"), &4)]);
+    /// ```
+    pub fn range(&self, start: Bound<&str>, end: Bound<&str>) -> Range<'_, T> {
+        Range {
+            label: String::new(),
+            stack: vec![(0, &self.entry)],
+            start: to_owned(start),
+            end: to_owned(end),
+        }
+    }
+}
+
+fn to_owned(bound: Bound<&str>) -> Bound<String> {
+    match bound {
+        Bound::Included(label) => Bound::Included(label.to_owned()),
+        Bound::Excluded(label) => Bound::Excluded(label.to_owned()),
+        Bound::Unbounded => Bound::Unbounded,
+    }
+}
+
+#[cfg(test)]
+mod range_tests {
+    use crate::trie::RadixTrie;
+    use std::collections::BTreeMap;
+    use std::ops::Bound;
+
+    #[test]
+    fn test_range_matches_btree_map() {
+        let words = [
+            "Won",
+            "Wonder",
+            "Wonderful",
+            "World",
+            "Axes",
+            "Toronto多倫多",
+            "Toronto",
+        ];
+        let trie = words
+            .iter()
+            .map(|word| (*word, word.len()))
+            .collect::<RadixTrie<_>>();
+        let map = words
+            .iter()
+            .map(|word| (word.to_string(), word.len()))
+            .collect::<BTreeMap<_, _>>();
+        let bounds = [
+            "", "A", "Axes", "T", "Toronto", "Wo", "Won", "Wonder", "Wz", "多",
+        ];
+        let mut all = vec![Bound::Unbounded];
+        for bound in &bounds {
+            all.push(Bound::Included(*bound));
+            all.push(Bound::Excluded(*bound));
+        }
+        for start in &all {
+            for end in &all {
+                let owned = |bound: &Bound<&str>| match bound {
+                    Bound::Included(label) => Bound::Included(label.to_string()),
+                    Bound::Excluded(label) => Bound::Excluded(label.to_string()),
+                    Bound::Unbounded => Bound::Unbounded,
+                };
+                let range = (owned(start), owned(end));
+                let expected = match (&range.0, &range.1) {
+                    (Bound::Included(start), Bound::Included(end))
+                    | (Bound::Included(start), Bound::Excluded(end))
+                    | (Bound::Excluded(start), Bound::Included(end))
+                        if start > end =>
+                    {
+                        vec![]
+                    }
+                    (Bound::Excluded(start), Bound::Excluded(end)) if start >= end => vec![],
+                    _ => map
+                        .range(range)
+                        .map(|(label, value)| (label.clone(), *value))
+                        .collect::<Vec<_>>(),
+                };
+                let res = trie
+                    .range(*start, *end)
+                    .map(|(label, value)| (label, *value))
+                    .collect::<Vec<_>>();
+                assert_eq!(res, expected, "range {:?}..{:?}", start, end);
+            }
+        }
+    }
+
+    #[test]
+    fn test_range_on_empty_trie() {
+        let trie = RadixTrie::<usize>::new();
+        assert_eq!(trie.range(Bound::Unbounded, Bound::Unbounded).count(), 0);
+    }
+}
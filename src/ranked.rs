@@ -0,0 +1,302 @@
+use crate::element::Element;
+use crate::trie::RadixTrie;
+use alloc::collections::BinaryHeap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+/// A trie ranking its labels by a score of their values for top-k prefix completion.
+///
+/// Every element caches the highest score below it, and the caches on the path
+/// of a label are refreshed when the label is inserted or removed.
+/// [`RankedTrie::complete_top_k`] then visits the elements best first
+/// and never descends into a subtree whose cached score cannot beat the k-th completion,
+/// unlike [`RadixTrie::complete_top_k`], which scores every value below the prefix.
+/// The scores are computed by the scorer given to [`RankedTrie::new`],
+/// so values are only changed through [`RankedTrie::insert`].
+///
+/// # Example
+/// ```rust
+/// use another_radix_trie::RankedTrie;
+///
+/// let mut trie = RankedTrie::new(|hits: &usize| *hits);
+/// trie.insert("label", 5);
+/// trie.insert("lab", 30);
+/// trie.insert("lazy", 12);
+/// trie.insert("tab", 90);
+/// let res = trie.complete_top_k("la", 2);
+/// assert_eq!(res, vec![(String::from("lab"), &30), (String::from("lazy"), &12)]);
+/// ```
+pub struct RankedTrie<T, S, F> {
+    trie: RadixTrie<T>,
+    /// The highest score below every element, under the full label of the element
+    best: RadixTrie<S>,
+    score: F,
+}
+
+/// An element or a value waiting to be visited by `complete_top_k`.
+/// Higher scores are greater, and on equal scores the smaller label is greater.
+/// An element is greater than a value with the same score and label,
+/// so the value of an element is only taken once the element is visited
+struct Pending<'a, S, T> {
+    score: S,
+    label: String,
+    next: Next<'a, T>,
+}
+
+enum Next<'a, T> {
+    /// An element whose cached score bounds every value below it
+    Element(&'a Element<T>),
+    Value(&'a T),
+}
+
+impl<'a, S: Ord, T> Ord for Pending<'a, S, T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let is_element = |pending: &Self| matches!(pending.next, Next::Element(_));
+        self.score
+            .cmp(&other.score)
+            .then_with(|| other.label.cmp(&self.label))
+            .then_with(|| is_element(self).cmp(&is_element(other)))
+    }
+}
+
+impl<'a, S: Ord, T> PartialOrd for Pending<'a, S, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<'a, S: Ord, T> PartialEq for Pending<'a, S, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<'a, S: Ord, T> Eq for Pending<'a, S, T> {}
+
+impl<T, S, F> RankedTrie<T, S, F>
+where
+    S: Ord + Clone,
+    F: Fn(&T) -> S,
+{
+    /// Construct an empty trie ranking the values with the given scorer
+    pub fn new(score: F) -> Self {
+        RankedTrie {
+            trie: RadixTrie::new(),
+            best: RadixTrie::new(),
+            score,
+        }
+    }
+
+    /// Insert the value under the label and refresh the cached scores on its path.
+    /// An existing value of the label is replaced and returned.
+    /// Inserting the empty label does nothing and returns None
+    pub fn insert(&mut self, label: &str, value: T) -> Option<T> {
+        let replaced = self.trie.insert(label, value);
+        self.refresh(label);
+        replaced
+    }
+
+    /// Removes the value of the label and returns it,
+    /// dropping the cached scores of the elements removed with it
+    pub fn remove(&mut self, label: &str) -> Option<T> {
+        let before = self
+            .trie
+            .path_to(label)
+            .into_iter()
+            .map(|(label, _)| label)
+            .collect::<Vec<_>>();
+        let removed = self.trie.remove(label)?;
+        let after = self.trie.path_to(label);
+        for label in &before {
+            if !after.iter().any(|(kept, _)| kept == label) {
+                self.best.remove(label);
+            }
+        }
+        self.refresh(label);
+        Some(removed)
+    }
+
+    /// Returns the borrowed value associated with related label.
+    /// If the label does not exist, return None
+    pub fn find(&self, label: &str) -> Option<&T> {
+        self.trie.find(label)
+    }
+
+    /// Number of values stored
+    pub fn len(&self) -> usize {
+        self.trie.len()
+    }
+
+    /// Returns true if no value is stored
+    pub fn is_empty(&self) -> bool {
+        self.trie.is_empty()
+    }
+
+    /// Returns the k labels starting with given prefix that have the highest scores,
+    /// with their values, from the highest score down. Equal scores are in lexicographic order.
+    /// Only the elements whose cached score can still make the k best are visited.
+    /// The empty prefix ranks every label
+    pub fn complete_top_k(&self, prefix: &str, k: usize) -> Vec<(String, &T)> {
+        let mut label = String::new();
+        let element = self
+            .trie
+            .find_prefix_element(prefix, |parent| label.push_str(parent));
+        let element = match element {
+            Some(element) if k > 0 => element,
+            _ => return vec![],
+        };
+        let mut pending = BinaryHeap::new();
+        match element {
+            // the root holds no value and has no cached score
+            Element::Base { .. } => self.push_children(&mut pending, element, &label),
+            _ => {
+                label.push_str(element.label());
+                pending.push(self.pending_element(element, label));
+            }
+        }
+        let mut res = Vec::with_capacity(k.min(element.value_count()));
+        while let Some(Pending { score, label, next }) = pending.pop() {
+            match next {
+                Next::Value(value) => {
+                    res.push((label, value));
+                    if res.len() == k {
+                        break;
+                    }
+                }
+                Next::Element(element) => {
+                    self.push_children(&mut pending, element, &label);
+                    if let Some(value) = element.value() {
+                        // the cached score of an element without children is its own
+                        let score = match element.children().is_empty() {
+                            true => score,
+                            false => (self.score)(value),
+                        };
+                        let next = Next::Value(value);
+                        pending.push(Pending { score, label, next });
+                    }
+                }
+            }
+        }
+        res
+    }
+
+    /// Returns the trie holding the values
+    pub fn trie(&self) -> &RadixTrie<T> {
+        &self.trie
+    }
+
+    /// Consumes the wrapper and returns the trie holding the values
+    pub fn into_trie(self) -> RadixTrie<T> {
+        self.trie
+    }
+
+    /// Recompute the cached scores of the elements on the path of the label from the bottom up.
+    /// The elements off the path keep their subtrees, so their cached scores stay valid
+    fn refresh(&mut self, label: &str) {
+        for (label, element) in self.trie.path_to(label).into_iter().rev() {
+            let mut best = element.value().map(&self.score);
+            let mut child_label = label.clone();
+            for child in element.children() {
+                child_label.truncate(label.len());
+                child_label.push_str(child.label());
+                best = best.max(self.best.find(&child_label).cloned());
+            }
+            // every element holds a value or has values below it
+            self.best.insert(&label, best.unwrap());
+        }
+    }
+
+    fn push_children<'a>(
+        &self,
+        pending: &mut BinaryHeap<Pending<'a, S, T>>,
+        element: &'a Element<T>,
+        label: &str,
+    ) {
+        for child in element.children() {
+            let mut child_label = String::with_capacity(label.len() + child.label().len());
+            child_label.push_str(label);
+            child_label.push_str(child.label());
+            pending.push(self.pending_element(child, child_label));
+        }
+    }
+
+    fn pending_element<'a>(&self, element: &'a Element<T>, label: String) -> Pending<'a, S, T> {
+        Pending {
+            score: self.best.find(&label).unwrap().clone(),
+            label,
+            next: Next::Element(element),
+        }
+    }
+}
+
+#[cfg(test)]
+mod ranked_tests {
+    use crate::ranked::RankedTrie;
+    use crate::test_util::{Lcg, MIXED_WORDS};
+    use crate::trie::RadixTrie;
+    use core::cell::Cell;
+
+    #[test]
+    fn test_complete_top_k_matches_unranked() {
+        let mut trie = RankedTrie::new(|hits: &u64| *hits);
+        let mut model = RadixTrie::new();
+        let mut rng = Lcg(11);
+        for round in 0..600 {
+            let label = rng.label();
+            if round % 3 == 2 {
+                assert_eq!(trie.remove(&label), model.remove(&label));
+            } else {
+                let hits = rng.next(20);
+                assert_eq!(trie.insert(&label, hits), model.insert(&label, hits));
+            }
+            // the cached scores are dropped with their elements
+            assert_eq!(trie.best.len(), model.node_count());
+        }
+        for prefix in &["", "a", "b", "多", "ab", "ba多", "c"] {
+            for k in 0..12 {
+                let expected = model.complete_top_k(prefix, k, |hits| *hits);
+                assert_eq!(trie.complete_top_k(prefix, k), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_complete_top_k_prunes_subtrees() {
+        let scored = Cell::new(0);
+        let mut trie = RankedTrie::new(|hits: &usize| {
+            scored.set(scored.get() + 1);
+            *hits
+        });
+        for (index, word) in MIXED_WORDS.iter().enumerate() {
+            trie.insert(word, index);
+        }
+        for index in 0..1000 {
+            trie.insert(&format!("Wonderful{}", index), 0);
+        }
+        trie.insert("Wonderland", 2000);
+        scored.set(0);
+        let res = trie.complete_top_k("Wo", 2);
+        assert_eq!(
+            res,
+            vec![
+                (String::from("Wonderland"), &2000),
+                (String::from("World"), &3)
+            ]
+        );
+        // only the values on the way to the best completions are scored again
+        assert!(scored.get() < 5);
+        assert_eq!(
+            trie.complete_top_k("Wonderful", 1)[0],
+            (String::from("Wonderful"), &2)
+        );
+        assert_eq!(trie.remove("Wonderland"), Some(2000));
+        let res = trie.complete_top_k("", 2);
+        assert_eq!(
+            res,
+            vec![(String::from("多"), &6), (String::from("多倫多"), &5)]
+        );
+        assert!(trie.complete_top_k("x", 1).is_empty());
+        assert_eq!(trie.len(), MIXED_WORDS.len() + 1000);
+    }
+}
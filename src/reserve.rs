@@ -0,0 +1,411 @@
+use crate::build::SortedBuilder;
+use crate::element::Element;
+use crate::entry::{Location, Position, Reservation};
+use crate::error::TrieError;
+use crate::trie::RadixTrie;
+use alloc::collections::TryReserveError;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// The error returned by [`RadixTrie::try_insert_reserve`] when an allocation fails.
+/// It gives back the value that was not inserted, and the trie is left unchanged
+#[derive(Debug)]
+pub struct ReserveError<T> {
+    /// The value that was not inserted
+    pub value: T,
+    /// The failed allocation
+    pub error: TryReserveError,
+}
+
+impl<T> fmt::Display for ReserveError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to allocate for the insertion: {}", self.error)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: fmt::Debug> std::error::Error for ReserveError<T> {}
+
+impl<T> RadixTrie<T> {
+    /// Insert label and associated value into the trie like [`RadixTrie::insert`],
+    /// returning the replaced value, but report a failed allocation instead of aborting.
+    /// The path of the search, the split labels, and the children of the new elements
+    /// are all reserved before the trie is changed, so on failure the trie is left as it was
+    /// and the value is given back in the error.
+    /// Inserting the empty label does nothing and returns `Ok(None)`
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// assert_eq!(trie.try_insert_reserve("label", 5).ok(), Some(None));
+    /// assert_eq!(trie.try_insert_reserve("lab", 3).ok(), Some(None));
+    /// assert_eq!(trie.try_insert_reserve("label", 6).ok(), Some(Some(5)));
+    /// ```
+    pub fn try_insert_reserve<K: AsRef<str>>(
+        &mut self,
+        label: K,
+        value: T,
+    ) -> Result<Option<T>, ReserveError<T>> {
+        self.try_insert_reserve_with(label.as_ref(), value, |element, value| {
+            Some(core::mem::replace(element.value_mut().unwrap(), value))
+        })
+    }
+
+    /// Inserts the value like [`RadixTrie::try_insert_reserve`],
+    /// handing the element of an occupied label to `occupied` with the value instead
+    fn try_insert_reserve_with<F>(
+        &mut self,
+        label: &str,
+        value: T,
+        occupied: F,
+    ) -> Result<Option<T>, ReserveError<T>>
+    where
+        F: FnOnce(&mut Element<T>, T) -> Option<T>,
+    {
+        if label.is_empty() {
+            return Ok(None);
+        }
+        let mut path = Vec::new();
+        let location = self.descend(label, |index| {
+            path.try_reserve(1)?;
+            path.push(index);
+            Ok(())
+        });
+        match location {
+            Ok(Location::Occupied) => Ok(occupied(self.element_at_mut(&path), value)),
+            Ok(Location::Vacant(matched, position)) => {
+                match self.reserve_at(&path, &label[matched..], &position) {
+                    Ok(reservation) => {
+                        self.insert_reserved(&path, position, reservation, value);
+                        Ok(None)
+                    }
+                    Err(error) => Err(ReserveError { value, error }),
+                }
+            }
+            Err(error) => Err(ReserveError { value, error }),
+        }
+    }
+
+    /// Inserts the labels and values one by one like [`RadixTrie::try_insert_reserve`].
+    /// The first failed allocation stops the insertions, and its value is given back in the error.
+    /// The labels inserted before it are kept, and the trie is never left half changed
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// assert!(trie.try_extend_reserve(vec![("lab", 3), ("label", 5)]).is_ok());
+    /// assert_eq!(trie.len(), 2);
+    /// ```
+    pub fn try_extend_reserve<K, I>(&mut self, entries: I) -> Result<(), ReserveError<T>>
+    where
+        K: AsRef<str>,
+        I: IntoIterator<Item = (K, T)>,
+    {
+        for (label, value) in entries {
+            self.try_insert_reserve(label, value)?;
+        }
+        Ok(())
+    }
+
+    /// Rebuilds a trie from labels and values sorted by label without duplicates
+    /// like [`RadixTrie::import_sorted`], but reports a failed allocation
+    /// as [`TrieError::AllocationFailed`] instead of aborting
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::{RadixTrie, TrieError};
+    ///
+    /// let imported = RadixTrie::try_import_sorted_reserve(vec![("lab", 3), ("label", 5)]).unwrap();
+    /// assert_eq!(imported.find("label"), Some(&5));
+    /// let unsorted = RadixTrie::try_import_sorted_reserve(vec![("b", 1), ("a", 2)]);
+    /// assert_eq!(unsorted.err(), Some(TrieError::UnsortedLabels));
+    /// ```
+    pub fn try_import_sorted_reserve<K, I>(entries: I) -> Result<Self, TrieError>
+    where
+        K: AsRef<str>,
+        I: IntoIterator<Item = (K, T)>,
+    {
+        let allocation_failed = |_| TrieError::AllocationFailed;
+        let mut builder = SortedBuilder::try_new().map_err(allocation_failed)?;
+        for (label, value) in entries {
+            let label = label.as_ref();
+            if !label.is_empty() {
+                builder.try_push(label, value)?;
+            }
+        }
+        builder.try_finish().map_err(allocation_failed)
+    }
+
+    /// Moves all labels starting with given prefix with their values into a new trie like
+    /// [`RadixTrie::split_off`], but reports a failed allocation instead of aborting.
+    /// The path, the labels joined by the split, and the entry of the new trie
+    /// are all reserved before the trie is changed, so on failure the trie is left as it was
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("/tmp/a", 1);
+    /// trie.insert("/usr", 3);
+    /// let tmp = trie.try_split_off_reserve("/tmp/").unwrap();
+    /// assert_eq!(tmp.find("/tmp/a"), Some(&1));
+    /// assert_eq!(trie.len(), 1);
+    /// ```
+    pub fn try_split_off_reserve(&mut self, prefix: &str) -> Result<RadixTrie<T>, TryReserveError> {
+        if prefix.is_empty() {
+            // the contents are swapped out without allocating
+            return Ok(self.split_off(prefix));
+        }
+        let mut path = Vec::new();
+        let mut existing_prefix = String::new();
+        let found = self.descend_prefix(prefix, |index, ancestor| {
+            path.try_reserve(1)?;
+            path.push(index);
+            existing_prefix.try_reserve(ancestor.len())?;
+            existing_prefix.push_str(ancestor);
+            Ok::<_, TryReserveError>(())
+        })?;
+        let mut trie = RadixTrie::new();
+        if !found {
+            return Ok(trie);
+        }
+        trie.entry.children_mut().try_reserve_exact(1)?;
+        self.element_at_mut(&path)
+            .try_reserve_label(existing_prefix.len())?;
+        let (&target_index, ancestors) = path.split_last().unwrap();
+        // a node left with one child is merged into it, taking the label of the node
+        let parent = self.element_at_mut(ancestors);
+        if parent.is_node() && parent.children().len() == 2 {
+            let parent_len = parent.label().len();
+            parent.children_mut()[1 - target_index].try_reserve_label(parent_len)?;
+        }
+        let mut detached = self.detach_at(&path);
+        trie.len = detached.value_count();
+        detached.add_label_prefix(existing_prefix);
+        trie.entry.children_mut().push(detached);
+        Ok(trie)
+    }
+
+    /// Moves all labels and values of the other trie into this one like [`RadixTrie::merge`],
+    /// but reports a failed allocation instead of aborting.
+    /// The values are moved one by one with [`RadixTrie::try_insert_reserve`],
+    /// so on failure this trie holds the labels moved so far and stays valid,
+    /// while the rest of the other trie is dropped
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("label", 5);
+    /// let mut other = RadixTrie::<usize>::new();
+    /// other.insert("label", 1);
+    /// other.insert("lazy", 4);
+    /// trie.try_merge_reserve(other, |mine, theirs| mine + theirs).unwrap();
+    /// assert_eq!(trie.find("label"), Some(&6));
+    /// assert_eq!(trie.len(), 2);
+    /// ```
+    pub fn try_merge_reserve<F>(
+        &mut self,
+        mut other: RadixTrie<T>,
+        mut resolve: F,
+    ) -> Result<(), TryReserveError>
+    where
+        F: FnMut(T, T) -> T,
+    {
+        // every element is taken with the length of the label of its parent
+        let mut stack = Vec::new();
+        stack.try_reserve_exact(other.entry.children().len())?;
+        stack.extend(
+            core::mem::take(other.entry.children_mut())
+                .into_iter()
+                .map(|child| (0, child)),
+        );
+        let mut label = String::new();
+        while let Some((parent_len, element)) = stack.pop() {
+            let (own_label, value, children) = element.unpack();
+            label.truncate(parent_len);
+            label.try_reserve(own_label.len())?;
+            label.push_str(&own_label);
+            stack.try_reserve(children.len())?;
+            let label_len = label.len();
+            stack.extend(children.into_iter().map(|child| (label_len, child)));
+            if let Some(value) = value {
+                self.try_insert_reserve_with(&label, value, |element, theirs| {
+                    let mine = element.take_value().unwrap();
+                    Element::node_to_value(element, resolve(mine, theirs));
+                    None
+                })
+                .map_err(|error| error.error)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Allocates what inserting the label at the position below the path needs,
+    /// including the room for a new element in the entry
+    fn reserve_at(
+        &mut self,
+        path: &[usize],
+        label: &str,
+        position: &Position,
+    ) -> Result<Reservation<T>, TryReserveError> {
+        let reservation = Reservation::try_new(label, position)?;
+        if let Position::At(_) = position {
+            self.element_at_mut(path).children_mut().try_reserve(1)?;
+        }
+        Ok(reservation)
+    }
+}
+
+#[cfg(test)]
+mod reserve_tests {
+    use crate::error::TrieError;
+    use crate::test_util::{mixed_words_trie, with_allocation_budget, MIXED_WORDS};
+    use crate::trie::RadixTrie;
+
+    fn structure<T: core::fmt::Debug>(trie: &RadixTrie<T>) -> String {
+        format!("{:?}", trie.entry)
+    }
+
+    #[test]
+    fn test_try_insert_reserve_matches_insert() {
+        let mut trie = RadixTrie::<usize>::new();
+        let mut expected = RadixTrie::<usize>::new();
+        let words = [
+            "Wonder",
+            "Won",
+            "Wonderful",
+            "World",
+            "Wo",
+            "Axes",
+            "Won",
+            "多倫多",
+            "多",
+        ];
+        for (index, word) in words.iter().enumerate() {
+            assert_eq!(
+                trie.try_insert_reserve(word, index).ok(),
+                Some(expected.insert(word, index))
+            );
+            assert_eq!(trie.validate(), Ok(()));
+        }
+        assert_eq!(trie.try_insert_reserve("", 0).ok(), Some(None));
+        assert_eq!(trie, expected);
+        assert_eq!(format!("{:?}", trie.entry), format!("{:?}", expected.entry));
+    }
+
+    #[test]
+    fn test_failed_insert_leaves_trie_unchanged() {
+        let base = mixed_words_trie();
+        // a new leaf, a prefix of a leaf, a split, a valueless node, and an occupied label
+        for label in &["B", "Wond", "Wox", "Wo", "Won", "多倫"] {
+            let mut expected = base.clone();
+            let replaced = expected.insert(label, 0);
+            for budget in 0.. {
+                let mut trie = base.clone();
+                match with_allocation_budget(budget, || trie.try_insert_reserve(label, 0)) {
+                    Ok(res) => {
+                        assert_eq!(res, replaced);
+                        assert_eq!(structure(&trie), structure(&expected));
+                        break;
+                    }
+                    Err(error) => {
+                        assert_eq!(error.value, 0);
+                        assert_eq!(structure(&trie), structure(&base));
+                        assert_eq!(trie.len(), base.len());
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_failed_split_off_leaves_trie_unchanged() {
+        let base = mixed_words_trie();
+        for prefix in &["Wonder", "Wonderful", "Wo", "Won", "多", "x", ""] {
+            let mut expected = base.clone();
+            let expected_split = expected.split_off(prefix);
+            for budget in 0.. {
+                let mut trie = base.clone();
+                match with_allocation_budget(budget, || trie.try_split_off_reserve(prefix)) {
+                    Ok(split) => {
+                        assert_eq!(structure(&split), structure(&expected_split));
+                        assert_eq!(structure(&trie), structure(&expected));
+                        assert_eq!(
+                            (split.len(), trie.len()),
+                            (expected_split.len(), expected.len())
+                        );
+                        break;
+                    }
+                    Err(_) => assert_eq!(structure(&trie), structure(&base)),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_failed_bulk_writes_keep_trie_valid() {
+        let entries = [("Wox", 1), ("Wond", 2), ("B", 3), ("Won", 4), ("多倫", 5)];
+        let mut other = RadixTrie::new();
+        for (label, value) in &entries {
+            other.insert(label, *value);
+        }
+        let mut merged = mixed_words_trie();
+        merged.merge(other.clone(), |mine, theirs| mine + theirs);
+        let mut extended = mixed_words_trie();
+        extended.extend(entries.iter().copied());
+        let mut failures = 0;
+        for budget in 0.. {
+            let mut trie = mixed_words_trie();
+            let other = other.clone();
+            let res = with_allocation_budget(budget, || {
+                trie.try_merge_reserve(other, |mine, theirs| mine + theirs)
+            });
+            assert_eq!(trie.validate(), Ok(()));
+            if res.is_ok() {
+                assert_eq!(structure(&trie), structure(&merged));
+                break;
+            }
+            failures += 1;
+            assert!(trie.len() < merged.len() || trie.find("Won") == Some(&3));
+        }
+        for budget in 0.. {
+            let mut trie = mixed_words_trie();
+            let res =
+                with_allocation_budget(budget, || trie.try_extend_reserve(entries.iter().copied()));
+            assert_eq!(trie.validate(), Ok(()));
+            if res.is_ok() {
+                assert_eq!(structure(&trie), structure(&extended));
+                break;
+            }
+            failures += 1;
+        }
+        assert!(failures > 2);
+    }
+
+    #[test]
+    fn test_failed_import_sorted() {
+        let trie = mixed_words_trie();
+        let sorted = trie
+            .iter()
+            .map(|(label, value)| (label, *value))
+            .collect::<Vec<_>>();
+        for budget in 0.. {
+            let entries = sorted.clone();
+            let res =
+                with_allocation_budget(budget, || RadixTrie::try_import_sorted_reserve(entries));
+            match res {
+                Ok(imported) => {
+                    assert_eq!(structure(&imported), structure(&trie));
+                    assert_eq!(imported.len(), MIXED_WORDS.len());
+                    break;
+                }
+                Err(error) => assert_eq!(error, TrieError::AllocationFailed),
+            }
+        }
+        let unsorted = RadixTrie::try_import_sorted_reserve(vec![("b", 1), ("a", 2)]);
+        assert_eq!(unsorted.err(), Some(TrieError::UnsortedLabels));
+    }
+}
@@ -0,0 +1,245 @@
+use crate::element::Element;
+use crate::trie::RadixTrie;
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// An element being rebuilt on the current path of `retain`
+struct Frame<T> {
+    label: String,
+    value: Option<T>,
+    /// The children not visited yet
+    children: alloc::vec::IntoIter<Element<T>>,
+    /// The visited children that still hold values
+    kept: Vec<Element<T>>,
+}
+
+impl<T> Frame<T> {
+    /// Finish the element, dropping it if nothing is left
+    /// and merging it into its only child if it has no value
+    fn into_element(self) -> Option<Element<T>> {
+        let Frame {
+            label,
+            value,
+            mut kept,
+            ..
+        } = self;
+        if value.is_none() && kept.len() <= 1 {
+            let mut child = kept.pop()?;
+            child.add_label_prefix(label);
+            return Some(child);
+        }
+        Some(Element::pack(label, value, kept))
+    }
+}
+
+impl<T> RadixTrie<T> {
+    /// Keeps only the values for which the predicate returns true, in one traversal.
+    /// The predicate is called in lexicographic order of labels,
+    /// and the elements left without value are compacted on the way back up
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("lab", 3);
+    /// trie.insert("label", 5);
+    /// trie.insert("lazy", 4);
+    /// trie.retain(|_, age| *age < 5);
+    /// assert_eq!(trie.find("label"), None);
+    /// assert_eq!(trie.len(), 2);
+    /// ```
+    pub fn retain<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(&str, &mut T) -> bool,
+    {
+        let children = core::mem::take(self.entry.children_mut());
+        let mut removed = 0;
+        *self.entry.children_mut() =
+            retain_children(children, &mut String::new(), &mut keep, |_, _| removed += 1);
+        self.len -= removed;
+    }
+
+    /// Removes the values of the labels starting with given prefix for which the predicate returns true,
+    /// descending to the prefix once. The removed labels and values are returned in lexicographic order,
+    /// and the elements left without value are compacted on the way back up
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("/tmp/a", 1);
+    /// trie.insert("/tmp/b", 2);
+    /// trie.insert("/usr/c", 3);
+    /// let removed = trie.remove_prefix_if("/", |_, size| *size >= 2);
+    /// assert_eq!(removed, vec![(String::from("/tmp/b"), 2), (String::from("/usr/c"), 3)]);
+    /// assert_eq!(trie.len(), 1);
+    /// ```
+    pub fn remove_prefix_if<F>(&mut self, prefix: &str, mut remove: F) -> Vec<(String, T)>
+    where
+        F: FnMut(&str, &T) -> bool,
+    {
+        let mut removed = vec![];
+        let mut keep = |label: &str, value: &mut T| !remove(label, value);
+        let mut collect = |label: &str, value| removed.push((label.to_owned(), value));
+        let mut label = String::new();
+        if prefix.is_empty() {
+            let children = core::mem::take(self.entry.children_mut());
+            *self.entry.children_mut() = retain_children(children, &mut label, &mut keep, collect);
+            self.len -= removed.len();
+            return removed;
+        }
+        let (path, mut label) = match self.locate_prefix(prefix) {
+            Some(found) => found,
+            None => return removed,
+        };
+        let (&target_index, ancestors) = path.split_last().unwrap();
+        let parent = self.element_at_mut(ancestors);
+        let target = parent.children_mut().remove(target_index);
+        let kept = retain_children(vec![target], &mut label, &mut keep, &mut collect);
+        if let Some(element) = kept.into_iter().next() {
+            parent.children_mut().insert(target_index, element);
+        }
+        self.finish_removal(ancestors, removed.len());
+        removed
+    }
+}
+
+/// Keeps the values of the elements and their descendants for which `keep` returns true,
+/// handing the others to `remove` in lexicographic order of labels.
+/// The label holds the joined labels of the ancestors of the elements.
+/// Returns the elements left, compacted, in the same order
+fn retain_children<T, K, R>(
+    children: Vec<Element<T>>,
+    label: &mut String,
+    keep: &mut K,
+    mut remove: R,
+) -> Vec<Element<T>>
+where
+    K: FnMut(&str, &mut T) -> bool,
+    R: FnMut(&str, T),
+{
+    let mut stack = vec![Frame {
+        label: String::new(),
+        value: None,
+        kept: Vec::with_capacity(children.len()),
+        children: children.into_iter(),
+    }];
+    loop {
+        let top = stack.last_mut().unwrap();
+        if let Some(child) = top.children.next() {
+            let (child_label, mut value, children) = child.unpack();
+            label.push_str(&child_label);
+            if let Some(mut current) = value.take() {
+                if keep(label, &mut current) {
+                    value = Some(current);
+                } else {
+                    remove(label, current);
+                }
+            }
+            stack.push(Frame {
+                label: child_label,
+                value,
+                kept: Vec::with_capacity(children.len()),
+                children: children.into_iter(),
+            });
+            continue;
+        }
+        let finished = stack.pop().unwrap();
+        match stack.last_mut() {
+            Some(parent) => {
+                label.truncate(label.len() - finished.label.len());
+                parent.kept.extend(finished.into_element());
+            }
+            None => return finished.kept,
+        }
+    }
+}
+
+#[cfg(test)]
+mod retain_tests {
+    use crate::trie::RadixTrie;
+
+    #[test]
+    fn test_retain_matches_insertion() {
+        let words = [
+            "Won",
+            "Wonder",
+            "Wonderful",
+            "World",
+            "Axes",
+            "Ax",
+            "多倫多",
+        ];
+        let mut trie = RadixTrie::<usize>::new();
+        for word in &words {
+            trie.insert(word, word.len());
+        }
+        let mut visited = vec![];
+        trie.retain(|label, value| {
+            visited.push(label.to_owned());
+            *value += 1;
+            label.len() % 2 == 0
+        });
+        let mut expected = RadixTrie::<usize>::new();
+        for word in words.iter().filter(|word| word.len() % 2 == 0) {
+            expected.insert(word, word.len() + 1);
+        }
+        assert_eq!(format!("{:?}", trie.entry), format!("{:?}", expected.entry));
+        assert_eq!(trie.len(), expected.len());
+        let mut sorted = words
+            .iter()
+            .map(|word| word.to_string())
+            .collect::<Vec<_>>();
+        sorted.sort();
+        assert_eq!(visited, sorted);
+    }
+
+    #[test]
+    fn test_retain_nothing_and_everything() {
+        let mut trie = RadixTrie::<usize>::new();
+        for word in &["Won", "Wonder", "World"] {
+            trie.insert(word, word.len());
+        }
+        trie.retain(|_, _| true);
+        assert_eq!(trie.len(), 3);
+        assert_eq!(trie.find("Wonder"), Some(&6));
+        trie.retain(|_, _| false);
+        assert!(trie.is_empty());
+        assert!(trie.entry.children().is_empty());
+    }
+
+    #[test]
+    fn test_remove_prefix_if() {
+        let words = [
+            "Won",
+            "Wonder",
+            "Wonderful",
+            "World",
+            "Wo",
+            "Axes",
+            "Ax",
+            "多倫多",
+        ];
+        for prefix in &["", "W", "Wo", "Won", "Wonderful", "Wonders", "A", "多", "Z"] {
+            let mut trie = RadixTrie::<usize>::new();
+            for word in &words {
+                trie.insert(word, word.len());
+            }
+            let removed = trie.remove_prefix_if(prefix, |_, len| len % 2 == 1);
+            let mut expected = RadixTrie::<usize>::new();
+            let mut expected_removed = vec![];
+            for word in &words {
+                if word.starts_with(prefix) && word.len() % 2 == 1 {
+                    expected_removed.push((word.to_string(), word.len()));
+                } else {
+                    expected.insert(word, word.len());
+                }
+            }
+            expected_removed.sort();
+            assert_eq!(removed, expected_removed, "{}", prefix);
+            assert_eq!(format!("{:?}", trie.entry), format!("{:?}", expected.entry));
+            assert_eq!(trie.len(), expected.len());
+        }
+    }
+}
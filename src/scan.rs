@@ -0,0 +1,123 @@
+use crate::trie::RadixTrie;
+
+/// A label found in the scanned text, yielded by [`Scan`]
+#[derive(Debug, PartialEq, Eq)]
+pub struct Match<'a, T> {
+    /// Byte offset of the match in the text
+    pub start: usize,
+    /// Byte offset right after the match in the text
+    pub end: usize,
+    /// The matched part of the text, which is a label in the trie
+    pub text: &'a str,
+    /// The value of the matched label
+    pub value: &'a T,
+}
+
+impl<'a, T> Clone for Match<'a, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T> Copy for Match<'a, T> {}
+
+/// Iterator over the longest labels matching the text from left to right, without overlap.
+///
+/// Created by [`RadixTrie::scan`]
+pub struct Scan<'a, T> {
+    trie: &'a RadixTrie<T>,
+    text: &'a str,
+    /// Byte offset where the next match is searched
+    position: usize,
+}
+
+impl<'a, T> Iterator for Scan<'a, T> {
+    type Item = Match<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.position < self.text.len() {
+            let rest = &self.text[self.position..];
+            if let Some((matched, value)) = self.trie.longest_prefix(rest) {
+                let start = self.position;
+                self.position += matched.len();
+                return Some(Match {
+                    start,
+                    end: self.position,
+                    text: matched,
+                    value,
+                });
+            }
+            // nothing starts here, try from the next char
+            self.position += rest.chars().next().unwrap().len_utf8();
+        }
+        None
+    }
+}
+
+impl<T> RadixTrie<T> {
+    /// Returns a lazy iterator segmenting the text with the labels of the trie.
+    /// At each position the longest matching label is taken and the scan continues after it.
+    /// Text not covered by any label is skipped one char at a time
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<&str>::new();
+    /// trie.insert("New", "adj");
+    /// trie.insert("New York", "city");
+    /// trie.insert("York", "city");
+    /// let res = trie
+    ///     .scan("New York, New Jersey")
+    ///     .map(|found| (found.start, found.text, *found.value))
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(res, vec![(0, "New York", "city"), (10, "New", "adj")]);
+    /// ```
+    pub fn scan<'a>(&'a self, text: &'a str) -> Scan<'a, T> {
+        Scan {
+            trie: self,
+            text,
+            position: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod scan_tests {
+    use crate::trie::RadixTrie;
+
+    #[test]
+    fn test_scan_longest_match() {
+        let mut trie = RadixTrie::<usize>::new();
+        for (index, word) in ["多倫多", "多", "大學", "倫敦", "Won", "Wonder"]
+            .iter()
+            .enumerate()
+        {
+            trie.insert(word, index);
+        }
+        let text = "多倫多大學 Wonderful 多倫敦";
+        let res = trie
+            .scan(text)
+            .map(|found| {
+                assert_eq!(&text[found.start..found.end], found.text);
+                (found.text, *found.value)
+            })
+            .collect::<Vec<_>>();
+        let expected = vec![
+            ("多倫多", 0),
+            ("大學", 2),
+            ("Wonder", 5),
+            ("多", 1),
+            ("倫敦", 3),
+        ];
+        assert_eq!(res, expected);
+    }
+
+    #[test]
+    fn test_scan_without_match() {
+        let mut trie = RadixTrie::<usize>::new();
+        trie.insert("abc", 0);
+        assert_eq!(trie.scan("ab ab").next(), None);
+        assert_eq!(trie.scan("").next(), None);
+        assert_eq!(RadixTrie::<usize>::new().scan("abc").count(), 0);
+    }
+}
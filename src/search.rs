@@ -0,0 +1,423 @@
+use crate::element::Element;
+use crate::trie::RadixTrie;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Limits of a prefix search run by [`RadixTrie::find_prefix_with`].
+/// The default options put no limit on the search
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct SearchOptions {
+    /// Stop the search once this many labels are found
+    pub max_results: Option<usize>,
+    /// Do not descend more than this many label segments below the prefix,
+    /// counting the segment the prefix ends in as the first
+    pub max_depth: Option<usize>,
+    /// Skip labels shorter than this many bytes
+    pub min_key_len: usize,
+}
+
+impl<T> RadixTrie<T> {
+    /// Returns all labels within the given Levenshtein distance of the query,
+    /// with their values and distances, in lexicographic order.
+    /// Branches are pruned as soon as no label below them can be close enough
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<()>::new();
+    /// trie.insert("hello", ());
+    /// trie.insert("help", ());
+    /// trie.insert("world", ());
+    /// let res = trie.find_fuzzy("helo", 1);
+    /// assert_eq!(res, vec![(String::from("hello"), &(), 1), (String::from("help"), &(), 1)]);
+    /// ```
+    pub fn find_fuzzy(&self, query: &str, max_distance: usize) -> Vec<(String, &T, usize)> {
+        let query = query.chars().collect::<Vec<_>>();
+        let mut res = vec![];
+        let mut label = String::new();
+        // each element is paired with the length of its parent label
+        // and the distances between the parent label and every prefix of the query
+        let first_row = (0..=query.len()).collect::<Vec<_>>();
+        let mut stack: Vec<(usize, &Element<T>, Vec<usize>)> = self
+            .entry
+            .children()
+            .iter()
+            .rev()
+            .map(|child| (0, child, first_row.clone()))
+            .collect();
+        'elements: while let Some((prefix_len, element, mut row)) = stack.pop() {
+            label.truncate(prefix_len);
+            for char in element.label().chars() {
+                label.push(char);
+                row = next_row(&row, &query, char);
+                if row.iter().min().unwrap() > &max_distance {
+                    continue 'elements;
+                }
+            }
+            let distance = row[query.len()];
+            if let Some(value) = element.value().filter(|_| distance <= max_distance) {
+                res.push((label.clone(), value, distance));
+            }
+            let children = element.children().iter().rev();
+            stack.extend(children.map(|child| (label.len(), child, row.clone())));
+        }
+        res
+    }
+
+    /// Returns all labels matching the wildcard pattern with their values, in lexicographic order.
+    /// `*` matches any run of chars, including none, and `?` matches exactly one char.
+    /// Branches are pruned as soon as the pattern cannot match below them
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("industrialization", 1);
+    /// trie.insert("industrial", 2);
+    /// trie.insert("india", 3);
+    /// assert_eq!(trie.find_pattern("ind*ial?zation"), vec![(String::from("industrialization"), &1)]);
+    /// assert_eq!(trie.find_pattern("ind?a"), vec![(String::from("india"), &3)]);
+    /// ```
+    pub fn find_pattern(&self, pattern: &str) -> Vec<(String, &T)> {
+        let pattern = pattern.chars().collect::<Vec<_>>();
+        let mut res = vec![];
+        let mut label = String::new();
+        // each element is paired with the length of its parent label
+        // and the pattern positions reachable after the parent label
+        let start = closure(&pattern, vec![0]);
+        let mut stack: Vec<(usize, &Element<T>, Vec<usize>)> = self
+            .entry
+            .children()
+            .iter()
+            .rev()
+            .map(|child| (0, child, start.clone()))
+            .collect();
+        'elements: while let Some((prefix_len, element, mut states)) = stack.pop() {
+            label.truncate(prefix_len);
+            for char in element.label().chars() {
+                label.push(char);
+                states = next_states(&pattern, &states, char);
+                if states.is_empty() {
+                    continue 'elements;
+                }
+            }
+            if element.value().is_some() && states.contains(&pattern.len()) {
+                res.push((label.clone(), element.value().unwrap()));
+            }
+            let children = element.children().iter().rev();
+            stack.extend(children.map(|child| (label.len(), child, states.clone())));
+        }
+        res
+    }
+
+    /// Returns the labels equal to the key when ASCII case is ignored, with their values,
+    /// in lexicographic order. The labels are returned with the casing they were inserted with
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("README.md", 1);
+    /// trie.insert("readme.md", 2);
+    /// trie.insert("ReadMe", 3);
+    /// let res = trie.find_ignore_case("readme.MD");
+    /// assert_eq!(res, vec![(String::from("README.md"), &1), (String::from("readme.md"), &2)]);
+    /// ```
+    pub fn find_ignore_case(&self, key: &str) -> Vec<(String, &T)> {
+        self.matches_ignore_case(key, false)
+    }
+
+    /// Returns the labels starting with the prefix when ASCII case is ignored, with their values,
+    /// in lexicographic order. The labels are returned with the casing they were inserted with
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("Toronto", 1);
+    /// trie.insert("TORONTO多倫多", 2);
+    /// trie.insert("Tokyo", 3);
+    /// let res = trie.find_prefix_ignore_case("toR");
+    /// assert_eq!(res, vec![(String::from("TORONTO多倫多"), &2), (String::from("Toronto"), &1)]);
+    /// ```
+    pub fn find_prefix_ignore_case(&self, prefix: &str) -> Vec<(String, &T)> {
+        self.matches_ignore_case(prefix, true)
+    }
+
+    /// Returns the labels starting with given prefix with their values, in lexicographic order,
+    /// within the limits of the options.
+    /// The search stops as soon as enough labels are found, and never enters
+    /// elements deeper than `max_depth`, so the rest of a large subtree is not visited
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::{RadixTrie, SearchOptions};
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// for (index, word) in ["a", "ab", "abc", "abd", "ac"].iter().enumerate() {
+    ///     trie.insert(word, index);
+    /// }
+    /// let options = SearchOptions { max_results: Some(2), min_key_len: 2, ..Default::default() };
+    /// let res = trie.find_prefix_with("a", options);
+    /// assert_eq!(res, vec![(String::from("ab"), &1), (String::from("abc"), &2)]);
+    /// let shallow = SearchOptions { max_depth: Some(1), ..Default::default() };
+    /// assert_eq!(trie.find_prefix_with("ab", shallow), vec![(String::from("ab"), &1)]);
+    /// ```
+    pub fn find_prefix_with(&self, prefix: &str, options: SearchOptions) -> Vec<(String, &T)> {
+        let mut res = vec![];
+        let mut label = String::new();
+        let (depth, element) = if prefix.is_empty() {
+            (0, &self.entry)
+        } else {
+            match self.find_prefix_element(prefix, |parent| label.push_str(parent)) {
+                Some(element) => (1, element),
+                None => return res,
+            }
+        };
+        let max_results = options.max_results.unwrap_or(usize::MAX);
+        let max_depth = options.max_depth.unwrap_or(usize::MAX);
+        if max_results == 0 {
+            return res;
+        }
+        // each element is paired with its depth below the prefix and the length of its parent label
+        let mut stack = vec![(depth, label.len(), element)];
+        while let Some((depth, prefix_len, element)) = stack.pop() {
+            if depth > max_depth {
+                continue;
+            }
+            label.truncate(prefix_len);
+            label.push_str(element.label());
+            if let Some(value) = element
+                .value()
+                .filter(|_| label.len() >= options.min_key_len)
+            {
+                res.push((label.clone(), value));
+                if res.len() == max_results {
+                    break;
+                }
+            }
+            let children = element.children().iter().rev();
+            stack.extend(children.map(|child| (depth + 1, label.len(), child)));
+        }
+        res
+    }
+
+    /// Collect the labels matching the query with ASCII case ignored,
+    /// either exactly or, when `prefix` is set, as a prefix.
+    /// Ignoring ASCII case keeps byte lengths, so labels are compared byte by byte
+    fn matches_ignore_case(&self, query: &str, prefix: bool) -> Vec<(String, &T)> {
+        let query = query.as_bytes();
+        let mut res = vec![];
+        let mut label = String::new();
+        // each element is paired with the length of its parent label
+        // and the number of query bytes matched by its ancestors
+        let mut stack: Vec<(usize, usize, &Element<T>)> = self
+            .entry
+            .children()
+            .iter()
+            .rev()
+            .map(|child| (0, 0, child))
+            .collect();
+        while let Some((prefix_len, matched, element)) = stack.pop() {
+            label.truncate(prefix_len);
+            let rest = &query[matched..];
+            let own = element.label().as_bytes();
+            if rest.len() <= own.len() {
+                if !own[..rest.len()].eq_ignore_ascii_case(rest) {
+                    continue;
+                }
+                if prefix {
+                    element
+                        .for_each_value(&mut label, |label, value| res.push((label.into(), value)));
+                } else if let Some(value) = element.value().filter(|_| rest.len() == own.len()) {
+                    label.push_str(element.label());
+                    res.push((label.clone(), value));
+                }
+            } else if rest[..own.len()].eq_ignore_ascii_case(own) {
+                label.push_str(element.label());
+                let children = element.children().iter().rev();
+                stack.extend(children.map(|child| (label.len(), matched + own.len(), child)));
+            }
+        }
+        res
+    }
+}
+
+/// Compute the edit distances after appending the char to the label from the previous row
+fn next_row(row: &[usize], query: &[char], char: char) -> Vec<usize> {
+    let mut next = Vec::with_capacity(row.len());
+    next.push(row[0] + 1);
+    for (index, query_char) in query.iter().enumerate() {
+        let substitution = row[index] + (*query_char != char) as usize;
+        let insertion = next[index] + 1;
+        let deletion = row[index + 1] + 1;
+        next.push(substitution.min(insertion).min(deletion));
+    }
+    next
+}
+
+/// Advance the reachable pattern positions by one char of the label
+fn next_states(pattern: &[char], states: &[usize], char: char) -> Vec<usize> {
+    let mut next = vec![];
+    for &state in states {
+        match pattern.get(state) {
+            Some('*') => next.push(state),
+            Some('?') => next.push(state + 1),
+            Some(expected) if *expected == char => next.push(state + 1),
+            _ => (),
+        }
+    }
+    closure(pattern, next)
+}
+
+/// Add the positions reachable by letting `*` match nothing, sorted without duplicates
+fn closure(pattern: &[char], mut states: Vec<usize>) -> Vec<usize> {
+    let mut index = 0;
+    while index < states.len() {
+        if pattern.get(states[index]) == Some(&'*') {
+            states.push(states[index] + 1);
+        }
+        index += 1;
+    }
+    states.sort_unstable();
+    states.dedup();
+    states
+}
+
+#[cfg(test)]
+mod search_tests {
+    use crate::search::SearchOptions;
+    use crate::trie::RadixTrie;
+
+    fn get_test_trie() -> RadixTrie<()> {
+        let mut trie = RadixTrie::new();
+        let words = [
+            "Won",
+            "Wonder",
+            "Wonderful",
+            "World",
+            "Axes",
+            "多倫多",
+            "多伦多",
+        ];
+        for word in &words {
+            trie.insert(word, ());
+        }
+        trie
+    }
+
+    fn labels(res: Vec<(String, &(), usize)>) -> Vec<(String, usize)> {
+        res.into_iter()
+            .map(|(label, _, distance)| (label, distance))
+            .collect()
+    }
+
+    #[test]
+    fn test_find_fuzzy() {
+        let trie = get_test_trie();
+        assert_eq!(
+            labels(trie.find_fuzzy("Word", 1)),
+            vec![("World".into(), 1)]
+        );
+        let expected: Vec<(String, usize)> = vec![("Won".into(), 2), ("Wonder".into(), 1)];
+        assert_eq!(labels(trie.find_fuzzy("Wonde", 2)), expected);
+        let expected: Vec<(String, usize)> = vec![("多伦多".into(), 0), ("多倫多".into(), 1)];
+        assert_eq!(labels(trie.find_fuzzy("多伦多", 1)), expected);
+        assert_eq!(labels(trie.find_fuzzy("Axes", 0)), vec![("Axes".into(), 0)]);
+        assert!(trie.find_fuzzy("Zebra", 2).is_empty());
+    }
+
+    #[test]
+    fn test_find_pattern() {
+        let trie = get_test_trie();
+        let labels = |pattern| {
+            trie.find_pattern(pattern)
+                .into_iter()
+                .map(|(label, _)| label)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(labels("Won*"), vec!["Won", "Wonder", "Wonderful"]);
+        assert_eq!(labels("W*r*"), vec!["Wonder", "Wonderful", "World"]);
+        assert_eq!(labels("Wo?"), vec!["Won"]);
+        assert_eq!(labels("*e*"), vec!["Axes", "Wonder", "Wonderful"]);
+        assert_eq!(labels("多?多"), vec!["多伦多", "多倫多"]);
+        assert_eq!(labels("*"), labels("**"));
+        assert_eq!(labels("*").len(), 7);
+        assert!(labels("Wonder").len() == 1 && labels("Wond").is_empty());
+    }
+
+    #[test]
+    fn test_ignore_case() {
+        let mut trie = get_test_trie();
+        for word in &["WONDER", "wonderland", "world", "多倫多Won"] {
+            trie.insert(word, ());
+        }
+        let labels =
+            |res: Vec<(String, &())>| res.into_iter().map(|(label, _)| label).collect::<Vec<_>>();
+        assert_eq!(
+            labels(trie.find_ignore_case("wonder")),
+            vec!["WONDER", "Wonder"]
+        );
+        assert_eq!(
+            labels(trie.find_ignore_case("WORLD")),
+            vec!["World", "world"]
+        );
+        assert_eq!(labels(trie.find_ignore_case("多倫多")), vec!["多倫多"]);
+        assert!(trie.find_ignore_case("wond").is_empty());
+        assert!(trie.find_ignore_case("").is_empty());
+        assert_eq!(
+            labels(trie.find_prefix_ignore_case("WONDER")),
+            vec!["WONDER", "Wonder", "Wonderful", "wonderland"]
+        );
+        assert_eq!(
+            labels(trie.find_prefix_ignore_case("多倫多w")),
+            vec!["多倫多Won"]
+        );
+        assert_eq!(labels(trie.find_prefix_ignore_case("wOr")).len(), 2);
+        assert!(trie.find_prefix_ignore_case("多倫多x").is_empty());
+        assert_eq!(trie.find_prefix_ignore_case("").len(), trie.len());
+    }
+
+    #[test]
+    fn test_find_prefix_with() {
+        let trie = get_test_trie();
+        let labels = |options| {
+            trie.find_prefix_with("Wo", options)
+                .into_iter()
+                .map(|(label, _)| label)
+                .collect::<Vec<_>>()
+        };
+        let all = trie
+            .find_prefix_iter("Wo")
+            .map(|(label, _)| label)
+            .collect::<Vec<_>>();
+        assert_eq!(labels(SearchOptions::default()), all);
+        let first = SearchOptions {
+            max_results: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(labels(first), vec!["Won", "Wonder"]);
+        // Wo -> (n -> der -> ful, rld)
+        let shallow = SearchOptions {
+            max_depth: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(labels(shallow), vec!["Won", "World"]);
+        let long = SearchOptions {
+            min_key_len: 6,
+            ..Default::default()
+        };
+        assert_eq!(labels(long), vec!["Wonder", "Wonderful"]);
+        let none = SearchOptions {
+            max_results: Some(0),
+            ..Default::default()
+        };
+        assert!(labels(none).is_empty());
+        let top = SearchOptions {
+            max_depth: Some(1),
+            ..Default::default()
+        };
+        let top_level = trie.find_prefix_with("", top);
+        assert_eq!(top_level, vec![(String::from("Axes"), &())]);
+        assert!(trie.find_prefix_with("Wx", top).is_empty());
+    }
+}
@@ -0,0 +1,404 @@
+use crate::element::Element;
+use crate::iter::{Keys, Traversal};
+use crate::trie::RadixTrie;
+use crate::util;
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+use core::iter::FromIterator;
+
+/// A set of strings stored in a radix trie without values.
+///
+/// # Example
+/// ```rust
+/// use another_radix_trie::RadixSet;
+///
+/// let mut set = RadixSet::new();
+/// assert!(set.insert("Toronto"));
+/// assert!(!set.insert("Toronto"));
+/// assert!(set.contains("Toronto"));
+/// assert_eq!(set.find_prefix_iter("Tor").collect::<Vec<_>>(), vec!["Toronto"]);
+/// ```
+#[derive(Default, Clone, PartialEq, Eq, Hash)]
+pub struct RadixSet {
+    trie: RadixTrie<()>,
+}
+
+impl RadixSet {
+    /// Construct an empty set
+    pub fn new() -> Self {
+        RadixSet {
+            trie: RadixTrie::new(),
+        }
+    }
+
+    /// Returns the number of labels in the set
+    pub fn len(&self) -> usize {
+        self.trie.len()
+    }
+
+    /// Returns true if the set holds no label
+    pub fn is_empty(&self) -> bool {
+        self.trie.is_empty()
+    }
+
+    /// Adds the label to the set.
+    /// Returns true if the label was not in the set before.
+    /// The empty label is never added
+    pub fn insert(&mut self, label: &str) -> bool {
+        let len = self.trie.len();
+        self.trie.insert(label, ());
+        self.trie.len() != len
+    }
+
+    /// Returns true if the label is in the set
+    pub fn contains(&self, label: &str) -> bool {
+        self.trie.find(label).is_some()
+    }
+
+    /// Removes the label from the set.
+    /// Returns true if the label was in the set
+    pub fn remove(&mut self, label: &str) -> bool {
+        self.trie.remove(label).is_some()
+    }
+
+    /// Returns a lazy iterator over the labels in lexicographic order
+    pub fn iter(&self) -> Keys<'_, ()> {
+        self.trie.keys()
+    }
+
+    /// Returns a lazy iterator over the labels starting with given prefix, in lexicographic order
+    pub fn find_prefix_iter(&self, prefix: &str) -> Keys<'_, ()> {
+        let mut existing_prefix = String::new();
+        let traversal = match self
+            .trie
+            .find_prefix_element(prefix, |label| existing_prefix.push_str(label))
+        {
+            Some(element) => Traversal::new(element, existing_prefix, 0),
+            None => Traversal::empty(),
+        };
+        Keys { traversal }
+    }
+
+    /// Returns the set of labels in either set
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixSet;
+    ///
+    /// let set1 = vec!["lab", "label"].into_iter().collect::<RadixSet>();
+    /// let set2 = vec!["label", "lazy"].into_iter().collect::<RadixSet>();
+    /// let union = set1.union(&set2);
+    /// assert_eq!(union.iter().collect::<Vec<_>>(), vec!["lab", "label", "lazy"]);
+    /// ```
+    pub fn union(&self, other: &RadixSet) -> RadixSet {
+        self.combine(other, |in_self, in_other| in_self || in_other)
+    }
+
+    /// Returns the set of labels in both sets
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixSet;
+    ///
+    /// let set1 = vec!["lab", "label"].into_iter().collect::<RadixSet>();
+    /// let set2 = vec!["label", "lazy"].into_iter().collect::<RadixSet>();
+    /// let intersection = set1.intersection(&set2);
+    /// assert_eq!(intersection.iter().collect::<Vec<_>>(), vec!["label"]);
+    /// ```
+    pub fn intersection(&self, other: &RadixSet) -> RadixSet {
+        self.combine(other, |in_self, in_other| in_self && in_other)
+    }
+
+    /// Returns the set of labels in this set but not in the other
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixSet;
+    ///
+    /// let set1 = vec!["lab", "label"].into_iter().collect::<RadixSet>();
+    /// let set2 = vec!["label", "lazy"].into_iter().collect::<RadixSet>();
+    /// let difference = set1.difference(&set2);
+    /// assert_eq!(difference.iter().collect::<Vec<_>>(), vec!["lab"]);
+    /// ```
+    pub fn difference(&self, other: &RadixSet) -> RadixSet {
+        self.combine(other, |in_self, in_other| in_self && !in_other)
+    }
+
+    /// Walks the elements of both sets side by side, level by level,
+    /// and builds a set from the labels for which `keep` returns true,
+    /// given whether the label is in this set and in the other.
+    /// An element found in only one set is kept with its whole subtree or skipped
+    /// without visiting it, so only the shared parts of the sets are compared
+    fn combine<F>(&self, other: &RadixSet, keep: F) -> RadixSet
+    where
+        F: Fn(bool, bool) -> bool,
+    {
+        let (entry, pending) = combine_level(
+            children(&self.trie.entry),
+            children(&other.trie.entry),
+            &keep,
+        );
+        let mut stack = vec![CombineFrame {
+            entry,
+            pending,
+            descended: 0,
+        }];
+        loop {
+            let top = stack.last_mut().unwrap();
+            if let Some((target_index, mine, theirs)) = top.pending.pop() {
+                let (entry, pending) = combine_level(mine, theirs, &keep);
+                top.descended = target_index;
+                stack.push(CombineFrame {
+                    entry,
+                    pending,
+                    descended: 0,
+                });
+                continue;
+            }
+            let finished = stack.pop().unwrap();
+            match stack.last_mut() {
+                Some(parent) => {
+                    *parent.entry[parent.descended].children_mut() = finished.entry;
+                    // a shared prefix left without value is pruned or joined with its only child
+                    RadixTrie::compact_child(&mut parent.entry, parent.descended);
+                }
+                None => {
+                    let mut trie = RadixTrie::new();
+                    trie.len = finished.entry.iter().map(Element::value_count).sum();
+                    *trie.entry.children_mut() = finished.entry;
+                    return RadixSet { trie };
+                }
+            }
+        }
+    }
+
+    /// Returns the trie holding the labels
+    pub fn trie(&self) -> &RadixTrie<()> {
+        &self.trie
+    }
+
+    /// Consumes the set and returns the trie holding the labels
+    pub fn into_trie(self) -> RadixTrie<()> {
+        self.trie
+    }
+}
+
+/// The elements of a set at one level, each with the length of its label matched above
+type Level<'a> = Vec<(usize, &'a Element<()>)>;
+
+/// The indices of the combined elements whose children are still to be combined
+/// from what is left of the paired elements of both sets
+type Pending<'a> = Vec<(usize, Level<'a>, Level<'a>)>;
+
+/// An entry built by `combine` while the elements below it are combined
+struct CombineFrame<'a> {
+    entry: Vec<Element<()>>,
+    pending: Pending<'a>,
+    /// The index of the element whose children are in the frame above
+    descended: usize,
+}
+
+/// Combine the elements of both sets, sorted by the first char of their unmatched labels.
+/// An element without pair is copied if `keep` holds for a label only in its set.
+/// Paired elements become one element labelled with their shared prefix,
+/// and the returned indices tell where the rest of them still has to be combined
+fn combine_level<'a, F>(
+    mine: Level<'a>,
+    theirs: Level<'a>,
+    keep: &F,
+) -> (Vec<Element<()>>, Pending<'a>)
+where
+    F: Fn(bool, bool) -> bool,
+{
+    let mut entry = vec![];
+    let mut pending = vec![];
+    let first_char =
+        |(offset, element): &(usize, &Element<()>)| util::first_char(&element.label()[*offset..]);
+    let mut mine = mine.into_iter().peekable();
+    let mut theirs = theirs.into_iter().peekable();
+    loop {
+        let order = match (mine.peek(), theirs.peek()) {
+            (Some(mine), Some(theirs)) => first_char(mine).cmp(&first_char(theirs)),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => break,
+        };
+        let ((mine_offset, mine), (theirs_offset, theirs)) = match order {
+            Ordering::Less => {
+                entry.extend(mine.next().filter(|_| keep(true, false)).map(graft));
+                continue;
+            }
+            Ordering::Greater => {
+                entry.extend(theirs.next().filter(|_| keep(false, true)).map(graft));
+                continue;
+            }
+            Ordering::Equal => (mine.next().unwrap(), theirs.next().unwrap()),
+        };
+        let mine_label = &mine.label()[mine_offset..];
+        let shared = util::longest_shared_prefix(mine_label, &theirs.label()[theirs_offset..]);
+        // whether the element holds the shared prefix, and what is left of it below
+        let below = |offset: usize, element: &'a Element<()>| {
+            if element.label().len() - offset == shared.len() {
+                (element.value().is_some(), children(element))
+            } else {
+                (false, vec![(offset + shared.len(), element)])
+            }
+        };
+        let (in_self, mine_below) = below(mine_offset, mine);
+        let (in_other, theirs_below) = below(theirs_offset, theirs);
+        let value = Some(()).filter(|_| (in_self || in_other) && keep(in_self, in_other));
+        pending.push((entry.len(), mine_below, theirs_below));
+        entry.push(Element::pack(shared.to_owned(), value, vec![]));
+    }
+    (entry, pending)
+}
+
+/// The children of the element, with nothing of their labels matched above
+fn children(element: &Element<()>) -> Level<'_> {
+    element.children().iter().map(|child| (0, child)).collect()
+}
+
+/// Copy the element with its subtree, without the part of its label matched above
+fn graft((offset, element): (usize, &Element<()>)) -> Element<()> {
+    let mut element = element.clone();
+    element.remove_label_prefix(offset);
+    element
+}
+
+impl fmt::Debug for RadixSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<'a> IntoIterator for &'a RadixSet {
+    type Item = String;
+    type IntoIter = Keys<'a, ()>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<S: AsRef<str>> FromIterator<S> for RadixSet {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        RadixSet {
+            trie: iter.into_iter().map(|label| (label, ())).collect(),
+        }
+    }
+}
+
+impl<S: AsRef<str>> Extend<S> for RadixSet {
+    fn extend<I: IntoIterator<Item = S>>(&mut self, iter: I) {
+        self.trie.extend(iter.into_iter().map(|label| (label, ())))
+    }
+}
+
+#[cfg(test)]
+mod set_tests {
+    use crate::set::RadixSet;
+    use std::collections::BTreeSet;
+
+    const WORDS1: [&str; 6] = ["Won", "Wonder", "Wonderful", "Axes", "多倫多", "多"];
+    const WORDS2: [&str; 5] = ["Wonder", "World", "Ax", "多倫多", "倫敦"];
+
+    #[test]
+    fn test_membership() {
+        let mut set = RadixSet::new();
+        for word in &WORDS1 {
+            assert!(set.insert(word));
+        }
+        assert!(!set.insert("Wonder"));
+        assert!(!set.insert(""));
+        assert_eq!(set.len(), WORDS1.len());
+        assert!(set.contains("Won") && !set.contains("Wo"));
+        assert!(set.remove("Won"));
+        assert!(!set.remove("Won"));
+        assert!(!set.contains("Won") && set.contains("Wonder"));
+        assert_eq!(
+            set.find_prefix_iter("Wo").collect::<Vec<_>>(),
+            vec!["Wonder", "Wonderful"]
+        );
+        let set = vec!["多倫多", "多"].into_iter().collect::<RadixSet>();
+        assert_eq!(format!("{:?}", set), r#"{"多", "多倫多"}"#);
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let set1 = WORDS1.iter().collect::<RadixSet>();
+        let set2 = WORDS2.iter().collect::<RadixSet>();
+        let expected1 = WORDS1
+            .iter()
+            .map(|word| word.to_string())
+            .collect::<BTreeSet<_>>();
+        let expected2 = WORDS2
+            .iter()
+            .map(|word| word.to_string())
+            .collect::<BTreeSet<_>>();
+        let check = |set: RadixSet, expected: Vec<&String>| {
+            assert_eq!(set.len(), expected.len());
+            assert_eq!(
+                set.iter().collect::<Vec<_>>(),
+                expected.into_iter().cloned().collect::<Vec<_>>()
+            );
+        };
+        check(set1.union(&set2), expected1.union(&expected2).collect());
+        check(
+            set1.intersection(&set2),
+            expected1.intersection(&expected2).collect(),
+        );
+        check(
+            set1.difference(&set2),
+            expected1.difference(&expected2).collect(),
+        );
+        check(
+            set2.difference(&set1),
+            expected2.difference(&expected1).collect(),
+        );
+        assert_eq!(set1.union(&RadixSet::new()), set1);
+        assert!(set1.intersection(&RadixSet::new()).is_empty());
+    }
+
+    #[test]
+    fn test_set_operations_keep_structure() {
+        let words = [
+            "W",
+            "Wo",
+            "Won",
+            "Wonder",
+            "Wonderful",
+            "World",
+            "Ax",
+            "Axes",
+            "多",
+            "多倫多",
+        ];
+        // every subset of the words against every other one, as bit masks over the words
+        let subset = |mask: usize| {
+            words
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| mask >> index & 1 == 1)
+                .map(|(_, word)| *word)
+                .collect::<RadixSet>()
+        };
+        for mask1 in (0..1 << words.len()).step_by(37) {
+            for mask2 in (0..1 << words.len()).step_by(41) {
+                let (set1, set2) = (subset(mask1), subset(mask2));
+                for (res, mask) in [
+                    (set1.union(&set2), mask1 | mask2),
+                    (set1.intersection(&set2), mask1 & mask2),
+                    (set1.difference(&set2), mask1 & !mask2),
+                ] {
+                    let expected = subset(mask);
+                    assert_eq!(res.trie.validate(), Ok(()));
+                    assert_eq!(res.len(), expected.len());
+                    assert_eq!(
+                        format!("{:?}", res.trie.entry),
+                        format!("{:?}", expected.trie.entry)
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use crate::trie::RadixTrie;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+impl<T> RadixTrie<Arc<T>> {
+    /// Returns a shared handle to the value associated with related label,
+    /// so the caller does not hold a borrow into the trie.
+    /// If the label does not exist in the trie, return None
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    /// use std::sync::Arc;
+    ///
+    /// let mut trie = RadixTrie::<Arc<String>>::new();
+    /// trie.insert("label", Arc::new("value".to_owned()));
+    /// let value = trie.get_cloned("label").unwrap();
+    /// trie.remove("label");
+    /// assert_eq!(value.as_str(), "value");
+    /// ```
+    pub fn get_cloned(&self, label: &str) -> Option<Arc<T>> {
+        self.find(label).cloned()
+    }
+
+    /// Returns shared handles to all values with their labels where the labels start with given prefix
+    pub fn start_with_cloned(&self, prefix: &str) -> Vec<(String, Arc<T>)> {
+        self.start_with(prefix)
+            .into_iter()
+            .map(|(label, value)| (label, Arc::clone(value)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod shared_tests {
+    use crate::trie::RadixTrie;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_cloned_handles_outlive_trie() {
+        let mut trie = RadixTrie::<Arc<usize>>::new();
+        trie.insert("lab", Arc::new(3));
+        trie.insert("label", Arc::new(5));
+        let lab = trie.get_cloned("lab");
+        let all = trie.start_with_cloned("la");
+        drop(trie);
+        assert_eq!(lab, Some(Arc::new(3)));
+        assert_eq!(
+            all,
+            vec![
+                ("lab".to_owned(), Arc::new(3)),
+                ("label".to_owned(), Arc::new(5))
+            ]
+        );
+        assert_eq!(Arc::strong_count(&all[0].1), 2);
+    }
+}
@@ -0,0 +1,757 @@
+use crate::element::Element;
+use crate::iter::{Iter, IterMut, Traversal, Values, ValuesMut};
+use crate::trie::FindOutcome::*;
+use crate::trie::RadixTrie;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::iter::Peekable;
+
+/// A read-only view of the labels starting with a prefix, borrowed from a trie.
+/// Labels passed to and returned by the view are relative to the prefix
+/// unless [`SubTrie::with_absolute_keys`] is set.
+///
+/// Created by [`RadixTrie::subtrie`]
+pub struct SubTrie<'a, T> {
+    element: &'a Element<T>,
+    /// The joined labels of the ancestors of the element
+    ancestors: String,
+    /// Bytes of the element label covered by the prefix
+    skip: usize,
+    absolute: bool,
+}
+
+/// A mutable view of the labels starting with a prefix, borrowed from a trie.
+/// Labels passed to and returned by the view are relative to the prefix
+/// unless [`SubTrieMut::with_absolute_keys`] is set.
+///
+/// Created by [`RadixTrie::subtrie_mut`]
+pub struct SubTrieMut<'a, T> {
+    element: &'a mut Element<T>,
+    /// The joined labels of the ancestors of the element
+    ancestors: String,
+    /// Bytes of the element label covered by the prefix
+    skip: usize,
+    absolute: bool,
+}
+
+/// Iterator over the entries of a sub-trie view, yielding relative or absolute labels.
+///
+/// Created by [`SubTrie::iter`], [`SubTrieMut::iter`] and [`SubTrieMut::iter_mut`]
+pub struct SubIter<I> {
+    iter: I,
+    /// Bytes removed from the front of every label
+    strip: usize,
+}
+
+impl<I, V> Iterator for SubIter<I>
+where
+    I: Iterator<Item = (String, V)>,
+{
+    type Item = (String, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (mut label, value) = self.iter.next()?;
+        label.drain(..self.strip);
+        Some((label, value))
+    }
+}
+
+/// Iterator over the labels of a sub-trie view in lexicographic order.
+///
+/// Created by [`SubTrie::keys`]
+pub struct SubKeys<'a, T> {
+    iter: SubIter<Iter<'a, T>>,
+}
+
+impl<'a, T> Iterator for SubKeys<'a, T> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(label, _)| label)
+    }
+}
+
+/// Iterator over several sub-trie views as one stream in lexicographic order of labels.
+/// A label found in more than one view is yielded once, with the value of the first view holding it.
+///
+/// Created by [`SubTrie::layered`]
+pub struct Layered<'a, T> {
+    /// The views from the highest precedence down
+    layers: Vec<Peekable<SubIter<Iter<'a, T>>>>,
+}
+
+impl<'a, T> Iterator for Layered<'a, T> {
+    type Item = (String, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // the first layer holding the smallest label wins it
+        let mut best: Option<(usize, &str)> = None;
+        for (index, layer) in self.layers.iter_mut().enumerate() {
+            if let Some((label, _)) = layer.peek() {
+                match best {
+                    Some((_, best_label)) if best_label <= label.as_str() => (),
+                    _ => best = Some((index, label)),
+                }
+            }
+        }
+        let (best, _) = best?;
+        let entry = self.layers[best].next()?;
+        // the shadowed entries of the lower layers are skipped
+        for layer in &mut self.layers[best + 1..] {
+            layer.next_if(|(label, _)| *label == entry.0);
+        }
+        Some(entry)
+    }
+}
+
+/// Finds the value of the label below the element,
+/// where the label is relative to the first `skip` bytes of the element label
+fn find_below<'a, T>(element: &'a Element<T>, skip: usize, label: &str) -> Option<&'a T> {
+    let mut label = label.strip_prefix(&element.label()[skip..])?;
+    let mut element = element;
+    while !label.is_empty() {
+        match RadixTrie::find_from_entry(element.children(), label) {
+            PrefixMatch(index) | ExactMatch(index) => {
+                element = &element.children()[index];
+                label = &label[element.label().len()..];
+            }
+            _ => return None,
+        }
+    }
+    element.value()
+}
+
+/// Finds the mutable value of the label below the element,
+/// where the label is relative to the first `skip` bytes of the element label
+fn find_below_mut<'a, T>(
+    element: &'a mut Element<T>,
+    skip: usize,
+    label: &str,
+) -> Option<&'a mut T> {
+    let mut label = label.strip_prefix(&element.label()[skip..])?;
+    let mut element = element;
+    while !label.is_empty() {
+        match RadixTrie::find_from_entry(element.children(), label) {
+            PrefixMatch(index) | ExactMatch(index) => {
+                element = &mut element.children_mut()[index];
+                label = &label[element.label().len()..];
+            }
+            _ => return None,
+        }
+    }
+    element.value_mut()
+}
+
+/// Finds the element holding the labels below the element starting with given prefix,
+/// where the prefix is relative to the first `skip` bytes of the element label.
+/// The labels from the element down to the ancestors of the found one are visited
+fn prefix_element_below<'a, T, F>(
+    element: &'a Element<T>,
+    skip: usize,
+    prefix: &str,
+    mut visit_ancestor: F,
+) -> Option<&'a Element<T>>
+where
+    F: FnMut(&'a str),
+{
+    let rest = &element.label()[skip..];
+    if rest.starts_with(prefix) {
+        return Some(element);
+    }
+    let mut prefix = prefix.strip_prefix(rest)?;
+    visit_ancestor(element.label());
+    let mut entry = element.children();
+    loop {
+        match RadixTrie::find_from_entry(entry, prefix) {
+            PrefixMatch(index) => {
+                let target = &entry[index];
+                visit_ancestor(target.label());
+                prefix = &prefix[target.label().len()..];
+                entry = target.children();
+            }
+            ExactMatch(index) | AsPrefixOf(index) => return Some(&entry[index]),
+            BeyondSizeLimit | NotMatch(_) | Intersects(_, _) => return None,
+        }
+    }
+}
+
+/// Finds the longest label below the element that is a prefix of the query,
+/// where the query is relative to the first `skip` bytes of the element label.
+/// Returns the length of the matched part of the query with the value
+fn longest_prefix_below<'a, T>(
+    element: &'a Element<T>,
+    skip: usize,
+    query: &str,
+) -> Option<(usize, &'a T)> {
+    let mut matched = element.label().len() - skip;
+    if !query.starts_with(&element.label()[skip..]) {
+        return None;
+    }
+    let mut longest = element.value().map(|value| (matched, value));
+    let mut entry = element.children();
+    while matched < query.len() {
+        match RadixTrie::find_from_entry(entry, &query[matched..]) {
+            ExactMatch(index) | PrefixMatch(index) => {
+                let target = &entry[index];
+                matched += target.label().len();
+                if let Some(value) = target.value() {
+                    longest = Some((matched, value));
+                }
+                entry = target.children();
+            }
+            _ => break,
+        }
+    }
+    longest
+}
+
+impl<'a, T> SubTrie<'a, T> {
+    /// Sets whether labels returned by the view include the prefix
+    pub fn with_absolute_keys(mut self, absolute: bool) -> Self {
+        self.absolute = absolute;
+        self
+    }
+
+    /// Returns the length of the prefix of the view
+    fn prefix_len(&self) -> usize {
+        self.ancestors.len() + self.skip
+    }
+
+    /// Returns the number of labels in the view
+    pub fn len(&self) -> usize {
+        self.element.value_count()
+    }
+
+    /// Returns true if the view holds no label
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the borrowed value of the label, which is relative to the prefix.
+    /// If the label does not exist, return None
+    pub fn find(&self, label: &str) -> Option<&'a T> {
+        let label = self.relative(label)?;
+        find_below(self.element, self.skip, label)
+    }
+
+    /// Returns true if the label, which is relative to the prefix, exists in the view
+    pub fn contains_key(&self, label: &str) -> bool {
+        self.find(label).is_some()
+    }
+
+    /// Returns a lazy iterator over the labels and borrowed values in lexicographic order
+    pub fn iter(&self) -> SubIter<Iter<'a, T>> {
+        SubIter {
+            iter: Iter {
+                traversal: Traversal::new(self.element, self.ancestors.clone(), 0),
+            },
+            strip: if self.absolute { 0 } else { self.prefix_len() },
+        }
+    }
+
+    /// Returns a lazy iterator over the labels and borrowed values where the labels start with given prefix,
+    /// in lexicographic order. The prefix is relative to the prefix of the view like the labels,
+    /// and a shorter absolute prefix covering the view selects all of its labels
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("tenant1/users/ann", 3);
+    /// trie.insert("tenant1/users/bob", 5);
+    /// trie.insert("tenant1/jobs", 4);
+    /// let tenant = trie.subtrie("tenant1/").unwrap();
+    /// let res = tenant.find_prefix_iter("users/").collect::<Vec<_>>();
+    /// assert_eq!(res, vec![(String::from("users/ann"), &3), (String::from("users/bob"), &5)]);
+    /// let tenant = tenant.with_absolute_keys(true);
+    /// assert_eq!(tenant.find_prefix_iter("tenant1/j").count(), 1);
+    /// assert_eq!(tenant.find_prefix_iter("ten").count(), 3);
+    /// ```
+    pub fn find_prefix_iter(&self, prefix: &str) -> SubIter<Iter<'a, T>> {
+        let mut ancestors = self.ancestors.clone();
+        let element = self.relative_prefix(prefix).and_then(|prefix| {
+            prefix_element_below(self.element, self.skip, prefix, |label| {
+                ancestors.push_str(label)
+            })
+        });
+        let traversal = match element {
+            Some(element) => Traversal::new(element, ancestors, 0),
+            None => Traversal::empty(),
+        };
+        SubIter {
+            iter: Iter { traversal },
+            strip: if self.absolute { 0 } else { self.prefix_len() },
+        }
+    }
+
+    /// Returns all values with their labels where the labels start with given prefix,
+    /// which is relative to the prefix of the view like the labels
+    pub fn start_with(&self, prefix: &str) -> Vec<(String, &'a T)> {
+        self.find_prefix_iter(prefix).collect()
+    }
+
+    /// Returns the longest label of the view that is a prefix of the query,
+    /// as the matched part of the query together with its value.
+    /// The query is relative to the prefix of the view like the labels
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<&str>::new();
+    /// trie.insert("/api", "api");
+    /// trie.insert("/api/users", "users");
+    /// trie.insert("/web/users", "web");
+    /// let api = trie.subtrie("/api").unwrap();
+    /// assert_eq!(api.longest_prefix("/users/42"), Some(("/users", &"users")));
+    /// assert_eq!(api.longest_prefix("/posts"), Some(("", &"api")));
+    /// let api = api.with_absolute_keys(true);
+    /// assert_eq!(api.longest_prefix("/api/users/42"), Some(("/api/users", &"users")));
+    /// assert_eq!(api.longest_prefix("/web/users"), None);
+    /// ```
+    pub fn longest_prefix<'b>(&self, query: &'b str) -> Option<(&'b str, &'a T)> {
+        let relative = self.relative(query)?;
+        let offset = query.len() - relative.len();
+        let (matched, value) = longest_prefix_below(self.element, self.skip, relative)?;
+        Some((&query[..offset + matched], value))
+    }
+
+    /// Returns a lazy iterator over the labels in lexicographic order
+    pub fn keys(&self) -> SubKeys<'a, T> {
+        SubKeys { iter: self.iter() }
+    }
+
+    /// Returns a lazy iterator over the borrowed values in lexicographic order of their labels
+    pub fn values(&self) -> Values<'a, T> {
+        Values {
+            stack: vec![self.element],
+        }
+    }
+
+    /// Strips the prefix from the label if the view uses absolute labels
+    fn relative<'b>(&self, label: &'b str) -> Option<&'b str> {
+        match self.absolute {
+            true => label_after(label, &self.ancestors, &self.element.label()[..self.skip]),
+            false => Some(label),
+        }
+    }
+
+    /// Strips the prefix like `relative`, where an absolute prefix of the prefix of the view selects all of its labels
+    fn relative_prefix<'b>(&self, prefix: &'b str) -> Option<&'b str> {
+        let covered = &self.element.label()[..self.skip];
+        match self.absolute && covers(&self.ancestors, covered, prefix) {
+            true => Some(""),
+            false => self.relative(prefix),
+        }
+    }
+
+    /// Returns a lazy iterator over the entries of all views, possibly of different tries,
+    /// as one stream in lexicographic order of their labels.
+    /// The views are given from the highest precedence down,
+    /// so a label held by several views comes with the value of the first one.
+    /// Every view keeps its own choice of relative or absolute labels,
+    /// and each entry is found by comparing the next label of every view
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::{RadixTrie, SubTrie};
+    ///
+    /// let mut defaults = RadixTrie::<usize>::new();
+    /// defaults.insert("app/timeout", 30);
+    /// defaults.insert("app/retries", 3);
+    /// let mut overrides = RadixTrie::<usize>::new();
+    /// overrides.insert("prod/app/timeout", 5);
+    /// let layers = vec![
+    ///     overrides.subtrie("prod/").unwrap(),
+    ///     defaults.subtrie("").unwrap(),
+    /// ];
+    /// let config = SubTrie::layered(layers).collect::<Vec<_>>();
+    /// assert_eq!(config, vec![
+    ///     (String::from("app/retries"), &3),
+    ///     (String::from("app/timeout"), &5),
+    /// ]);
+    /// ```
+    pub fn layered<I>(views: I) -> Layered<'a, T>
+    where
+        I: IntoIterator<Item = SubTrie<'a, T>>,
+    {
+        Layered {
+            layers: views
+                .into_iter()
+                .map(|view| view.iter().peekable())
+                .collect(),
+        }
+    }
+}
+
+impl<'a, T> SubTrieMut<'a, T> {
+    /// Sets whether labels returned by the view include the prefix
+    pub fn with_absolute_keys(mut self, absolute: bool) -> Self {
+        self.absolute = absolute;
+        self
+    }
+
+    /// Returns the length of the prefix of the view
+    fn prefix_len(&self) -> usize {
+        self.ancestors.len() + self.skip
+    }
+
+    /// Returns the number of labels in the view
+    pub fn len(&self) -> usize {
+        self.element.value_count()
+    }
+
+    /// Returns true if the view holds no label
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the borrowed value of the label, which is relative to the prefix.
+    /// If the label does not exist, return None
+    pub fn find(&self, label: &str) -> Option<&T> {
+        let label = self.relative(label)?;
+        find_below(self.element, self.skip, label)
+    }
+
+    /// Returns the mutable borrowed value of the label, which is relative to the prefix.
+    /// If the label does not exist, return None
+    pub fn find_mut(&mut self, label: &str) -> Option<&mut T> {
+        let label = self.relative(label)?;
+        find_below_mut(self.element, self.skip, label)
+    }
+
+    /// Returns a lazy iterator over the labels and borrowed values in lexicographic order
+    pub fn iter(&self) -> SubIter<Iter<'_, T>> {
+        SubIter {
+            iter: Iter {
+                traversal: Traversal::new(self.element, self.ancestors.clone(), 0),
+            },
+            strip: if self.absolute { 0 } else { self.prefix_len() },
+        }
+    }
+
+    /// Returns a lazy iterator over the labels and mutable borrowed values in lexicographic order
+    pub fn iter_mut(&mut self) -> SubIter<IterMut<'_, T>> {
+        SubIter {
+            strip: if self.absolute { 0 } else { self.prefix_len() },
+            iter: IterMut {
+                stack: vec![(self.ancestors.len(), &mut *self.element)],
+                label: self.ancestors.clone(),
+            },
+        }
+    }
+
+    /// Returns a read-only view of the same labels, with the same choice of relative or absolute labels
+    pub fn as_view(&self) -> SubTrie<'_, T> {
+        SubTrie {
+            element: self.element,
+            ancestors: self.ancestors.clone(),
+            skip: self.skip,
+            absolute: self.absolute,
+        }
+    }
+
+    /// Returns a lazy iterator over the labels and borrowed values where the labels start with given prefix,
+    /// in lexicographic order, like [`SubTrie::find_prefix_iter`]
+    pub fn find_prefix_iter(&self, prefix: &str) -> SubIter<Iter<'_, T>> {
+        self.as_view().find_prefix_iter(prefix)
+    }
+
+    /// Returns all values with their labels where the labels start with given prefix,
+    /// which is relative to the prefix of the view like the labels
+    pub fn start_with(&self, prefix: &str) -> Vec<(String, &T)> {
+        self.find_prefix_iter(prefix).collect()
+    }
+
+    /// Returns the longest label of the view that is a prefix of the query,
+    /// like [`SubTrie::longest_prefix`]
+    pub fn longest_prefix<'b>(&self, query: &'b str) -> Option<(&'b str, &T)> {
+        let relative = self.relative(query)?;
+        let offset = query.len() - relative.len();
+        let (matched, value) = longest_prefix_below(self.element, self.skip, relative)?;
+        Some((&query[..offset + matched], value))
+    }
+
+    /// Returns a lazy iterator over the labels in lexicographic order
+    pub fn keys(&self) -> SubKeys<'_, T> {
+        SubKeys { iter: self.iter() }
+    }
+
+    /// Returns a lazy iterator over the borrowed values in lexicographic order of their labels
+    pub fn values(&self) -> Values<'_, T> {
+        Values {
+            stack: vec![&*self.element],
+        }
+    }
+
+    /// Returns a lazy iterator over the mutable borrowed values in lexicographic order of their labels
+    pub fn values_mut(&mut self) -> ValuesMut<'_, T> {
+        ValuesMut {
+            stack: vec![&mut *self.element],
+        }
+    }
+
+    /// Strips the prefix from the label if the view uses absolute labels
+    fn relative<'b>(&self, label: &'b str) -> Option<&'b str> {
+        match self.absolute {
+            true => label_after(label, &self.ancestors, &self.element.label()[..self.skip]),
+            false => Some(label),
+        }
+    }
+}
+
+/// Returns true if the two parts of the prefix start with the label
+fn covers(ancestors: &str, covered: &str, label: &str) -> bool {
+    match label.strip_prefix(ancestors) {
+        Some(rest) => covered.starts_with(rest),
+        None => ancestors.starts_with(label),
+    }
+}
+
+/// Returns the rest of the label after the two parts of the prefix
+fn label_after<'b>(label: &'b str, ancestors: &str, covered: &str) -> Option<&'b str> {
+    label.strip_prefix(ancestors)?.strip_prefix(covered)
+}
+
+impl<T> RadixTrie<T> {
+    /// Returns a read-only view of the labels starting with given prefix, without copying them.
+    /// If no label starts with the prefix, return None
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("tenant1/users", 3);
+    /// trie.insert("tenant1/jobs", 5);
+    /// trie.insert("tenant2/users", 4);
+    /// let tenant = trie.subtrie("tenant1/").unwrap();
+    /// assert_eq!(tenant.find("users"), Some(&3));
+    /// assert_eq!(tenant.len(), 2);
+    /// let labels = tenant.with_absolute_keys(true).iter().map(|(label, _)| label);
+    /// assert_eq!(labels.collect::<Vec<_>>(), vec!["tenant1/jobs", "tenant1/users"]);
+    /// ```
+    pub fn subtrie(&self, prefix: &str) -> Option<SubTrie<'_, T>> {
+        let mut ancestors = String::new();
+        let element = self.find_prefix_element(prefix, |label| ancestors.push_str(label))?;
+        Some(SubTrie {
+            element,
+            skip: prefix.len() - ancestors.len(),
+            ancestors,
+            absolute: false,
+        })
+    }
+
+    /// Returns a mutable view of the labels starting with given prefix, without copying them.
+    /// If no label starts with the prefix, return None
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("tenant1/users", 3);
+    /// trie.insert("tenant2/users", 4);
+    /// let mut tenant = trie.subtrie_mut("tenant1/").unwrap();
+    /// *tenant.find_mut("users").unwrap() += 1;
+    /// assert_eq!(trie.find("tenant1/users"), Some(&4));
+    /// ```
+    pub fn subtrie_mut(&mut self, prefix: &str) -> Option<SubTrieMut<'_, T>> {
+        let mut ancestors = String::new();
+        let element = self.find_prefix_element_mut(prefix, &mut ancestors)?;
+        Some(SubTrieMut {
+            element,
+            skip: prefix.len() - ancestors.len(),
+            ancestors,
+            absolute: false,
+        })
+    }
+}
+
+#[cfg(test)]
+mod subtrie_tests {
+    use crate::subtrie::SubTrie;
+    use crate::test_util::mixed_words_trie;
+    use crate::trie::RadixTrie;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_subtrie_matches_prefix_queries() {
+        let trie = mixed_words_trie();
+        for prefix in &["", "W", "Wo", "Wonde", "Wonder", "多", "多倫", "Axes"] {
+            let expected = match prefix.is_empty() {
+                true => trie.iter().collect::<Vec<_>>(),
+                false => trie.find_prefix_iter(prefix).collect::<Vec<_>>(),
+            };
+            let subtrie = trie.subtrie(prefix).unwrap();
+            assert_eq!(subtrie.len(), expected.len(), "{}", prefix);
+            let relative = subtrie.iter().collect::<Vec<_>>();
+            for ((absolute, value), (label, other)) in expected.iter().zip(&relative) {
+                assert_eq!(absolute, &format!("{}{}", prefix, label));
+                assert_eq!(value, other);
+                assert_eq!(subtrie.find(label), Some(*value));
+            }
+            assert_eq!(relative.len(), expected.len());
+            assert_eq!(subtrie.find("ful!"), None);
+            let subtrie = subtrie.with_absolute_keys(true);
+            assert_eq!(subtrie.iter().collect::<Vec<_>>(), expected);
+            for (label, value) in &expected {
+                assert_eq!(subtrie.find(label), Some(*value));
+            }
+            assert_eq!(subtrie.find("Z"), None);
+        }
+        assert!(trie.subtrie("Wx").is_none());
+        assert!(trie.subtrie("Wonderfully").is_none());
+        let subtrie = trie.subtrie("Wonde").unwrap();
+        assert!(subtrie.contains_key("r") && !subtrie.contains_key(""));
+        assert!(!subtrie.contains_key("rf"));
+    }
+
+    #[test]
+    fn test_subtrie_prefix_queries() {
+        let trie = mixed_words_trie();
+        let queries = [
+            "",
+            "W",
+            "Wo",
+            "Won",
+            "Wonde",
+            "Wonderfully",
+            "World!",
+            "多倫多!",
+            "Z",
+        ];
+        for view_prefix in &["", "W", "Wo", "Wonde", "多"] {
+            let subtrie = trie.subtrie(view_prefix).unwrap();
+            let absolute = trie.subtrie(view_prefix).unwrap().with_absolute_keys(true);
+            let all = trie.find_prefix_iter(view_prefix).collect::<Vec<_>>();
+            assert_eq!(
+                absolute.keys().collect::<Vec<_>>(),
+                trie.find_prefix_iter(view_prefix)
+                    .map(|(label, _)| label)
+                    .collect::<Vec<_>>()
+            );
+            assert_eq!(
+                subtrie.keys().collect::<Vec<_>>(),
+                all.iter()
+                    .map(|(label, _)| &label[view_prefix.len()..])
+                    .collect::<Vec<_>>()
+            );
+            assert_eq!(
+                subtrie.values().collect::<Vec<_>>(),
+                all.iter().map(|(_, value)| *value).collect::<Vec<_>>()
+            );
+            for query in &queries {
+                let full = format!("{}{}", view_prefix, query);
+                let expected = trie.find_prefix_iter(&full).collect::<Vec<_>>();
+                assert_eq!(absolute.start_with(&full), expected, "{}", full);
+                let relative = expected
+                    .into_iter()
+                    .map(|(label, value)| (label[view_prefix.len()..].to_owned(), value))
+                    .collect::<Vec<_>>();
+                assert_eq!(subtrie.start_with(query), relative, "{}", full);
+                // labels of the view that are prefixes of the query
+                let longest = trie
+                    .longest_prefix(&full)
+                    .filter(|(label, _)| label.len() >= view_prefix.len());
+                assert_eq!(absolute.longest_prefix(&full), longest);
+                let longest = longest.map(|(label, value)| (&label[view_prefix.len()..], value));
+                assert_eq!(subtrie.longest_prefix(query), longest);
+                if !view_prefix.starts_with(*query) && !query.starts_with(*view_prefix) {
+                    assert!(absolute.start_with(query).is_empty());
+                    assert_eq!(absolute.longest_prefix(query), None);
+                }
+            }
+            // an absolute prefix covering the view selects all of it
+            for (end, _) in view_prefix.char_indices() {
+                assert_eq!(absolute.start_with(&view_prefix[..end]), all);
+            }
+        }
+    }
+
+    #[test]
+    fn test_subtrie_mut() {
+        let mut trie = mixed_words_trie();
+        let mut subtrie = trie.subtrie_mut("Won").unwrap();
+        assert_eq!(subtrie.len(), 3);
+        *subtrie.find_mut("").unwrap() += 10;
+        *subtrie.find_mut("derful").unwrap() += 10;
+        assert_eq!(subtrie.find("der"), Some(&6));
+        for (label, value) in subtrie.iter_mut() {
+            *value += label.len();
+        }
+        let res = subtrie.iter().collect::<Vec<_>>();
+        assert_eq!(
+            res,
+            vec![
+                (String::from(""), &13),
+                (String::from("der"), &9),
+                (String::from("derful"), &25)
+            ]
+        );
+        assert_eq!(
+            subtrie.keys().collect::<Vec<_>>(),
+            vec!["", "der", "derful"]
+        );
+        assert_eq!(subtrie.longest_prefix("derfully"), Some(("derful", &25)));
+        assert_eq!(
+            subtrie.start_with("derf"),
+            vec![(String::from("derful"), &25)]
+        );
+        for value in subtrie.values_mut() {
+            *value -= 1;
+        }
+        assert_eq!(subtrie.values().collect::<Vec<_>>(), vec![&12, &8, &24]);
+        let mut subtrie = subtrie.with_absolute_keys(true);
+        assert_eq!(subtrie.find_prefix_iter("Wonder").count(), 2);
+        assert_eq!(
+            subtrie.as_view().longest_prefix("Wonders"),
+            Some(("Wonder", &8))
+        );
+        assert!(subtrie.find_mut("der").is_none());
+        *subtrie.find_mut("Wonder").unwrap() = 0;
+        assert_eq!(trie.find("Wonder"), Some(&0));
+        assert_eq!(trie.find("World"), Some(&5));
+        assert!(trie.subtrie_mut("B").is_none());
+    }
+
+    #[test]
+    fn test_layered_views() {
+        let base = mixed_words_trie();
+        let mut site = RadixTrie::new();
+        for (word, value) in &[("Wonder", 0), ("Wo", 1), ("Wonderful", 2), ("Ax", 3)] {
+            site.insert(word, *value);
+        }
+        let mut user = RadixTrie::new();
+        for (word, value) in &[("user/Won", 10), ("user/Ax", 11), ("user/Z", 12)] {
+            user.insert(word, *value);
+        }
+        let layers = vec![
+            user.subtrie("user/").unwrap(),
+            site.subtrie("").unwrap(),
+            base.subtrie("").unwrap(),
+        ];
+        let layered = SubTrie::layered(layers).collect::<Vec<_>>();
+        let mut expected = BTreeMap::new();
+        for trie in &[&base, &site] {
+            expected.extend(trie.iter());
+        }
+        expected.extend(user.subtrie("user/").unwrap().iter());
+        assert_eq!(layered, expected.into_iter().collect::<Vec<_>>());
+        assert_eq!(layered[0], (String::from("Ax"), &11));
+        // absolute and relative views can be layered, and a lower layer fills the gaps
+        let layers = vec![
+            base.subtrie("Won").unwrap(),
+            site.subtrie("Wo").unwrap().with_absolute_keys(true),
+        ];
+        let labels = SubTrie::layered(layers).map(|(label, value)| (label, *value));
+        assert_eq!(
+            labels.collect::<Vec<_>>(),
+            vec![
+                (String::from(""), 3),
+                (String::from("Wo"), 1),
+                (String::from("Wonder"), 0),
+                (String::from("Wonderful"), 2),
+                (String::from("der"), 6),
+                (String::from("derful"), 9),
+            ]
+        );
+        assert_eq!(SubTrie::<usize>::layered(vec![]).next(), None);
+    }
+}
@@ -0,0 +1,73 @@
+use crate::trie::RadixTrie;
+
+impl<T: Clone> RadixTrie<T> {
+    /// Insert every non-empty suffix of the text with a copy of the value,
+    /// turning the trie into a suffix trie of the texts inserted this way.
+    /// Each suffix is inserted as a slice of the text, so only the labels of new elements are copied.
+    /// A suffix shared with an earlier text gets the new value like in `insert`
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert_all_suffixes("banana", 0);
+    /// assert_eq!(trie.len(), 6);
+    /// assert!(trie.contains_substring("nan"));
+    /// assert!(!trie.contains_substring("nab"));
+    /// ```
+    pub fn insert_all_suffixes(&mut self, text: &str, value: T) {
+        for (index, _) in text.char_indices() {
+            self.insert(&text[index..], value.clone());
+        }
+    }
+}
+
+impl<T> RadixTrie<T> {
+    /// Returns true if the pattern is the start of some label.
+    /// After [`RadixTrie::insert_all_suffixes`], it tells whether the pattern
+    /// occurs anywhere inside the inserted texts.
+    /// The empty pattern is contained in every inserted text
+    pub fn contains_substring(&self, pattern: &str) -> bool {
+        self.find_prefix_element(pattern, |_| ())
+            .is_some_and(|element| element.value_count() > 0)
+    }
+}
+
+#[cfg(test)]
+mod suffix_tests {
+    use crate::trie::RadixTrie;
+
+    #[test]
+    fn test_contains_substring() {
+        let texts = ["mississippi", "多倫多大學", "banana"];
+        let mut trie = RadixTrie::<usize>::new();
+        for (index, text) in texts.iter().enumerate() {
+            trie.insert_all_suffixes(text, index);
+        }
+        for pattern in &[
+            "ssi",
+            "issip",
+            "i",
+            "倫多大",
+            "學",
+            "anana",
+            "ipp",
+            "ana",
+            "多大學",
+            "",
+        ] {
+            assert!(trie.contains_substring(pattern), "{}", pattern);
+        }
+        for pattern in &["sss", "多多", "bananas", "x", "pis"] {
+            assert!(!trie.contains_substring(pattern), "{}", pattern);
+        }
+        assert!(!RadixTrie::<usize>::new().contains_substring(""));
+        assert_eq!(trie.find("ssippi"), Some(&0));
+        assert_eq!(trie.find("大學"), Some(&1));
+        assert_eq!(trie.len(), 11 + 5 + 6);
+        // a suffix shared with a later text takes the later value
+        trie.insert_all_suffixes("cabana", 3);
+        assert_eq!(trie.find("ana"), Some(&3));
+        assert_eq!(trie.find("nana"), Some(&2));
+    }
+}
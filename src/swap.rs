@@ -0,0 +1,119 @@
+use crate::frozen::FrozenRadixTrie;
+use crate::trie::RadixTrie;
+use std::sync::RwLock;
+
+/// A frozen trie behind a pointer that can be swapped while other threads read it.
+///
+/// Readers take a [`FrozenRadixTrie`] handle with [`SwappableRadixTrie::load`]
+/// and keep using their snapshot without any lock, while a writer builds the next trie
+/// on the side and publishes it with [`SwappableRadixTrie::store`].
+/// The lock is only held to clone or replace the handle, never during a lookup.
+///
+/// # Example
+/// ```rust
+/// use another_radix_trie::{RadixTrie, SwappableRadixTrie};
+///
+/// let mut routes = RadixTrie::<usize>::new();
+/// routes.insert("/api", 1);
+/// let current = SwappableRadixTrie::new(routes);
+/// let snapshot = current.load();
+/// let mut next = RadixTrie::clone(&snapshot);
+/// next.insert("/web", 2);
+/// current.store(next.freeze());
+/// assert_eq!(snapshot.find("/web"), None);
+/// assert_eq!(current.load().find("/web"), Some(&2));
+/// ```
+pub struct SwappableRadixTrie<T> {
+    current: RwLock<FrozenRadixTrie<T>>,
+}
+
+impl<T> Default for SwappableRadixTrie<T> {
+    fn default() -> Self {
+        SwappableRadixTrie::new(RadixTrie::new())
+    }
+}
+
+impl<T> From<FrozenRadixTrie<T>> for SwappableRadixTrie<T> {
+    fn from(frozen: FrozenRadixTrie<T>) -> Self {
+        SwappableRadixTrie {
+            current: RwLock::new(frozen),
+        }
+    }
+}
+
+impl<T> SwappableRadixTrie<T> {
+    /// Freeze the trie and hold it as the current one
+    pub fn new(trie: RadixTrie<T>) -> Self {
+        SwappableRadixTrie::from(trie.freeze())
+    }
+
+    /// Returns a handle to the current trie, which stays valid after later swaps
+    pub fn load(&self) -> FrozenRadixTrie<T> {
+        // the lock is never held while user code runs, so a poisoned lock still holds a valid handle
+        let current = self
+            .current
+            .read()
+            .unwrap_or_else(|error| error.into_inner());
+        FrozenRadixTrie::clone(&current)
+    }
+
+    /// Replace the current trie and return the previous one.
+    /// Readers holding handles to the previous trie keep it alive until they drop them
+    pub fn store(&self, trie: FrozenRadixTrie<T>) -> FrozenRadixTrie<T> {
+        let mut current = self
+            .current
+            .write()
+            .unwrap_or_else(|error| error.into_inner());
+        core::mem::replace(&mut *current, trie)
+    }
+}
+
+#[cfg(test)]
+mod swap_tests {
+    use crate::swap::SwappableRadixTrie;
+    use crate::trie::RadixTrie;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::thread;
+
+    /// A trie where every label holds the version it was built for
+    fn version(version: usize) -> RadixTrie<usize> {
+        (0..50)
+            .map(|index| (format!("route/{}", index), version))
+            .collect()
+    }
+
+    #[test]
+    fn test_swap_while_reading() {
+        let current = SwappableRadixTrie::new(version(0));
+        let done = AtomicBool::new(false);
+        thread::scope(|scope| {
+            let readers = (0..4)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut last = 0;
+                        while !done.load(Ordering::Acquire) {
+                            let snapshot = current.load();
+                            let seen = *snapshot.find("route/0").unwrap();
+                            // a snapshot never mixes two versions, and versions only move forward
+                            assert!(snapshot.values().all(|value| *value == seen));
+                            assert!(seen >= last);
+                            last = seen;
+                        }
+                        last
+                    })
+                })
+                .collect::<Vec<_>>();
+            for next in 1..=100 {
+                let previous = current.store(version(next).freeze());
+                assert_eq!(previous.find("route/49"), Some(&(next - 1)));
+            }
+            done.store(true, Ordering::Release);
+            for reader in readers {
+                assert!(reader.join().unwrap() <= 100);
+            }
+        });
+        assert_eq!(current.load().find("route/7"), Some(&100));
+        let empty = SwappableRadixTrie::<usize>::default();
+        assert!(empty.load().is_empty());
+    }
+}
@@ -0,0 +1,116 @@
+use crate::trie::RadixTrie;
+use alloc::string::String;
+use core::cell::Cell;
+use core::ptr;
+use std::alloc::{GlobalAlloc, Layout, System};
+
+/// Labels sharing prefixes at several depths, with one outside of them
+pub(crate) const WORDS: [&str; 5] = ["Won", "Wonder", "Wonderful", "World", "Axes"];
+
+/// The labels of [`WORDS`] with multibyte labels, one of them a prefix of the other
+pub(crate) const MIXED_WORDS: [&str; 7] = [
+    "Won",
+    "Wonder",
+    "Wonderful",
+    "World",
+    "Axes",
+    "多倫多",
+    "多",
+];
+
+/// A trie holding every label of [`WORDS`] with its length as the value
+pub(crate) fn words_trie() -> RadixTrie<usize> {
+    trie_of(&WORDS)
+}
+
+/// A trie holding every label of [`MIXED_WORDS`] with its length as the value
+pub(crate) fn mixed_words_trie() -> RadixTrie<usize> {
+    trie_of(&MIXED_WORDS)
+}
+
+fn trie_of(words: &[&str]) -> RadixTrie<usize> {
+    let mut trie = RadixTrie::new();
+    for word in words {
+        trie.insert(word, word.len());
+    }
+    trie
+}
+
+/// Small linear congruential generator, to keep the tests deterministic
+pub(crate) struct Lcg(pub(crate) u64);
+
+impl Lcg {
+    /// Returns the next number below the bound
+    pub(crate) fn next(&mut self, bound: u64) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 33) % bound
+    }
+
+    /// Returns a label of one to five chars out of three,
+    /// one of them multibyte, so random labels often share prefixes
+    pub(crate) fn label(&mut self) -> String {
+        let len = 1 + self.next(5) as usize;
+        (0..len)
+            .map(|_| ['a', 'b', '多'][self.next(3) as usize])
+            .collect()
+    }
+}
+
+/// The system allocator, failing the allocations of a thread once its budget set by
+/// [`with_allocation_budget`] runs out, so the fallible paths can be tested
+struct FailingAllocator;
+
+std::thread_local! {
+    /// The number of allocations the thread may still make, or None for no limit
+    static BUDGET: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+impl FailingAllocator {
+    fn allowed() -> bool {
+        BUDGET
+            .try_with(|budget| match budget.get() {
+                Some(0) => false,
+                Some(left) => {
+                    budget.set(Some(left - 1));
+                    true
+                }
+                None => true,
+            })
+            .unwrap_or(true)
+    }
+}
+
+unsafe impl GlobalAlloc for FailingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        match Self::allowed() {
+            true => System.alloc(layout),
+            false => ptr::null_mut(),
+        }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        match Self::allowed() {
+            true => System.realloc(ptr, layout, new_size),
+            false => ptr::null_mut(),
+        }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: FailingAllocator = FailingAllocator;
+
+/// Runs the closure with the given number of allocations allowed on this thread,
+/// after which every allocation fails. An infallible allocation failing aborts the tests
+pub(crate) fn with_allocation_budget<R, F: FnOnce() -> R>(budget: usize, run: F) -> R {
+    BUDGET.with(|left| left.set(Some(budget)));
+    let res = run();
+    BUDGET.with(|left| left.set(None));
+    res
+}
@@ -0,0 +1,198 @@
+use crate::trie::RadixTrie;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// The latest write of a label, numbered by the version it was made in
+struct Record<T> {
+    version: usize,
+    /// None marks a tombstone left by a removal
+    value: Option<T>,
+}
+
+/// A trie where removals leave tombstones until they are purged.
+///
+/// Every write is numbered with the next version, starting from 1.
+/// A removed label is hidden from the reads but stays in the trie as a tombstone,
+/// so [`TombstoneTrie::changes_since`] reports the removal to replicas
+/// until [`TombstoneTrie::purge`] physically removes it.
+///
+/// # Example
+/// ```rust
+/// use another_radix_trie::TombstoneTrie;
+///
+/// let mut trie = TombstoneTrie::new();
+/// trie.insert("app/timeout", 30);
+/// trie.insert("app/retries", 3);
+/// let synced = trie.version();
+/// trie.remove("app/retries");
+/// assert_eq!(trie.find("app/retries"), None);
+/// assert_eq!(trie.changes_since(synced), vec![(String::from("app/retries"), 3, None)]);
+/// assert_eq!(trie.purge(trie.version() + 1), 1);
+/// assert!(trie.changes_since(synced).is_empty());
+/// ```
+pub struct TombstoneTrie<T> {
+    trie: RadixTrie<Record<T>>,
+    /// The version of the latest write
+    version: usize,
+    /// Number of labels holding a value
+    live: usize,
+}
+
+impl<T> Default for TombstoneTrie<T> {
+    fn default() -> Self {
+        TombstoneTrie::new()
+    }
+}
+
+impl<T> TombstoneTrie<T> {
+    /// Construct an empty trie at version 0
+    pub fn new() -> Self {
+        TombstoneTrie {
+            trie: RadixTrie::new(),
+            version: 0,
+            live: 0,
+        }
+    }
+
+    /// Returns the version of the latest write, or 0 before any write
+    pub fn version(&self) -> usize {
+        self.version
+    }
+
+    /// Insert the value under the label as a new version, replacing a tombstone of the label.
+    /// An existing value of the label is replaced and returned.
+    /// Inserting the empty label does nothing and returns None
+    pub fn insert(&mut self, label: &str, value: T) -> Option<T> {
+        if label.is_empty() {
+            return None;
+        }
+        self.version += 1;
+        let record = Record {
+            version: self.version,
+            value: Some(value),
+        };
+        let replaced = self
+            .trie
+            .insert(label, record)
+            .and_then(|record| record.value);
+        if replaced.is_none() {
+            self.live += 1;
+        }
+        replaced
+    }
+
+    /// Removes the value of the label as a new version and returns it,
+    /// leaving a tombstone in its place.
+    /// Removing a label without value does nothing and returns None
+    pub fn remove(&mut self, label: &str) -> Option<T> {
+        let record = self.trie.find_mut(label)?;
+        let value = record.value.take()?;
+        self.version += 1;
+        record.version = self.version;
+        self.live -= 1;
+        Some(value)
+    }
+
+    /// Returns the borrowed value of the label.
+    /// If the label does not exist or was removed, return None
+    pub fn find(&self, label: &str) -> Option<&T> {
+        self.trie.find(label)?.value.as_ref()
+    }
+
+    /// Returns the number of labels holding a value
+    pub fn len(&self) -> usize {
+        self.live
+    }
+
+    /// Returns true if no label holds a value
+    pub fn is_empty(&self) -> bool {
+        self.live == 0
+    }
+
+    /// Returns the number of tombstones not purged yet
+    pub fn tombstone_count(&self) -> usize {
+        self.trie.len() - self.live
+    }
+
+    /// Returns the values with their labels where the labels start with given prefix,
+    /// in lexicographic order. Tombstones are skipped
+    pub fn start_with(&self, prefix: &str) -> Vec<(String, &T)> {
+        self.trie
+            .find_prefix_iter(prefix)
+            .filter_map(|(label, record)| record.value.as_ref().map(|value| (label, value)))
+            .collect()
+    }
+
+    /// Returns the labels written after the given version with the version of their latest write,
+    /// in lexicographic order. The value is None for a label removed by that write,
+    /// as long as its tombstone is not purged
+    pub fn changes_since(&self, version: usize) -> Vec<(String, usize, Option<&T>)> {
+        self.trie
+            .iter()
+            .filter(|(_, record)| record.version > version)
+            .map(|(label, record)| (label, record.version, record.value.as_ref()))
+            .collect()
+    }
+
+    /// Physically removes the tombstones left by removals before the given version
+    /// and returns how many were removed.
+    /// Replicas synced at or after that version have already seen those removals
+    pub fn purge(&mut self, before_version: usize) -> usize {
+        let tombstones = self.tombstone_count();
+        self.trie
+            .retain(|_, record| record.value.is_some() || record.version >= before_version);
+        tombstones - self.tombstone_count()
+    }
+}
+
+#[cfg(test)]
+mod tombstone_tests {
+    use crate::test_util::MIXED_WORDS;
+    use crate::tombstone::TombstoneTrie;
+
+    #[test]
+    fn test_tombstones_hidden_until_purged() {
+        let mut trie = TombstoneTrie::new();
+        for (index, word) in MIXED_WORDS.iter().enumerate() {
+            assert_eq!(trie.insert(word, index), None);
+        }
+        assert_eq!(trie.insert("", 0), None);
+        let synced = trie.version();
+        assert_eq!(synced, MIXED_WORDS.len());
+        assert_eq!(trie.remove("Won"), Some(0));
+        assert_eq!(trie.remove("Won"), None);
+        assert_eq!(trie.remove("Wo"), None);
+        assert_eq!(trie.remove("多倫多"), Some(5));
+        assert_eq!(trie.insert("World", 10), Some(3));
+        assert_eq!(trie.version(), synced + 3);
+        assert_eq!(trie.find("Won"), None);
+        assert_eq!(trie.find("Wonder"), Some(&1));
+        assert_eq!(
+            (trie.len(), trie.tombstone_count()),
+            (MIXED_WORDS.len() - 2, 2)
+        );
+        let labels = trie.start_with("Wo").into_iter().map(|(label, _)| label);
+        assert_eq!(
+            labels.collect::<Vec<_>>(),
+            vec!["Wonder", "Wonderful", "World"]
+        );
+        assert_eq!(
+            trie.changes_since(synced),
+            vec![
+                (String::from("Won"), synced + 1, None),
+                (String::from("World"), synced + 3, Some(&10)),
+                (String::from("多倫多"), synced + 2, None),
+            ]
+        );
+        // only the removal of "Won" is older than the version
+        assert_eq!(trie.purge(synced + 2), 1);
+        assert_eq!(trie.tombstone_count(), 1);
+        assert_eq!(trie.changes_since(synced).len(), 2);
+        assert_eq!(trie.find("Wonder"), Some(&1));
+        // a purged label can be inserted again
+        assert_eq!(trie.insert("Won", 7), None);
+        assert_eq!(trie.insert("多倫多", 8), None);
+        assert_eq!(trie.purge(trie.version() + 1), 0);
+        assert_eq!((trie.len(), trie.tombstone_count()), (MIXED_WORDS.len(), 0));
+    }
+}
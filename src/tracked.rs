@@ -0,0 +1,234 @@
+use crate::element::Element;
+use crate::trie::RadixTrie;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// A point in the history of a [`TrackedTrie`], returned by [`TrackedTrie::mark`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Epoch(usize);
+
+/// The epochs of the latest writes at and below an element
+struct Stamp {
+    /// The epoch the value of the element was last written in
+    value: usize,
+    /// The latest epoch of the writes to the element and its descendants
+    subtree: usize,
+}
+
+/// A trie tracking which labels were inserted or updated since a marked epoch.
+///
+/// Every element carries the epoch of the latest write below it, refreshed on the path
+/// of every insert, so [`TrackedTrie::modified_since`] skips the subtrees
+/// left untouched since the marker without visiting their labels.
+/// Removed labels are not reported.
+///
+/// # Example
+/// ```rust
+/// use another_radix_trie::TrackedTrie;
+///
+/// let mut index = TrackedTrie::new();
+/// index.insert("docs/a", 1);
+/// index.insert("docs/b", 2);
+/// let indexed = index.mark();
+/// index.insert("docs/b", 3);
+/// index.insert("docs/c", 4);
+/// let res = index.modified_since(indexed);
+/// assert_eq!(res, vec![(String::from("docs/b"), &3), (String::from("docs/c"), &4)]);
+/// ```
+pub struct TrackedTrie<T> {
+    trie: RadixTrie<T>,
+    /// The stamp of every element, under the full label of the element
+    stamps: RadixTrie<Stamp>,
+    /// The epoch of the writes made now
+    epoch: usize,
+}
+
+impl<T> Default for TrackedTrie<T> {
+    fn default() -> Self {
+        TrackedTrie::new()
+    }
+}
+
+impl<T> TrackedTrie<T> {
+    /// Construct an empty trie
+    pub fn new() -> Self {
+        TrackedTrie {
+            trie: RadixTrie::new(),
+            stamps: RadixTrie::new(),
+            epoch: 0,
+        }
+    }
+
+    /// Ends the current epoch and returns a marker of it.
+    /// The writes made from now on are reported by [`TrackedTrie::modified_since`] with this marker
+    pub fn mark(&mut self) -> Epoch {
+        let marker = Epoch(self.epoch);
+        self.epoch += 1;
+        marker
+    }
+
+    /// Insert the value under the label and stamp the path of the label with the current epoch.
+    /// An existing value of the label is replaced and returned.
+    /// Inserting the empty label does nothing and returns None
+    pub fn insert(&mut self, label: &str, value: T) -> Option<T> {
+        let replaced = self.trie.insert(label, value);
+        let path = self.trie.path_to(label);
+        for (full_label, element) in &path {
+            let exact = full_label.len() == label.len() && element.value().is_some();
+            match self.stamps.find_mut(full_label) {
+                Some(stamp) => {
+                    stamp.subtree = self.epoch;
+                    if exact {
+                        stamp.value = self.epoch;
+                    }
+                }
+                None => {
+                    // an element created by the insert, holding the value or splitting a label
+                    let stamp = Stamp {
+                        value: self.epoch,
+                        subtree: self.epoch,
+                    };
+                    self.stamps.insert(full_label, stamp);
+                }
+            }
+        }
+        replaced
+    }
+
+    /// Removes the value of the label and returns it,
+    /// dropping the stamps of the elements removed with it
+    pub fn remove(&mut self, label: &str) -> Option<T> {
+        let before = self
+            .trie
+            .path_to(label)
+            .into_iter()
+            .map(|(label, _)| label)
+            .collect::<Vec<_>>();
+        let removed = self.trie.remove(label)?;
+        let after = self.trie.path_to(label);
+        for label in &before {
+            if !after.iter().any(|(kept, _)| kept == label) {
+                self.stamps.remove(label);
+            }
+        }
+        Some(removed)
+    }
+
+    /// Returns the borrowed value associated with related label.
+    /// If the label does not exist, return None
+    pub fn find(&self, label: &str) -> Option<&T> {
+        self.trie.find(label)
+    }
+
+    /// Number of values stored
+    pub fn len(&self) -> usize {
+        self.trie.len()
+    }
+
+    /// Returns true if no value is stored
+    pub fn is_empty(&self) -> bool {
+        self.trie.is_empty()
+    }
+
+    /// Returns the labels inserted or updated after the marker with their values,
+    /// in lexicographic order. The subtrees without such a label are skipped
+    pub fn modified_since(&self, marker: Epoch) -> Vec<(String, &T)> {
+        let mut res = vec![];
+        let mut label = String::new();
+        let mut stack = self
+            .trie
+            .entry
+            .children()
+            .iter()
+            .rev()
+            .map(|child| (0, child))
+            .collect::<Vec<(usize, &Element<T>)>>();
+        while let Some((prefix_len, element)) = stack.pop() {
+            label.truncate(prefix_len);
+            label.push_str(element.label());
+            // every element below the root has a stamp
+            let stamp = self.stamps.find(&label).unwrap();
+            if stamp.subtree <= marker.0 {
+                continue;
+            }
+            if let Some(value) = element.value().filter(|_| stamp.value > marker.0) {
+                res.push((label.clone(), value));
+            }
+            let children = element.children().iter().rev();
+            stack.extend(children.map(|child| (label.len(), child)));
+        }
+        res
+    }
+
+    /// Returns the trie holding the values
+    pub fn trie(&self) -> &RadixTrie<T> {
+        &self.trie
+    }
+
+    /// Consumes the wrapper and returns the trie holding the values
+    pub fn into_trie(self) -> RadixTrie<T> {
+        self.trie
+    }
+}
+
+#[cfg(test)]
+mod tracked_tests {
+    use crate::test_util::Lcg;
+    use crate::tracked::TrackedTrie;
+    use crate::trie::RadixTrie;
+
+    #[test]
+    fn test_modified_since_matches_model() {
+        let mut trie = TrackedTrie::new();
+        // the epoch every label was last inserted in
+        let mut model = RadixTrie::new();
+        let mut markers = vec![trie.mark()];
+        let mut rng = Lcg(5);
+        for round in 0..800 {
+            let label = rng.label();
+            if round % 4 == 3 {
+                assert_eq!(
+                    trie.remove(&label).is_some(),
+                    model.remove(&label).is_some()
+                );
+            } else {
+                trie.insert(&label, round);
+                model.insert(&label, (markers.len(), round));
+            }
+            if round % 50 == 49 {
+                markers.push(trie.mark());
+            }
+            // the stamps are dropped with their elements
+            assert_eq!(trie.stamps.len(), trie.trie.node_count());
+        }
+        for (index, marker) in markers.iter().enumerate() {
+            let expected = model
+                .iter()
+                .filter(|(_, (epoch, _))| *epoch > index)
+                .map(|(label, (_, round))| (label, round))
+                .collect::<Vec<_>>();
+            assert_eq!(trie.modified_since(*marker), expected);
+        }
+        let marker = trie.mark();
+        assert!(trie.modified_since(marker).is_empty());
+    }
+
+    #[test]
+    fn test_modified_since_after_splits_and_merges() {
+        let mut trie = TrackedTrie::new();
+        trie.insert("Wonder", 0);
+        trie.insert("World", 1);
+        let marker = trie.mark();
+        // splits "Wonder" below a new "Won" element
+        trie.insert("Wonk", 2);
+        let split = trie.mark();
+        // merges "Won" back into "Wonder"
+        assert_eq!(trie.remove("Wonk"), Some(2));
+        assert!(trie.modified_since(marker).is_empty());
+        trie.insert("Wonder", 3);
+        let res = trie.modified_since(split);
+        assert_eq!(res, vec![(String::from("Wonder"), &3)]);
+        assert_eq!(trie.find("World"), Some(&1));
+        assert_eq!(trie.len(), 2);
+    }
+}
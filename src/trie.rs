@@ -1,6 +1,15 @@
 use self::FindOutcome::*;
+use crate::compiled::CompiledRadixTrie;
 use crate::element::Element;
+use crate::entry::Location;
 use crate::util;
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::Infallible;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::ops::{Index, IndexMut};
 
 /// RadixTrie stores values associated with strings
 ///
@@ -9,17 +18,19 @@ use crate::util;
 /// use another_radix_trie::RadixTrie;
 /// let mut trie = RadixTrie::<usize>::new();
 /// trie.insert("ON", 3);
-/// trie.insert("ON20", 4)
+/// trie.insert("ON20", 4);
 /// // The internal structure of this trie will be
 /// // - "ON" 3
 /// //    - "20" 4
 /// ```
 pub struct RadixTrie<T> {
-    entry: Element<T>,
+    pub(crate) entry: Element<T>,
+    /// Number of values stored
+    pub(crate) len: usize,
 }
 
 /// Outcome of a searching with a given label against an entry
-enum FindOutcome<'a> {
+pub(crate) enum FindOutcome<'a> {
     /// The given label matches the label of an element.
     /// The index of that element is included
     /// Example:
@@ -49,6 +60,123 @@ enum FindOutcome<'a> {
     BeyondSizeLimit,
 }
 
+impl<T> Default for RadixTrie<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone> Clone for RadixTrie<T> {
+    fn clone(&self) -> Self {
+        RadixTrie {
+            entry: self.entry.clone(),
+            len: self.len,
+        }
+    }
+}
+
+/// An entry taken out of the trie by `remove_sorted` while labels are removed below it
+struct RemoveFrame<'a, T> {
+    entry: Vec<Element<T>>,
+    /// The labels to remove grouped by the index of the child they go into
+    groups: Vec<(usize, Vec<(usize, &'a str)>)>,
+    /// The index of the child whose children are in the frame above
+    descended: usize,
+}
+
+impl<'a, T> RemoveFrame<'a, T> {
+    fn new(entry: Vec<Element<T>>, labels: &[(usize, &'a str)]) -> Self {
+        // sorted labels going into the same child are next to each other
+        let mut groups: Vec<(usize, Vec<(usize, &str)>)> = vec![];
+        for &(position, label) in labels {
+            let (target_index, rest) = match RadixTrie::find_from_entry(&entry, label) {
+                ExactMatch(target_index) => (target_index, ""),
+                PrefixMatch(target_index) => {
+                    (target_index, &label[entry[target_index].label().len()..])
+                }
+                BeyondSizeLimit | NotMatch(_) | Intersects(_, _) | AsPrefixOf(_) => continue,
+            };
+            match groups.last_mut() {
+                Some((index, group)) if *index == target_index => group.push((position, rest)),
+                _ => groups.push((target_index, vec![(position, rest)])),
+            }
+        }
+        RemoveFrame {
+            entry,
+            groups,
+            descended: 0,
+        }
+    }
+}
+
+impl<T> Drop for RadixTrie<T> {
+    fn drop(&mut self) {
+        Element::drop_all(self.entry.children_mut())
+    }
+}
+
+/// Tries are equal when they hold the same labels with equal values
+impl<T: PartialEq> PartialEq for RadixTrie<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for RadixTrie<T> {}
+
+/// Hashes the labels and values in lexicographic order, consistent with `PartialEq`
+impl<T: Hash> Hash for RadixTrie<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len.hash(state);
+        self.iter().for_each(|entry| entry.hash(state));
+    }
+}
+
+/// Returns the borrowed value of the label
+///
+/// # Panics
+/// Panics if the label does not exist in the trie
+impl<T> Index<&str> for RadixTrie<T> {
+    type Output = T;
+
+    fn index(&self, label: &str) -> &T {
+        match self.find(label) {
+            Some(value) => value,
+            None => panic!("Label {:?} does not exist in the trie", label),
+        }
+    }
+}
+
+/// Returns the mutable borrowed value of the label
+///
+/// # Panics
+/// Panics if the label does not exist in the trie
+///
+/// # Example
+/// ```rust
+/// use another_radix_trie::RadixTrie;
+///
+/// let mut trie = RadixTrie::<usize>::new();
+/// trie.insert("label", 5);
+/// trie["label"] += 1;
+/// assert_eq!(trie["label"], 6);
+/// ```
+impl<T> IndexMut<&str> for RadixTrie<T> {
+    fn index_mut(&mut self, label: &str) -> &mut T {
+        match self.find_mut(label) {
+            Some(value) => value,
+            None => panic!("Label {:?} does not exist in the trie", label),
+        }
+    }
+}
+
+/// Formats the trie as a map from labels to values in lexicographic order
+impl<T: fmt::Debug> fmt::Debug for RadixTrie<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
 impl<T> RadixTrie<T> {
     /// Construct a new trie
     pub fn new() -> Self {
@@ -57,73 +185,177 @@ impl<T> RadixTrie<T> {
                 label: "".to_owned(),
                 children: vec![],
             },
+            len: 0,
         }
     }
 
+    /// Number of values stored, tracked on every insertion and removal
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("ON", 3);
+    /// trie.insert("ON", 4);
+    /// assert_eq!(trie.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if no value is stored
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Removes all labels and values, keeping the allocation of the root for reuse
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("label", 5);
+    /// trie.clear();
+    /// assert!(trie.is_empty());
+    /// assert_eq!(trie.find("label"), None);
+    /// ```
+    pub fn clear(&mut self) {
+        Element::drop_all(self.entry.children_mut());
+        self.len = 0;
+    }
+
+    /// Number of elements in the trie, including the ones without value but excluding the root.
+    /// Compared with `len`, it shows how well the labels are compressed
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("ON2", 3);
+    /// trie.insert("ON3", 4);
+    /// // "ON" is shared by both labels
+    /// assert_eq!(trie.node_count(), 3);
+    /// ```
+    pub fn node_count(&self) -> usize {
+        let mut count = 0;
+        let mut stack = vec![&self.entry];
+        while let Some(element) = stack.pop() {
+            count += element.children().len();
+            stack.extend(element.children());
+        }
+        count
+    }
+
     /// Insert label and associated value into the trie.
-    /// Values will be override if the label provided is already in the trie
+    /// Values will be override if the label provided is already in the trie,
+    /// and the replaced value is returned.
+    /// Inserting the empty label does nothing and returns None
     /// # Example
     /// ```rust
     /// use another_radix_trie::RadixTrie;
     ///
-    /// let mut trie = RadixTrie::<()>::new();
-    /// trie.insert("label", ());
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// assert_eq!(trie.insert("label", 5), None);
+    /// assert_eq!(trie.insert("label", 6), Some(5));
     /// ```
-    pub fn insert(&mut self, mut label: &str, value: T) {
-        let mut entry = (&mut self.entry).children_mut();
-        while label.len() > 0 {
-            match Self::find_from_entry(&entry, label) {
-                BeyondSizeLimit => return entry.push(util::value_element(label, value, vec![])),
-                AsPrefixOf(index) => return Self::insert_prefix_node(entry, index, label, value),
-                Intersects(index, shared_prefix) => {
-                    let shared_prefix = shared_prefix.to_owned();
-                    return Self::join_intersected_nodes(entry, index, shared_prefix, label, value);
-                }
-                NotMatch(index) => {
-                    let merged = util::value_element(label, value, vec![]);
-                    return entry.insert(index, merged);
-                }
-                ExactMatch(index) => {
-                    let target = &mut entry[index];
-                    return match target.value_mut() {
-                        Some(old_value) => *old_value = value,
-                        None => Element::node_to_value(&mut entry[index], value),
-                    };
-                }
-                PrefixMatch(index) => {
-                    let target = &mut entry[index];
-                    label = &label[target.label().len()..];
-                    entry = target.children_mut();
-                }
+    pub fn insert<K: AsRef<str>>(&mut self, label: K, value: T) -> Option<T> {
+        let label = label.as_ref();
+        if label.is_empty() {
+            return None;
+        }
+        match self.locate(label) {
+            (path, Location::Occupied) => {
+                let old_value = self.element_at_mut(&path).value_mut().unwrap();
+                Some(core::mem::replace(old_value, value))
+            }
+            (path, Location::Vacant(matched, position)) => {
+                self.insert_at(&path, &label[matched..], position, value);
+                None
             }
         }
     }
 
-    fn insert_prefix_node(entry: &mut Vec<Element<T>>, index: usize, label: &str, value: T) {
+    pub(crate) fn insert_prefix_node(
+        entry: &mut Vec<Element<T>>,
+        index: usize,
+        label: String,
+        mut children: Vec<Element<T>>,
+        value: T,
+    ) -> &mut T {
         let mut origin = entry.remove(index);
         origin.remove_label_prefix(label.len());
-        let new_value = util::value_element(label, value, vec![origin]);
-        return entry.insert(index, new_value);
+        children.push(origin);
+        entry.insert(index, Element::pack(label, Some(value), children));
+        entry[index].value_mut().unwrap()
     }
 
     /// When two nodes have intersected labels, call this helper to process
-    fn join_intersected_nodes(
+    pub(crate) fn join_intersected_nodes(
         entry: &mut Vec<Element<T>>,
         index: usize,
         shared_prefix: String,
-        label: &str,
+        label: String,
+        mut children: Vec<Element<T>>,
         value: T,
-    ) {
+    ) -> &mut T {
         let mut original = entry.remove(index);
         original.remove_label_prefix(shared_prefix.len());
-        let new = util::value_element(&label[shared_prefix.len()..], value, vec![]);
-        let mut children = vec![original, new];
-        children.sort_by(|e1, e2| e1.label().cmp(e2.label()));
-        let merged = Element::Node {
-            label: shared_prefix,
-            children,
-        };
-        entry.insert(index, merged)
+        let new = Element::pack(label, Some(value), vec![]);
+        let new_index = if original.label() < new.label() { 1 } else { 0 };
+        children.push(original);
+        children.insert(new_index, new);
+        let merged = Element::pack(shared_prefix, None, children);
+        entry.insert(index, merged);
+        entry[index].children_mut()[new_index].value_mut().unwrap()
+    }
+
+    /// Returns the mutable value associated with the label.
+    /// If the label does not exist, the value created by `make_value` is inserted first
+    pub(crate) fn get_or_insert_with<F>(&mut self, label: &str, make_value: F) -> &mut T
+    where
+        F: FnOnce() -> T,
+    {
+        self.entry(label).or_insert_with(make_value)
+    }
+
+    /// Returns the mutable borrowed value associated with related label.
+    /// If the label does not exist in the trie, the default value is inserted in the same traversal
+    ///
+    /// # Panics
+    /// Panics if the label is empty, since the empty label cannot hold a value
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// *trie.get_or_default("label") += 1;
+    /// *trie.get_or_default("label") += 1;
+    /// assert_eq!(trie.find("label"), Some(&2));
+    /// ```
+    pub fn get_or_default<K: AsRef<str>>(&mut self, label: K) -> &mut T
+    where
+        T: Default,
+    {
+        self.entry(label).or_default()
+    }
+
+    /// Returns the mutable borrowed value associated with related label.
+    /// If the label does not exist in the trie, the given value is inserted in the same traversal
+    ///
+    /// # Panics
+    /// Panics if the label is empty, since the empty label cannot hold a value
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// for word in "the cat and the hat".split(' ') {
+    ///     *trie.insert_or_get_mut(word, 0) += 1;
+    /// }
+    /// assert_eq!(trie.find("the"), Some(&2));
+    /// ```
+    pub fn insert_or_get_mut<K: AsRef<str>>(&mut self, label: K, default: T) -> &mut T {
+        self.entry(label).or_insert(default)
     }
 
     /// Returns the borrowed value associated with related label.
@@ -137,132 +369,814 @@ impl<T> RadixTrie<T> {
     /// assert_eq!(trie.find("label"), Some(&5));
     /// assert_eq!(trie.find("not exist"), None);
     /// ```
-    pub fn find(&self, mut label: &str) -> Option<&T> {
+    pub fn find<K: AsRef<str>>(&self, label: K) -> Option<&T> {
+        let mut label = label.as_ref();
+        let mut entry = self.entry.children();
+        while !label.is_empty() {
+            match Self::find_from_entry(entry, label) {
+                NotMatch(_) | AsPrefixOf(_) | Intersects(_, _) | BeyondSizeLimit => break,
+                PrefixMatch(target_index) => {
+                    let target = &entry[target_index];
+                    label = &label[target.label().len()..];
+                    entry = target.children();
+                }
+                ExactMatch(target_index) => {
+                    return entry[target_index].value();
+                }
+            }
+        }
+        None
+    }
+
+    /// Returns a copy of the value associated with related label.
+    /// If the label does not exist in the trie, return None
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("label", 5);
+    /// assert_eq!(trie.get_copied("label"), Some(5));
+    /// assert_eq!(trie.get_copied("not exist"), None);
+    /// ```
+    pub fn get_copied<K: AsRef<str>>(&self, label: K) -> Option<T>
+    where
+        T: Copy,
+    {
+        self.find(label).copied()
+    }
+
+    /// Returns the mutable borrowed value associated with related label.
+    /// If the label does not exist in the
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("label", 5);
+    /// assert_eq!(trie.find_mut("label"), Some(&mut 5));
+    /// assert_eq!(trie.find("not exist"), None);
+    /// ```
+    pub fn find_mut<K: AsRef<str>>(&mut self, label: K) -> Option<&mut T> {
+        let mut label = label.as_ref();
+        let mut entry = self.entry.children_mut();
+        while !label.is_empty() {
+            match Self::find_from_entry(entry, label) {
+                NotMatch(_) | AsPrefixOf(_) | Intersects(_, _) | BeyondSizeLimit => break,
+                PrefixMatch(target_index) => {
+                    let target = &mut entry[target_index];
+                    label = &label[target.label().len()..];
+                    entry = target.children_mut();
+                }
+                ExactMatch(target_index) => {
+                    return entry[target_index].value_mut();
+                }
+            }
+        }
+        None
+    }
+
+    /// Returns the borrowed value associated with related label.
+    /// It is the same as [`RadixTrie::find`], named after the std maps
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// let label = String::from("label");
+    /// trie.insert(&label, 5);
+    /// assert_eq!(trie.get(&label), Some(&5));
+    /// assert_eq!(trie.get("lab"), None);
+    /// ```
+    pub fn get<K: AsRef<str>>(&self, label: K) -> Option<&T> {
+        self.find(label)
+    }
+
+    /// Returns the mutable borrowed value associated with related label.
+    /// It is the same as [`RadixTrie::find_mut`], named after the std maps
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert(String::from("label"), 5);
+    /// *trie.get_mut("label").unwrap() += 1;
+    /// assert_eq!(trie.get("label"), Some(&6));
+    /// ```
+    pub fn get_mut<K: AsRef<str>>(&mut self, label: K) -> Option<&mut T> {
+        self.find_mut(label)
+    }
+
+    /// Returns the borrowed values of all the given labels, in the same order as the labels,
+    /// with None for the labels that do not exist in the trie. The labels are looked up in sorted order,
+    /// and every lookup resumes from the deepest element shared with the previous label
+    /// instead of descending from the root again
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("lab", 3);
+    /// trie.insert("label", 5);
+    /// assert_eq!(trie.find_many(vec!["label", "not exist", "lab"]), vec![Some(&5), None, Some(&3)]);
+    /// ```
+    pub fn find_many<'a, I>(&self, labels: I) -> Vec<Option<&T>>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let mut labels = labels.into_iter().enumerate().collect::<Vec<_>>();
+        let mut found = labels.iter().map(|_| None).collect::<Vec<_>>();
+        labels.sort_by_key(|(_, label)| *label);
+        // elements matched by the previous label, with the length of the label up to their end
+        let mut path = vec![(0, &self.entry)];
+        let mut previous = "";
+        for (position, label) in labels {
+            let shared = util::longest_shared_prefix(previous, label).len();
+            while path.last().unwrap().0 > shared {
+                path.pop();
+            }
+            let (mut matched, mut element) = *path.last().unwrap();
+            while matched < label.len() {
+                let children = element.children();
+                match Self::find_from_entry(children, &label[matched..]) {
+                    ExactMatch(index) | PrefixMatch(index) => {
+                        element = &children[index];
+                        matched += element.label().len();
+                        path.push((matched, element));
+                    }
+                    _ => break,
+                }
+            }
+            if matched == label.len() {
+                found[position] = element.value();
+            }
+            previous = label;
+        }
+        found
+    }
+
+    /// Returns true if the label exists in the trie
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("label", 5);
+    /// assert!(trie.contains_key("label"));
+    /// assert!(!trie.contains_key("lab"));
+    /// ```
+    pub fn contains_key<K: AsRef<str>>(&self, label: K) -> bool {
+        self.find(label).is_some()
+    }
+
+    /// Returns the label together with its borrowed value.
+    /// If the label does not exist in the trie, return None
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("label", 5);
+    /// assert_eq!(trie.get_key_value("label"), Some(("label", &5)));
+    /// assert_eq!(trie.get_key_value("lab"), None);
+    /// ```
+    pub fn get_key_value<'a>(&self, label: &'a str) -> Option<(&'a str, &T)> {
+        self.find(label).map(|value| (label, value))
+    }
+
+    /// Returns the borrowed value associated with the longest label
+    /// that is a prefix of the given query, together with the matched part of the query.
+    /// If no label in the trie is a prefix of the query, return None
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<&str>::new();
+    /// trie.insert("/api", "api");
+    /// trie.insert("/api/users", "users");
+    /// assert_eq!(trie.longest_prefix("/api/users/42"), Some(("/api/users", &"users")));
+    /// assert_eq!(trie.longest_prefix("/api/posts"), Some(("/api", &"api")));
+    /// assert_eq!(trie.longest_prefix("/web"), None);
+    /// ```
+    pub fn longest_prefix<'a>(&self, query: &'a str) -> Option<(&'a str, &T)> {
+        self.find_longest_prefix_where(query, |_| true)
+    }
+
+    /// Returns all labels that are prefixes of the given query, from the shortest to the longest.
+    /// Each label is returned as the matched part of the query together with its value
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<&str>::new();
+    /// trie.insert("1", "country");
+    /// trie.insert("1416", "city");
+    /// trie.insert("14165", "exchange");
+    /// assert_eq!(
+    ///     trie.common_prefixes("14165550123"),
+    ///     vec![("1", &"country"), ("1416", &"city"), ("14165", &"exchange")]
+    /// );
+    /// ```
+    pub fn common_prefixes<'a>(&self, query: &'a str) -> Vec<(&'a str, &T)> {
+        let mut entry = self.entry.children();
+        let mut matched_len = 0;
+        let mut prefixes = vec![];
+        while matched_len < query.len() {
+            match Self::find_from_entry(entry, &query[matched_len..]) {
+                NotMatch(_) | AsPrefixOf(_) | Intersects(_, _) | BeyondSizeLimit => break,
+                ExactMatch(target_index) | PrefixMatch(target_index) => {
+                    let target = &entry[target_index];
+                    matched_len += target.label().len();
+                    if let Some(value) = target.value() {
+                        prefixes.push((&query[..matched_len], value));
+                    }
+                    entry = target.children();
+                }
+            }
+        }
+        prefixes
+    }
+
+    /// Returns the matched part of the query and the value of the longest label
+    /// that is a prefix of the query and whose value is accepted
+    pub(crate) fn find_longest_prefix_where<'a, F>(
+        &self,
+        query: &'a str,
+        accept: F,
+    ) -> Option<(&'a str, &T)>
+    where
+        F: Fn(&T) -> bool,
+    {
+        let mut entry = self.entry.children();
+        let mut matched_len = 0;
+        let mut longest = None;
+        while matched_len < query.len() {
+            match Self::find_from_entry(entry, &query[matched_len..]) {
+                NotMatch(_) | AsPrefixOf(_) | Intersects(_, _) | BeyondSizeLimit => break,
+                ExactMatch(target_index) | PrefixMatch(target_index) => {
+                    let target = &entry[target_index];
+                    matched_len += target.label().len();
+                    if let Some(value) = target.value().filter(|value| accept(value)) {
+                        longest = Some((matched_len, value));
+                    }
+                    entry = target.children();
+                }
+            }
+        }
+        longest.map(|(len, value)| (&query[..len], value))
+    }
+
+    /// Returns the mutable borrowed value associated with the longest label
+    /// that is a prefix of the given query, together with the matched part of the query.
+    /// If no label in the trie is a prefix of the query, return None
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("/api", 0);
+    /// trie.insert("/api/users", 0);
+    /// if let Some((_, hits)) = trie.find_longest_prefix_mut("/api/users/42") {
+    ///     *hits += 1;
+    /// }
+    /// assert_eq!(trie.find("/api/users"), Some(&1));
+    /// assert_eq!(trie.find_longest_prefix_mut("/web"), None);
+    /// ```
+    pub fn find_longest_prefix_mut<'a>(&mut self, query: &'a str) -> Option<(&'a str, &mut T)> {
+        let mut entry = self.entry.children_mut();
+        let mut matched_len = 0;
+        let mut longest = None;
+        while matched_len < query.len() {
+            let label = &query[matched_len..];
+            match Self::find_from_entry(entry, label) {
+                NotMatch(_) | AsPrefixOf(_) | Intersects(_, _) | BeyondSizeLimit => break,
+                ExactMatch(target_index) | PrefixMatch(target_index) => {
+                    let target = &mut entry[target_index];
+                    matched_len += target.label().len();
+                    let (value, children) = target.value_and_children_mut();
+                    if let Some(value) = value {
+                        longest = Some((matched_len, value));
+                    }
+                    entry = children;
+                }
+            }
+        }
+        longest.map(|(len, value)| (&query[..len], value))
+    }
+
+    /// Removes the value associated with related label.
+    /// If the provided label does not exist in the trie, return None
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("label", 5);
+    /// assert_eq!(trie.remove("label"), Some(5));
+    /// assert_eq!(trie.remove("not exist"), None);
+    /// ```
+    pub fn remove<K: AsRef<str>>(&mut self, label: K) -> Option<T> {
+        self.remove_if(label, |_| true)
+    }
+
+    /// Removes the value associated with related label if the predicate returns true for it,
+    /// checking and removing in one traversal.
+    /// If the label does not exist or the predicate returns false, the trie is unchanged and None is returned
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("label", 5);
+    /// assert_eq!(trie.remove_if("label", |value| *value > 5), None);
+    /// assert_eq!(trie.remove_if("label", |value| *value == 5), Some(5));
+    /// assert!(trie.is_empty());
+    /// ```
+    pub fn remove_if<K, F>(&mut self, label: K, predicate: F) -> Option<T>
+    where
+        K: AsRef<str>,
+        F: FnOnce(&T) -> bool,
+    {
+        let label = label.as_ref();
+        if label.is_empty() {
+            return None;
+        }
+        let path = match self.locate(label) {
+            (path, Location::Occupied) => path,
+            (_, Location::Vacant(_, _)) => return None,
+        };
+        if !self.element_at(&path).value().is_some_and(predicate) {
+            return None;
+        }
+        Some(self.remove_at(&path))
+    }
+
+    /// Removes the value of the element at the path, given as the indices of the children
+    /// from the root down, and compacts the elements left around it
+    pub(crate) fn remove_at(&mut self, path: &[usize]) -> T {
+        let (&target_index, ancestors) = path.split_last().unwrap();
+        let parent = self.element_at_mut(ancestors);
+        let (label, value, mut children) = parent.children_mut().remove(target_index).unpack();
+        if children.len() > 1 {
+            // target node has more than one children. Make target node a none value node
+            let node = Element::pack(label, None, children);
+            parent.children_mut().insert(target_index, node);
+        } else if children.len() == 1 {
+            // Only one child. Make the child parent
+            let mut child = children.pop().unwrap();
+            child.add_label_prefix(label);
+            parent.children_mut().insert(target_index, child);
+        }
+        self.finish_removal(ancestors, 1);
+        value.unwrap()
+    }
+
+    /// Finishes a removal of values below the element at the path:
+    /// the element is merged into its only child if it is a node left with one child,
+    /// and the removed values are taken off the counts of the elements on the path
+    pub(crate) fn finish_removal(&mut self, path: &[usize], removed: usize) {
+        let parent = self.element_at_mut(path);
+        let merged = parent.is_node() && parent.children().len() == 1;
+        if merged {
+            let mut another_child = parent.children_mut().pop().unwrap();
+            another_child.add_label_prefix(parent.label());
+            *parent = another_child;
+        }
+        // the merged child already holds the right count
+        let counted = &path[..path.len() - merged as usize];
+        Element::update_counts(self.entry.children_mut(), counted, |count| {
+            *count -= removed
+        });
+        self.len -= removed;
+    }
+
+    /// Returns the element at the path, given as the indices of the children from the root down
+    pub(crate) fn element_at(&self, path: &[usize]) -> &Element<T> {
+        path.iter()
+            .fold(&self.entry, |element, &index| &element.children()[index])
+    }
+
+    /// Returns the mutable element at the path, like [`RadixTrie::element_at`]
+    pub(crate) fn element_at_mut(&mut self, path: &[usize]) -> &mut Element<T> {
+        path.iter().fold(&mut self.entry, |element, &index| {
+            &mut element.children_mut()[index]
+        })
+    }
+
+    /// Takes the value of the label out of the trie, handing over its ownership.
+    /// It is the same as [`RadixTrie::remove`]
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<String>::new();
+    /// trie.insert("label", String::from("value"));
+    /// assert_eq!(trie.take("label"), Some(String::from("value")));
+    /// assert!(trie.is_empty());
+    /// ```
+    pub fn take<K: AsRef<str>>(&mut self, label: K) -> Option<T> {
+        self.remove(label)
+    }
+
+    /// Removes the values associated with all the given labels in one traversal.
+    /// The removed values are returned in the same order as the labels,
+    /// with None for the labels that do not exist in the trie
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("lab", 3);
+    /// trie.insert("label", 5);
+    /// assert_eq!(trie.remove_many(vec!["label", "not exist", "lab"]), vec![Some(5), None, Some(3)]);
+    /// ```
+    pub fn remove_many<'a, I>(&mut self, labels: I) -> Vec<Option<T>>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let mut labels = labels.into_iter().enumerate().collect::<Vec<_>>();
+        let mut removed = labels.iter().map(|_| None).collect::<Vec<_>>();
+        labels.retain(|(_, label)| !label.is_empty());
+        labels.sort_by_key(|(_, label)| *label);
+        Self::remove_sorted(self.entry.children_mut(), &labels, &mut removed);
+        self.len -= removed.iter().filter(|value| value.is_some()).count();
+        removed
+    }
+
+    /// Removes the sorted labels from the entry, then compacts every visited child once.
+    /// The children of the element being descended into are moved into their own frame,
+    /// so the walk needs no recursion however deep the labels go
+    fn remove_sorted(
+        entry: &mut Vec<Element<T>>,
+        labels: &[(usize, &str)],
+        removed: &mut [Option<T>],
+    ) {
+        let mut stack = vec![RemoveFrame::new(core::mem::take(entry), labels)];
+        loop {
+            let top = stack.last_mut().unwrap();
+            // process from the back so that compacting a child does not shift the others
+            if let Some((target_index, group)) = top.groups.pop() {
+                let target = &mut top.entry[target_index];
+                let deeper_start = group.iter().take_while(|(_, rest)| rest.is_empty()).count();
+                for &(position, _) in &group[..deeper_start] {
+                    removed[position] = target.take_value();
+                }
+                if deeper_start == group.len() {
+                    Self::compact_child(&mut top.entry, target_index);
+                } else {
+                    let children = core::mem::take(target.children_mut());
+                    top.descended = target_index;
+                    stack.push(RemoveFrame::new(children, &group[deeper_start..]));
+                }
+                continue;
+            }
+            let finished = stack.pop().unwrap();
+            match stack.last_mut() {
+                Some(parent) => {
+                    let target_index = parent.descended;
+                    *parent.entry[target_index].children_mut() = finished.entry;
+                    Self::compact_child(&mut parent.entry, target_index);
+                }
+                None => return *entry = finished.entry,
+            }
+        }
+    }
+
+    /// Removes all values with their labels where the labels start with given prefix,
+    /// detaching the whole subtree in one traversal.
+    /// The removed labels and values are returned in lexicographic order
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("/tmp/a", 1);
+    /// trie.insert("/tmp/b", 2);
+    /// trie.insert("/usr", 3);
+    /// let removed = trie.remove_prefix("/tmp/");
+    /// assert_eq!(removed, vec![(String::from("/tmp/a"), 1), (String::from("/tmp/b"), 2)]);
+    /// assert_eq!(trie.find("/usr"), Some(&3));
+    /// ```
+    pub fn remove_prefix(&mut self, prefix: &str) -> Vec<(String, T)> {
+        self.drain_prefix(prefix).collect()
+    }
+
+    /// Moves all labels starting with given prefix with their values into a new trie and returns it.
+    /// The subtree is detached as a whole, so no label is removed or inserted one by one.
+    /// Labels keep their full form in the new trie
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("/tmp/a", 1);
+    /// trie.insert("/tmp/b", 2);
+    /// trie.insert("/usr", 3);
+    /// let tmp = trie.split_off("/tmp/");
+    /// assert_eq!(tmp.find("/tmp/a"), Some(&1));
+    /// assert_eq!(tmp.len(), 2);
+    /// assert_eq!(trie.find("/tmp/a"), None);
+    /// assert_eq!(trie.len(), 1);
+    /// ```
+    pub fn split_off(&mut self, prefix: &str) -> RadixTrie<T> {
+        let mut trie = RadixTrie::new();
+        if prefix.is_empty() {
+            core::mem::swap(&mut trie.entry, &mut self.entry);
+            trie.len = core::mem::take(&mut self.len);
+            return trie;
+        }
+        if let Some((existing_prefix, mut detached)) = self.detach_prefix(prefix) {
+            trie.len = detached.value_count();
+            detached.add_label_prefix(existing_prefix);
+            trie.entry.children_mut().push(detached);
+        }
+        trie
+    }
+
+    /// Detaches the subtree of all labels starting with given prefix.
+    /// The joined labels of the ancestors of the subtree are returned with it
+    pub(crate) fn detach_prefix(&mut self, prefix: &str) -> Option<(String, Element<T>)> {
+        let (path, existing_prefix) = self.locate_prefix(prefix)?;
+        Some((existing_prefix, self.detach_at(&path)))
+    }
+
+    /// Detaches the element at the non-empty path with all its descendants
+    pub(crate) fn detach_at(&mut self, path: &[usize]) -> Element<T> {
+        let (&target_index, ancestors) = path.split_last().unwrap();
+        let detached = self
+            .element_at_mut(ancestors)
+            .children_mut()
+            .remove(target_index);
+        self.finish_removal(ancestors, detached.value_count());
+        detached
+    }
+
+    /// Finds the element holding all labels starting with given prefix,
+    /// as the indices of the children from the root down to it.
+    /// The root has no index, so the empty prefix is never found.
+    /// The joined labels of the ancestors of the element are returned with it
+    pub(crate) fn locate_prefix(&self, prefix: &str) -> Option<(Vec<usize>, String)> {
+        let mut path = vec![];
+        let mut existing_prefix = String::new();
+        let found = self.descend_prefix(prefix, |index, ancestor| {
+            path.push(index);
+            existing_prefix.push_str(ancestor);
+            Ok::<_, Infallible>(())
+        });
+        match found {
+            Ok(true) => Some((path, existing_prefix)),
+            Ok(false) => None,
+            Err(never) => match never {},
+        }
+    }
+
+    /// Finds the element holding all labels starting with given prefix like [`RadixTrie::locate_prefix`],
+    /// handing the index and the label of every ancestor to the closure from the top down,
+    /// and then the index of the element with an empty label.
+    /// Returns whether the element is found, and the first error of the closure stops the search
+    pub(crate) fn descend_prefix<E, F>(&self, mut prefix: &str, mut visit: F) -> Result<bool, E>
+    where
+        F: FnMut(usize, &str) -> Result<(), E>,
+    {
+        let mut entry = self.entry.children();
+        while !prefix.is_empty() {
+            match Self::find_from_entry(entry, prefix) {
+                BeyondSizeLimit | NotMatch(_) | Intersects(_, _) => break,
+                PrefixMatch(target_index) => {
+                    let target = &entry[target_index];
+                    visit(target_index, target.label())?;
+                    prefix = &prefix[target.label().len()..];
+                    entry = target.children();
+                }
+                ExactMatch(target_index) | AsPrefixOf(target_index) => {
+                    visit(target_index, "")?;
+                    return Ok(true);
+                }
+            }
+        }
+        Ok(false)
+    }
+
+    /// Prune the child if it is a node without children,
+    /// or merge it with its only child if it is a node with one child
+    pub(crate) fn compact_child(entry: &mut Vec<Element<T>>, index: usize) {
+        let child = &mut entry[index];
+        // the children are final, so their counts add up to the new count of the child
+        child.recount();
+        if !child.is_node() {
+            return;
+        }
+        match child.children().len() {
+            0 => {
+                entry.remove(index);
+            }
+            1 => {
+                let mut grandchild = child.children_mut().pop().unwrap();
+                grandchild.add_label_prefix(child.label());
+                entry[index] = grandchild;
+            }
+            _ => (),
+        }
+    }
+
+    /// Returns all values with their labels where the labels start with given prefix.
+    /// Every label starts with the empty prefix
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("lab", 3);
+    /// trie.insert("label", 5);
+    /// assert_eq!(trie.start_with("la"), vec![(String::from("lab"), &3), (String::from("label"), &5)])
+    /// ```
+    pub fn start_with(&self, prefix: &str) -> Vec<(String, &T)> {
+        let mut existing_prefix = String::new();
+        match self.find_prefix_element(prefix, |label| existing_prefix.push_str(label)) {
+            Some(element) => Self::format_children(element, &existing_prefix),
+            None => vec![],
+        }
+    }
+
+    /// Returns the number of labels starting with given prefix.
+    /// Every element stores the number of values below it,
+    /// so this only descends to the prefix and allocates nothing
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("lab", 3);
+    /// trie.insert("label", 5);
+    /// trie.insert("lazy", 4);
+    /// assert_eq!(trie.count_prefix("lab"), 2);
+    /// assert_eq!(trie.count_prefix("x"), 0);
+    /// ```
+    pub fn count_prefix(&self, prefix: &str) -> usize {
+        self.find_prefix_element(prefix, |_| ())
+            .map_or(0, Element::value_count)
+    }
+
+    /// Returns copies of all values with their labels where the labels start with given prefix
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("lab", 3);
+    /// trie.insert("label", 5);
+    /// assert_eq!(trie.start_with_copied("la"), vec![(String::from("lab"), 3), (String::from("label"), 5)])
+    /// ```
+    pub fn start_with_copied(&self, prefix: &str) -> Vec<(String, T)>
+    where
+        T: Copy,
+    {
+        self.start_with(prefix)
+            .into_iter()
+            .map(|(label, value)| (label, *value))
+            .collect()
+    }
+
+    /// Returns the remainder of every label after the given prefix with their values,
+    /// in lexicographic order. The label equal to the prefix yields an empty remainder
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("industry", 1);
+    /// trie.insert("india", 2);
+    /// assert_eq!(trie.start_with_suffixes("ind"), vec![(String::from("ia"), &2), (String::from("ustry"), &1)])
+    /// ```
+    pub fn start_with_suffixes(&self, prefix: &str) -> Vec<(String, &T)> {
+        let mut ancestors_len = 0;
+        let element = match self.find_prefix_element(prefix, |label| ancestors_len += label.len()) {
+            Some(element) => element,
+            None => return vec![],
+        };
+        // the part of the prefix inside the label of the element
+        let skip = prefix.len() - ancestors_len;
+        let mut res = vec![];
+        element.for_each_value(&mut String::new(), |label, value| {
+            res.push((label[skip..].to_owned(), value))
+        });
+        res
+    }
+
+    /// Returns the element holding all the labels that start with given prefix,
+    /// which is the root for the empty prefix.
+    /// The labels of its ancestors are passed to `visit_ancestor` from the top down
+    pub(crate) fn find_prefix_element<'a, F>(
+        &'a self,
+        mut prefix: &str,
+        mut visit_ancestor: F,
+    ) -> Option<&'a Element<T>>
+    where
+        F: FnMut(&'a str),
+    {
+        if prefix.is_empty() {
+            return Some(&self.entry);
+        }
         let mut entry = self.entry.children();
-        while label.len() > 0 {
-            match Self::find_from_entry(&entry, label) {
-                NotMatch(_) | AsPrefixOf(_) | Intersects(_, _) | BeyondSizeLimit => break,
+        while !prefix.is_empty() {
+            match Self::find_from_entry(entry, prefix) {
+                BeyondSizeLimit | NotMatch(_) | Intersects(_, _) => break,
                 PrefixMatch(target_index) => {
+                    // existing_label matches the prefix of label. Move to next node
                     let target = &entry[target_index];
-                    label = &label[target.label().len()..];
+                    visit_ancestor(target.label());
+                    prefix = &prefix[target.label().len()..];
                     entry = target.children();
                 }
-                ExactMatch(target_index) => {
-                    return entry[target_index].value();
+                ExactMatch(target_index) | AsPrefixOf(target_index) => {
+                    return Some(&entry[target_index]);
                 }
             }
         }
         None
     }
 
-    /// Returns the mutable borrowed value associated with related label.
-    /// If the label does not exist in the
-    /// # Example
-    /// ```rust
-    /// use another_radix_trie::RadixTrie;
-    ///
-    /// let mut trie = RadixTrie::<usize>::new();
-    /// trie.insert("label", 5);
-    /// assert_eq!(trie.find_mut("label"), Some(&mut 5));
-    /// assert_eq!(trie.find("not exist"), None);
-    /// ```
-    pub fn find_mut(&mut self, mut label: &str) -> Option<&mut T> {
+    /// Finds the element holding all labels starting with given prefix for mutation,
+    /// which is the root for the empty prefix.
+    /// The joined labels of its ancestors are pushed to `existing_prefix`
+    pub(crate) fn find_prefix_element_mut<'a>(
+        &'a mut self,
+        mut prefix: &str,
+        existing_prefix: &mut String,
+    ) -> Option<&'a mut Element<T>> {
+        if prefix.is_empty() {
+            return Some(&mut self.entry);
+        }
         let mut entry = self.entry.children_mut();
-        while label.len() > 0 {
-            match Self::find_from_entry(&entry, label) {
-                NotMatch(_) | AsPrefixOf(_) | Intersects(_, _) | BeyondSizeLimit => break,
+        while !prefix.is_empty() {
+            match Self::find_from_entry(entry, prefix) {
+                BeyondSizeLimit | NotMatch(_) | Intersects(_, _) => break,
                 PrefixMatch(target_index) => {
                     let target = &mut entry[target_index];
-                    label = &label[target.label().len()..];
+                    existing_prefix.push_str(target.label());
+                    prefix = &prefix[target.label().len()..];
                     entry = target.children_mut();
                 }
-                ExactMatch(target_index) => {
-                    return entry[target_index].value_mut();
+                ExactMatch(target_index) | AsPrefixOf(target_index) => {
+                    return Some(&mut entry[target_index]);
                 }
             }
         }
         None
     }
 
-    /// Removes the value associated with related label.
-    /// If the provided label does not exist in the trie, return None
+    /// Returns all labels with a clone of their values in lexicographic order
     /// # Example
     /// ```rust
     /// use another_radix_trie::RadixTrie;
     ///
     /// let mut trie = RadixTrie::<usize>::new();
     /// trie.insert("label", 5);
-    /// assert_eq!(trie.remove("label"), Some(5));
-    /// assert_eq!(trie.remove("not exist"), None);
+    /// trie.insert("lab", 3);
+    /// assert_eq!(trie.to_sorted_vec(), vec![(String::from("lab"), 3), (String::from("label"), 5)])
     /// ```
-    pub fn remove(&mut self, mut label: &str) -> Option<T> {
-        let mut parent = &mut self.entry;
-        while label.len() > 0 {
-            match Self::find_from_entry(parent.children(), label) {
-                BeyondSizeLimit | NotMatch(_) | Intersects(_, _) | AsPrefixOf(_) => break,
-                ExactMatch(target_index) => {
-                    let parent_is_node = parent.is_node();
-                    let (label, value, mut children) =
-                        parent.children_mut().remove(target_index).unpack();
-                    if children.len() > 1 {
-                        // target node has more than one children. Make target node a none value node
-                        parent
-                            .children_mut()
-                            .insert(target_index, Element::Node { label, children });
-                    } else if children.len() == 1 {
-                        // Only one child. Make the child parent
-                        let mut child = children.pop().unwrap();
-                        child.add_label_prefix(label);
-                        parent.children_mut().insert(target_index, child);
-                    }
-                    // if parent has only one node child and parent is node. Merge them
-                    if parent.children().len() == 1 && parent_is_node {
-                        let mut another_child = parent.children_mut().pop().unwrap();
-                        another_child.add_label_prefix(parent.label());
-                        *parent = another_child;
-                    }
-                    return value;
-                }
-                PrefixMatch(target_index) => {
-                    let target = &parent.children()[target_index];
-                    label = &label[target.label().len()..];
-                    parent = &mut parent.children_mut()[target_index];
-                }
-            }
-        }
-        None
+    pub fn to_sorted_vec(&self) -> Vec<(String, T)>
+    where
+        T: Clone,
+    {
+        let mut res = vec![];
+        let mut buf = String::new();
+        self.entry.for_each_value(&mut buf, |label, value| {
+            res.push((label.to_owned(), value.clone()))
+        });
+        res
     }
 
-    /// Returns all values with their labels where the labels start with given prefix
+    /// Returns all labels with their borrowed values in lexicographic order
     /// # Example
     /// ```rust
     /// use another_radix_trie::RadixTrie;
     ///
     /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("label", 5);
     /// trie.insert("lab", 3);
+    /// assert_eq!(trie.to_sorted_vec_ref(), vec![(String::from("lab"), &3), (String::from("label"), &5)])
+    /// ```
+    pub fn to_sorted_vec_ref(&self) -> Vec<(String, &T)> {
+        let mut res = vec![];
+        let mut buf = String::new();
+        self.entry
+            .for_each_value(&mut buf, |label, value| res.push((label.to_owned(), value)));
+        res
+    }
+
+    /// Compile the trie into a read-only flat representation optimized for lookups
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
     /// trie.insert("label", 5);
-    /// assert_eq!(trie.start_with("la"), vec![(String::from("lab"), &3), (String::from("label"), &5)])
+    /// let compiled = trie.compile();
+    /// assert_eq!(compiled.find("label"), Some(&5));
     /// ```
-    pub fn start_with(&self, mut prefix: &str) -> Vec<(String, &T)> {
-        let mut entry = self.entry.children();
-        let mut prefixes: Vec<&str> = vec![];
-        while prefix.len() > 0 {
-            match Self::find_from_entry(entry, prefix) {
-                BeyondSizeLimit | NotMatch(_) | Intersects(_, _) => break,
-                PrefixMatch(target_index) => {
-                    // existing_label matches the prefix of label. Move to next node
-                    let target = &entry[target_index];
-                    prefixes.push(target.label());
-                    prefix = &prefix[target.label().len()..];
-                    entry = target.children();
-                }
-                ExactMatch(target_index) | AsPrefixOf(target_index) => {
-                    let existing_prefix: String = prefixes.join("");
-                    return Self::format_children(&entry[target_index], &existing_prefix);
-                }
-            }
-        }
-        vec![]
+    pub fn compile(self) -> CompiledRadixTrie<T> {
+        CompiledRadixTrie::from_element(self.into_entry())
     }
 
     fn format_children<'a>(entry: &'a Element<T>, prefix: &str) -> Vec<(String, &'a T)> {
@@ -276,8 +1190,36 @@ impl<T> RadixTrie<T> {
             .collect()
     }
 
+    /// Consumes the trie and returns its root element
+    pub(crate) fn into_entry(mut self) -> Element<T> {
+        let empty = Element::Base {
+            label: String::new(),
+            children: vec![],
+        };
+        core::mem::replace(&mut self.entry, empty)
+    }
+
+    /// The elements whose full labels are prefixes of the label, with those full labels, from the top down
+    pub(crate) fn path_to(&self, label: &str) -> Vec<(String, &Element<T>)> {
+        let mut path = vec![];
+        let mut full_label = String::new();
+        let mut entry = self.entry.children();
+        while full_label.len() < label.len() {
+            match Self::find_from_entry(entry, &label[full_label.len()..]) {
+                ExactMatch(target_index) | PrefixMatch(target_index) => {
+                    let target = &entry[target_index];
+                    full_label.push_str(target.label());
+                    path.push((full_label.clone(), target));
+                    entry = target.children();
+                }
+                _ => break,
+            }
+        }
+        path
+    }
+
     /// Run a binary search on the given entry and return outcome based on different conditions
-    fn find_from_entry<'a>(entry: &'a [Element<T>], label: &'a str) -> FindOutcome<'a> {
+    pub(crate) fn find_from_entry<'a>(entry: &'a [Element<T>], label: &'a str) -> FindOutcome<'a> {
         let char = util::first_char(label);
         let target_index = util::binary_search(char, entry);
         if target_index >= entry.len() {
@@ -301,7 +1243,36 @@ impl<T> RadixTrie<T> {
 
 #[cfg(test)]
 mod trie_tests {
+    use crate::element::Element;
+    use crate::test_util::{words_trie, Lcg};
     use crate::trie::RadixTrie;
+    use std::collections::BTreeMap;
+
+    /// Asserts no empty non-root label, sorted children with distinct first chars,
+    /// and no valueless element with fewer than two children
+    fn assert_invariants<T>(root: &Element<T>) {
+        let mut stack = vec![root];
+        while let Some(element) = stack.pop() {
+            let children = element.children();
+            for pair in children.windows(2) {
+                let first = pair[0].label().chars().next();
+                let second = pair[1].label().chars().next();
+                assert!(first < second, "unsorted children {:?}", first);
+            }
+            for child in children {
+                assert!(!child.label().is_empty());
+                assert_eq!(child.value_count(), child.counted_values());
+                if child.value().is_none() {
+                    assert!(
+                        child.children().len() >= 2,
+                        "degenerate node {}",
+                        child.label()
+                    );
+                }
+            }
+            stack.extend(children);
+        }
+    }
 
     #[test]
     fn test_insert_find_remove() {
@@ -321,7 +1292,7 @@ mod trie_tests {
         let mut trie = RadixTrie::<usize>::new();
         let words = ["Won", "Wonder", "Wonderful", "World", "Axes"];
         for word in &words {
-            trie.insert(word, word.len())
+            trie.insert(word, word.len());
         }
         for word in &words {
             assert_eq!(trie.find(word), Some(&word.len()));
@@ -334,7 +1305,7 @@ mod trie_tests {
         let mut trie = RadixTrie::<usize>::new();
         let words = ["Won", "Wonder", "Wonderful", "World", "Axes"];
         for word in &words {
-            trie.insert(word, word.len())
+            trie.insert(word, word.len());
         }
         let res = trie.start_with("W");
         let expected: Vec<(String, &usize)> = vec![
@@ -343,7 +1314,8 @@ mod trie_tests {
             ("Wonder".into(), &6),
             ("Wonderful".into(), &9),
         ];
-        assert_eq!(res, expected)
+        assert_eq!(res, expected);
+        assert_eq!(trie.start_with("").len(), words.len());
     }
 
     #[test]
@@ -351,7 +1323,7 @@ mod trie_tests {
         let mut trie = RadixTrie::<usize>::new();
         let words = ["Won", "Wonder", "Wonderful", "World", "Axes"];
         for word in &words {
-            trie.insert(word, word.len())
+            trie.insert(word, word.len());
         }
         let res = trie.start_with("Won");
         let expected: Vec<(String, &usize)> = vec![
@@ -395,4 +1367,693 @@ mod trie_tests {
         let found = trie.find("ON");
         assert_eq!(found, Some(&416));
     }
+
+    #[test]
+    fn test_find_longest_prefix_mut() {
+        let mut trie = RadixTrie::<usize>::new();
+        let words = ["Won", "Wonder", "Wonderful", "World"];
+        for word in &words {
+            trie.insert(word, 0);
+        }
+        for query in &["Wonders", "Wonderfully", "Wonder", "Wonk", "Worlds"] {
+            let (_, hits) = trie.find_longest_prefix_mut(query).unwrap();
+            *hits += 1;
+        }
+        assert_eq!(trie.find("Won"), Some(&1));
+        assert_eq!(trie.find("Wonder"), Some(&2));
+        assert_eq!(trie.find("Wonderful"), Some(&1));
+        assert_eq!(trie.find("World"), Some(&1));
+        let (matched, _) = trie.find_longest_prefix_mut("Wonderfully").unwrap();
+        assert_eq!(matched, "Wonderful");
+        assert_eq!(trie.find_longest_prefix_mut("Wo"), None);
+        assert_eq!(trie.find_longest_prefix_mut(""), None);
+    }
+
+    #[test]
+    fn test_remove_many() {
+        let words = [
+            "exe", "execute", "exec", "example", "Won", "Wonder", "World",
+        ];
+        let mut trie = RadixTrie::<usize>::new();
+        let mut expected = RadixTrie::<usize>::new();
+        for word in &words {
+            trie.insert(word, word.len());
+            expected.insert(word, word.len());
+        }
+        let removed = trie.remove_many(vec!["exec", "Wonder", "example", "exa", "Won", "exec"]);
+        assert_eq!(
+            removed,
+            vec![Some(4), Some(6), Some(7), None, Some(3), None]
+        );
+        for word in &["exec", "Wonder", "example", "Won"] {
+            expected.remove(word);
+        }
+        assert_eq!(format!("{:?}", trie.entry), format!("{:?}", expected.entry));
+        assert_eq!(trie.find("execute"), Some(&7));
+        assert_eq!(trie.find("World"), Some(&5));
+    }
+
+    #[test]
+    fn test_get_or_default() {
+        let mut trie = RadixTrie::<usize>::new();
+        let words = ["exe", "execute", "exec", "example", "exe", "ex", "execute"];
+        for word in &words {
+            *trie.get_or_default(word) += 1;
+        }
+        assert_eq!(trie.find("exe"), Some(&2));
+        assert_eq!(trie.find("execute"), Some(&2));
+        assert_eq!(trie.find("exec"), Some(&1));
+        assert_eq!(trie.find("example"), Some(&1));
+        assert_eq!(trie.find("ex"), Some(&1));
+        assert_eq!(trie.start_with("ex").len(), 5);
+    }
+
+    #[test]
+    fn test_copied_values() {
+        let mut trie = RadixTrie::<usize>::new();
+        let words = ["Won", "Wonder", "World"];
+        for word in &words {
+            trie.insert(word, word.len());
+        }
+        let copied = trie.get_copied("Wonder");
+        trie.insert("Wonder", 0);
+        assert_eq!(copied, Some(6));
+        assert_eq!(trie.get_copied("Wond"), None);
+        let expected: Vec<(String, usize)> = vec![("Won".into(), 3), ("Wonder".into(), 0)];
+        assert_eq!(trie.start_with_copied("Won"), expected);
+    }
+
+    #[test]
+    fn test_to_sorted_vec() {
+        let mut trie = RadixTrie::<usize>::new();
+        let words = [
+            "Wonderful",
+            "World",
+            "Won",
+            "Axes",
+            "Wonder",
+            "Toronto多倫多",
+            "Toronto",
+        ];
+        for word in &words {
+            trie.insert(word, word.len());
+        }
+        let mut expected = words
+            .iter()
+            .map(|word| (word.to_string(), word.len()))
+            .collect::<Vec<_>>();
+        expected.sort();
+        assert_eq!(trie.to_sorted_vec(), expected);
+        let borrowed = trie
+            .to_sorted_vec_ref()
+            .into_iter()
+            .map(|(label, value)| (label, *value))
+            .collect::<Vec<_>>();
+        assert_eq!(borrowed, expected);
+        assert!(RadixTrie::<usize>::new().to_sorted_vec().is_empty());
+    }
+
+    #[test]
+    fn test_start_with_suffixes() {
+        let trie = words_trie();
+        let res = trie.start_with_suffixes("Wond");
+        let expected: Vec<(String, &usize)> = vec![("er".into(), &6), ("erful".into(), &9)];
+        assert_eq!(res, expected);
+        let res = trie.start_with_suffixes("Won");
+        let expected: Vec<(String, &usize)> =
+            vec![("".into(), &3), ("der".into(), &6), ("derful".into(), &9)];
+        assert_eq!(res, expected);
+        assert!(trie.start_with_suffixes("Wa").is_empty());
+    }
+
+    #[test]
+    fn test_longest_prefix() {
+        let mut trie = RadixTrie::<usize>::new();
+        let words = ["Won", "Wonder", "Wonderful", "World"];
+        for word in &words {
+            trie.insert(word, word.len());
+        }
+        assert_eq!(trie.longest_prefix("Wonderfully"), Some(("Wonderful", &9)));
+        assert_eq!(trie.longest_prefix("Wonders"), Some(("Wonder", &6)));
+        assert_eq!(trie.longest_prefix("Wonde"), Some(("Won", &3)));
+        assert_eq!(trie.longest_prefix("World"), Some(("World", &5)));
+        assert_eq!(trie.longest_prefix("Wo"), None);
+        assert_eq!(trie.longest_prefix(""), None);
+    }
+
+    #[test]
+    fn test_random_insert_remove_keeps_invariants() {
+        let mut rng = Lcg(42);
+        let mut trie = RadixTrie::<u64>::new();
+        let mut model = BTreeMap::new();
+        for round in 0..3000 {
+            let len = 1 + rng.next(6) as usize;
+            let key = (0..len)
+                .map(|_| ['a', 'b', '多'][rng.next(3) as usize])
+                .collect::<String>();
+            if rng.next(3) == 0 {
+                assert_eq!(trie.remove(&key), model.remove(&key));
+            } else {
+                trie.insert(&key, round);
+                model.insert(key, round);
+            }
+            assert_invariants(&trie.entry);
+            assert_eq!(trie.len(), model.len());
+        }
+        let entries = trie
+            .iter()
+            .map(|(key, value)| (key, *value))
+            .collect::<Vec<_>>();
+        assert_eq!(entries, model.into_iter().collect::<Vec<_>>());
+        let keys = entries
+            .iter()
+            .map(|(key, _)| key.as_str())
+            .collect::<Vec<_>>();
+        for key in keys {
+            trie.remove(key);
+            assert_invariants(&trie.entry);
+        }
+        assert!(trie.entry.children().is_empty());
+    }
+
+    #[test]
+    fn test_remove_prefix() {
+        let mut trie = words_trie();
+        let removed = trie.remove_prefix("Wonde");
+        let expected: Vec<(String, usize)> = vec![("Wonder".into(), 6), ("Wonderful".into(), 9)];
+        assert_eq!(removed, expected);
+        assert_invariants(&trie.entry);
+        assert!(trie.remove_prefix("Wonders").is_empty());
+        let removed = trie.remove_prefix("Wor");
+        assert_eq!(removed, vec![(String::from("World"), 5)]);
+        assert_invariants(&trie.entry);
+        assert_eq!(trie.entry.children()[1].label(), "Won");
+        assert_eq!(trie.remove_prefix("A").len(), 1);
+        assert_eq!(trie.iter().count(), 1);
+    }
+
+    #[test]
+    fn test_multi_byte_labels_split_on_char_boundaries() {
+        let words = [
+            "多倫多",
+            "多伦多",
+            "多",
+            "👨\u{200d}👩\u{200d}👧",
+            "👨\u{200d}👩\u{200d}👦",
+            "👨",
+            "cafe",
+            "cafe\u{301}",
+            "cafe\u{300}s",
+            "caf\u{e9}",
+        ];
+        let mut trie = RadixTrie::<usize>::new();
+        for (index, word) in words.iter().enumerate() {
+            trie.insert(word, index);
+            assert_invariants(&trie.entry);
+        }
+        for (index, word) in words.iter().enumerate() {
+            assert_eq!(trie.find(word), Some(&index));
+        }
+        assert_eq!(trie.find("👨\u{200d}"), None);
+        assert_eq!(trie.find("cafe\u{300}"), None);
+        assert_eq!(trie.start_with("cafe").len(), 3);
+        assert_eq!(trie.start_with("👨\u{200d}👩").len(), 2);
+        for word in &words {
+            assert!(trie.remove(word).is_some());
+            assert_invariants(&trie.entry);
+        }
+        assert!(trie.entry.children().is_empty());
+    }
+
+    #[test]
+    fn test_random_multi_byte_keys() {
+        let mut rng = Lcg(7);
+        let mut trie = RadixTrie::<u64>::new();
+        let mut model = BTreeMap::new();
+        // one to four byte chars, including a combining accent and a joiner
+        let alphabet = ['e', '\u{301}', '\u{e9}', '多', '\u{200d}', '👩'];
+        for round in 0..3000 {
+            let len = 1 + rng.next(5) as usize;
+            let key = (0..len)
+                .map(|_| alphabet[rng.next(alphabet.len() as u64) as usize])
+                .collect::<String>();
+            if rng.next(3) == 0 {
+                assert_eq!(trie.remove(&key), model.remove(&key));
+            } else {
+                trie.insert(&key, round);
+                model.insert(key, round);
+            }
+        }
+        assert_invariants(&trie.entry);
+        let entries = trie
+            .iter()
+            .map(|(key, value)| (key, *value))
+            .collect::<Vec<_>>();
+        assert_eq!(entries, model.into_iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_len_tracks_every_mutation() {
+        let mut trie = RadixTrie::<usize>::new();
+        assert!(trie.is_empty());
+        for word in &["Won", "Wonder", "Wonderful", "World", "Axes", "Ax"] {
+            trie.insert(word, word.len());
+        }
+        trie.insert("Won", 0);
+        trie.insert("", 0);
+        assert_eq!(trie.len(), 6);
+        *trie.entry("Wo").or_default() += 1;
+        *trie.entry("Won").or_default() += 1;
+        trie.get_or_default("Axes");
+        assert_eq!(trie.len(), 7);
+        trie.remove("Wo");
+        trie.remove("Wo");
+        assert_eq!(trie.len(), 6);
+        trie.remove_many(vec!["Ax", "Nope", "Wonderful"]);
+        assert_eq!(trie.len(), 4);
+        trie.remove_prefix("Won");
+        assert_eq!(trie.len(), 2);
+        assert_eq!(trie.len(), trie.iter().count());
+        assert_eq!(trie.node_count(), 2);
+        let built = vec![("b", 1), ("a", 2), ("b", 3)]
+            .into_iter()
+            .collect::<RadixTrie<_>>();
+        assert_eq!(built.len(), 2);
+    }
+
+    #[test]
+    fn test_counts_track_every_mutation() {
+        let mut lcg = Lcg(11);
+        let mut trie = RadixTrie::<u64>::new();
+        let key = |lcg: &mut Lcg| {
+            let len = 1 + lcg.next(6) as usize;
+            (0..len)
+                .map(|_| ['a', 'b', '多'][lcg.next(3) as usize])
+                .collect::<String>()
+        };
+        for round in 0..300 {
+            let label = key(&mut lcg);
+            match lcg.next(8) {
+                0 => drop(trie.remove(&label)),
+                1 => drop(trie.remove_many(vec![label.as_str(), "a", "ab"])),
+                2 => drop(trie.remove_prefix_if(&label, |_, value| value % 2 == 0)),
+                3 => drop(trie.split_off(&label)),
+                4 => {
+                    let mut other = RadixTrie::new();
+                    for _ in 0..lcg.next(10) {
+                        other.insert(key(&mut lcg), round);
+                    }
+                    trie.merge(other, |mine, _| mine);
+                }
+                _ => drop(trie.insert(&label, round)),
+            }
+            assert_eq!(trie.validate(), Ok(()), "round {}", round);
+        }
+        for n in 0..trie.len() {
+            let (label, _) = trie.nth(n).unwrap();
+            assert_eq!(trie.rank(&label), n);
+        }
+    }
+
+    #[test]
+    fn test_clone_eq_hash() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+
+        let hash = |trie: &RadixTrie<usize>| {
+            let mut hasher = DefaultHasher::new();
+            trie.hash(&mut hasher);
+            hasher.finish()
+        };
+        let mut trie = RadixTrie::<usize>::new();
+        for word in &["Won", "Wonder", "World"] {
+            trie.insert(word, word.len());
+        }
+        let cloned = trie.clone();
+        assert_eq!(cloned, trie);
+        assert_eq!(hash(&cloned), hash(&trie));
+        // the same content reached through different operations
+        let mut other = RadixTrie::<usize>::new();
+        for word in &["World", "Wonderful", "Wonder", "Won"] {
+            other.insert(word, word.len());
+        }
+        assert_ne!(other, trie);
+        other.remove("Wonderful");
+        assert_eq!(other, trie);
+        assert_eq!(hash(&other), hash(&trie));
+        other.insert("Won", 0);
+        assert_ne!(other, trie);
+        assert_eq!(
+            format!("{:?}", trie),
+            r#"{"Won": 3, "Wonder": 6, "World": 5}"#
+        );
+    }
+
+    #[test]
+    fn test_index() {
+        let mut trie = RadixTrie::<usize>::new();
+        for word in "the cat and the hat".split(' ') {
+            trie.get_or_default(word);
+            trie[word] += 1;
+        }
+        assert_eq!(trie["the"], 2);
+        assert_eq!(trie["hat"], 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Label \"th\" does not exist in the trie")]
+    fn test_index_missing_label() {
+        let mut trie = RadixTrie::<usize>::new();
+        trie.insert("the", 1);
+        trie["th"] += 1;
+    }
+
+    #[test]
+    fn test_count_prefix() {
+        let mut trie = RadixTrie::<usize>::new();
+        let words = ["Won", "Wonder", "Wonderful", "World", "Axes", "多倫多"];
+        for word in &words {
+            trie.insert(word, word.len());
+        }
+        for prefix in &[
+            "W",
+            "Wo",
+            "Won",
+            "Wonde",
+            "Wonderful",
+            "Wonderfully",
+            "A",
+            "多",
+            "B",
+            "",
+        ] {
+            assert_eq!(
+                trie.count_prefix(prefix),
+                trie.start_with(prefix).len(),
+                "{}",
+                prefix
+            );
+        }
+        trie.remove("Wonder");
+        trie.remove_prefix("Wonderful");
+        trie.insert("Wont", 4);
+        assert_eq!(trie.count_prefix("Won"), 2);
+        assert_eq!(trie.count_prefix("W"), 3);
+        assert_eq!(trie.count_prefix(""), trie.len());
+    }
+
+    #[test]
+    fn test_insert_or_get_mut() {
+        let mut trie = RadixTrie::<usize>::new();
+        for word in &["Wonder", "Won", "World", "Wonder", "Wo", "Wonderful", "Won"] {
+            *trie.insert_or_get_mut(word, 0) += 1;
+            assert_invariants(&trie.entry);
+        }
+        assert_eq!(trie.find("Won"), Some(&2));
+        assert_eq!(trie.find("Wonder"), Some(&2));
+        assert_eq!(trie.find("Wo"), Some(&1));
+        assert_eq!(trie.len(), 5);
+    }
+
+    #[test]
+    fn test_common_prefixes() {
+        let mut trie = RadixTrie::<usize>::new();
+        for word in &["W", "Won", "Wonder", "Wonderful", "World", "多", "多倫多"] {
+            trie.insert(word, word.len());
+        }
+        let labels = |query| {
+            trie.common_prefixes(query)
+                .into_iter()
+                .map(|(label, _)| label)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(labels("Wonderland"), vec!["W", "Won", "Wonder"]);
+        assert_eq!(labels("Wonderful"), vec!["W", "Won", "Wonder", "Wonderful"]);
+        assert_eq!(labels("Wor"), vec!["W"]);
+        assert_eq!(labels("多倫多市"), vec!["多", "多倫多"]);
+        assert!(labels("").is_empty() && labels("Axes").is_empty());
+        assert_eq!(
+            trie.common_prefixes("Wonderland").last(),
+            trie.longest_prefix("Wonderland").as_ref()
+        );
+    }
+
+    #[test]
+    fn test_map_primitives() {
+        let mut trie = RadixTrie::<String>::new();
+        for word in &["Won", "Wonder", "World", "多倫多"] {
+            trie.insert(word, word.to_string());
+        }
+        for query in &[
+            "Won",
+            "Wonder",
+            "World",
+            "多倫多",
+            "Wo",
+            "Wonderful",
+            "多",
+            "",
+        ] {
+            let expected = trie.find(query).is_some();
+            assert_eq!(trie.contains_key(query), expected, "{}", query);
+            assert_eq!(
+                trie.get_key_value(query),
+                trie.find(query).map(|value| (*query, value))
+            );
+        }
+        assert_eq!(trie.take("Wonder"), Some(String::from("Wonder")));
+        assert_eq!(trie.take("Wonder"), None);
+        assert!(!trie.contains_key("Wonder"));
+        assert!(trie.contains_key("Won"));
+        assert_eq!(trie.len(), 3);
+    }
+
+    #[test]
+    fn test_remove_if() {
+        let mut trie = RadixTrie::<usize>::new();
+        for word in &["Won", "Wonder", "Wonderful", "World"] {
+            trie.insert(word, word.len());
+        }
+        assert_eq!(trie.remove_if("Wonder", |len| *len > 6), None);
+        assert_eq!(trie.remove_if("Wond", |_| true), None);
+        assert_eq!(trie.remove_if("Wo", |_| true), None);
+        assert_eq!(trie.len(), 4);
+        assert_eq!(trie.remove_if("Wonder", |len| *len == 6), Some(6));
+        assert_eq!(trie.remove_if("Won", |_| true), Some(3));
+        assert_eq!(trie.len(), 2);
+        let mut expected = RadixTrie::<usize>::new();
+        expected.insert("Wonderful", 9);
+        expected.insert("World", 5);
+        assert_eq!(format!("{:?}", trie.entry), format!("{:?}", expected.entry));
+    }
+
+    #[test]
+    fn test_find_many() {
+        let mut trie = RadixTrie::<usize>::new();
+        let words = [
+            "Won",
+            "Wonder",
+            "Wonderful",
+            "World",
+            "Axes",
+            "多倫多",
+            "多",
+        ];
+        for word in &words {
+            trie.insert(word, word.len());
+        }
+        let queries = [
+            "Wonderful",
+            "Wo",
+            "",
+            "多倫多",
+            "Wonder",
+            "Wonders",
+            "Won",
+            "Wonder",
+            "多倫",
+            "World",
+            "Axe",
+            "多",
+            "Worlds",
+        ];
+        let expected = queries
+            .iter()
+            .map(|query| trie.find(query))
+            .collect::<Vec<_>>();
+        assert_eq!(trie.find_many(queries.iter().copied()), expected);
+        assert_eq!(trie.find_many(vec![]), Vec::<Option<&usize>>::new());
+    }
+
+    #[test]
+    fn test_string_keys() {
+        let mut trie = RadixTrie::<usize>::new();
+        let owned = String::from("Wonder");
+        trie.insert(owned.clone(), 1);
+        trie.insert(&owned[..3], 2);
+        assert_eq!(trie.get(&owned), Some(&1));
+        assert_eq!(trie.find(String::from("Won")), Some(&2));
+        *trie.get_mut(&*owned).unwrap() += 1;
+        *trie.entry(&owned).or_insert(0) += 1;
+        assert_eq!(trie.get_copied(owned.as_str()), Some(3));
+        assert!(trie.contains_key(&owned));
+        assert_eq!(trie.remove(owned), Some(3));
+        assert_eq!(trie.take(String::from("Won")), Some(2));
+        assert!(trie.is_empty());
+    }
+
+    #[test]
+    fn test_wide_node_lookup() {
+        // the root gets one child per first char, inserted out of order
+        let chars = (0..2000u32)
+            .rev()
+            .filter_map(|code| core::char::from_u32(0x4e00 + code * 7 % 2000))
+            .collect::<Vec<_>>();
+        let mut trie = RadixTrie::<u32>::new();
+        for char in &chars {
+            trie.insert(format!("{}x", char), *char as u32);
+        }
+        assert_eq!(trie.entry.children().len(), chars.len());
+        assert_invariants(&trie.entry);
+        for char in &chars {
+            assert_eq!(trie.find(format!("{}x", char)), Some(&(*char as u32)));
+            assert_eq!(trie.find(char.to_string()), None);
+        }
+        assert_eq!(trie.find("ax"), None);
+        assert_eq!(trie.find("\u{10ffff}x"), None);
+    }
+
+    #[test]
+    fn test_split_off() {
+        let words = [
+            "Won",
+            "Wonder",
+            "Wonderful",
+            "World",
+            "Axes",
+            "多倫多",
+            "多",
+        ];
+        for prefix in &[
+            "W",
+            "Wo",
+            "Won",
+            "Wonde",
+            "Wonderful",
+            "多倫",
+            "Axes",
+            "B",
+            "",
+        ] {
+            let mut trie = RadixTrie::<usize>::new();
+            for word in &words {
+                trie.insert(word, word.len());
+            }
+            let split = trie.split_off(prefix);
+            for word in &words {
+                let (moved, kept) = match word.starts_with(prefix) {
+                    true => (Some(&word.len()), None),
+                    false => (None, Some(&word.len())),
+                };
+                assert_eq!(split.find(word), moved, "{} {}", prefix, word);
+                assert_eq!(trie.find(word), kept, "{} {}", prefix, word);
+            }
+            assert_eq!(split.len() + trie.len(), words.len());
+            assert_eq!(split.len(), split.iter().count());
+            assert_eq!(trie.len(), trie.iter().count());
+            assert_invariants(&split.entry);
+            assert_invariants(&trie.entry);
+        }
+    }
+
+    #[test]
+    fn test_random_long_runs() {
+        // long runs of one char with a rare other one make deep chains of single char splits
+        let mut rng = Lcg(11);
+        let mut trie = RadixTrie::<u64>::new();
+        let mut model = BTreeMap::new();
+        for round in 0..500 {
+            let len = 1 + rng.next(2000) as usize;
+            let mut key = "a".repeat(len);
+            if rng.next(4) == 0 {
+                key.insert(rng.next(len as u64) as usize, 'b');
+            }
+            if rng.next(4) == 0 {
+                let batch = vec![key.as_str(), &key[..len / 2]];
+                let expected = vec![model.remove(&key), model.remove(&key[..len / 2])];
+                assert_eq!(trie.remove_many(batch), expected);
+            } else {
+                assert_eq!(trie.insert(&key, round), model.insert(key, round));
+            }
+            assert_eq!(trie.len(), model.len());
+        }
+        assert_invariants(&trie.entry);
+        let entries = trie.clone().into_iter().collect::<Vec<_>>();
+        assert_eq!(entries, model.into_iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_deep_chain_without_recursion() {
+        // "a", "aa", "aaa", ... as one element per char, built bottom up
+        const DEPTH: usize = 100_000;
+        let mut element = Element::pack(String::from("a"), Some(DEPTH), vec![]);
+        for depth in (1..DEPTH).rev() {
+            element = Element::pack(String::from("a"), Some(depth), vec![element]);
+        }
+        let mut trie = RadixTrie::<usize>::new();
+        trie.entry.children_mut().push(element);
+        trie.len = DEPTH;
+        let deepest = "a".repeat(DEPTH);
+        assert_eq!(trie.find(&deepest), Some(&DEPTH));
+        assert_eq!(trie.values().count(), DEPTH);
+        assert_eq!(trie.node_count(), DEPTH);
+        assert_eq!(trie.insert(format!("{}b", deepest), 0), None);
+        assert_eq!(trie.insert(&deepest[..DEPTH / 2], 1), Some(DEPTH / 2));
+        let removed = trie.remove_many(vec![&deepest[..DEPTH - 1], &deepest[..DEPTH / 3]]);
+        assert_eq!(removed, vec![Some(DEPTH - 1), Some(DEPTH / 3)]);
+        assert_eq!(trie.remove(&deepest), Some(DEPTH));
+        assert_eq!(trie.len(), DEPTH - 2);
+        let cloned = trie.clone();
+        assert_eq!(cloned.find(format!("{}b", deepest)), Some(&0));
+        // the stored counts keep positional lookups linear in the depth
+        assert_eq!(cloned.nth(DEPTH - 3).map(|(_, value)| *value), Some(0));
+        assert_eq!(cloned.rank(&deepest), DEPTH - 3);
+        assert_eq!(cloned.sample(|n| n - 1).map(|(_, value)| *value), Some(0));
+        let sampled = cloned.sample_prefix(&deepest[..DEPTH - 2], |n| n - 1);
+        assert_eq!(sampled.map(|(label, _)| label.len()), Some(DEPTH + 1));
+        let mut visited = 0;
+        trie.retain(|label, _| {
+            visited += 1;
+            label.len() % 2 == 0
+        });
+        assert_eq!(visited, DEPTH - 2);
+        assert_eq!(trie.find(&deepest[..DEPTH / 2]), Some(&1));
+        let doubled = cloned.map(|_, value| value * 2);
+        assert_eq!(doubled.find(&deepest[..10]), Some(&20));
+        let mut iter = doubled.into_iter();
+        assert_eq!(iter.next(), Some((String::from("a"), 2)));
+        // dropping the rest of the chain must not recurse either
+        drop(iter);
+        drop(trie);
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut trie = RadixTrie::<usize>::new();
+        for (index, word) in ["Won", "Wonder", "World", "Axes", "多倫多"]
+            .iter()
+            .enumerate()
+        {
+            trie.insert(word, index);
+        }
+        let capacity = trie.entry.children().capacity();
+        trie.clear();
+        assert!(trie.is_empty());
+        assert_eq!(trie.node_count(), 0);
+        assert_eq!(trie.entry.children().capacity(), capacity);
+        assert_eq!(trie.find("Won"), None);
+        *trie.get_or_default("Won") += 1;
+        assert_eq!(trie.to_sorted_vec(), vec![(String::from("Won"), 1)]);
+        assert_invariants(&trie.entry);
+    }
 }
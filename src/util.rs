@@ -1,4 +1,5 @@
 use crate::element::Element;
+use alloc::string::String;
 
 pub fn binary_search<T>(target: char, array: &[Element<T>]) -> usize {
     let mut first = 0;
@@ -21,15 +22,10 @@ pub fn longest_shared_prefix<'a>(s1: &'a str, s2: &'a str) -> &'a str {
             return &s1[..index1];
         }
     }
-    return if s1.len() > s2.len() { s2 } else { s1 };
-}
-
-/// A helper function to create an value element
-pub fn value_element<T, S: ToString>(label: S, value: T, children: Vec<Element<T>>) -> Element<T> {
-    Element::Value {
-        label: label.to_string(),
-        value,
-        children,
+    if s1.len() > s2.len() {
+        s2
+    } else {
+        s1
     }
 }
 
@@ -40,6 +36,25 @@ pub fn first_char<S: AsRef<str>>(s: S) -> char {
         .expect("First char called on empty string")
 }
 
+/// Quote and escape the string as a JSON string literal
+pub fn json_string(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for char in s.chars() {
+        match char {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            char if (char as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", char as u32)),
+            char => quoted.push(char),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
 #[cfg(test)]
 mod util_tests {
     use crate::util;
@@ -51,4 +66,17 @@ mod util_tests {
         let prefix = util::longest_shared_prefix(s1, s2);
         assert_eq!(prefix, "Toronto多");
     }
+
+    #[test]
+    fn longest_shared_prefix_multi_byte_test() {
+        // the family emojis only differ in their last code point
+        let prefix =
+            util::longest_shared_prefix("👨\u{200d}👩\u{200d}👧", "👨\u{200d}👩\u{200d}👦");
+        assert_eq!(prefix, "👨\u{200d}👩\u{200d}");
+        // the same base letter with different combining accents
+        let prefix = util::longest_shared_prefix("cafe\u{301}", "cafe\u{300}");
+        assert_eq!(prefix, "cafe");
+        assert_eq!(util::longest_shared_prefix("多倫多", "多"), "多");
+        assert_eq!(util::longest_shared_prefix("倫", "伦"), "");
+    }
 }
@@ -0,0 +1,276 @@
+use crate::element::Element;
+use crate::trie::RadixTrie;
+use crate::util;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// A broken structural invariant found by [`RadixTrie::validate`].
+/// Elements are identified by the joined labels from the root to them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvariantViolation {
+    /// The root has a label, or an element below the root is marked as the root
+    MisplacedRoot { label: String },
+    /// An element below the root has an empty label
+    EmptyLabel { parent: String },
+    /// Two children of the element are not in ascending order of their first chars
+    UnsortedChildren { parent: String },
+    /// Two children of the element start with the same char
+    SharedFirstChar { parent: String, char: char },
+    /// An element without value has fewer than two children
+    SparseNode { label: String, children: usize },
+    /// The stored number of values differs from the number of values in the elements
+    LenMismatch { stored: usize, counted: usize },
+    /// The number of values stored on the element differs from the number held below it
+    CountMismatch {
+        label: String,
+        stored: usize,
+        counted: usize,
+    },
+}
+
+impl fmt::Display for InvariantViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InvariantViolation::MisplacedRoot { label } => {
+                write!(f, "root element found at {:?}", label)
+            }
+            InvariantViolation::EmptyLabel { parent } => {
+                write!(f, "empty label below {:?}", parent)
+            }
+            InvariantViolation::UnsortedChildren { parent } => {
+                write!(f, "children of {:?} are not sorted", parent)
+            }
+            InvariantViolation::SharedFirstChar { parent, char } => {
+                write!(
+                    f,
+                    "children of {:?} share the first char {:?}",
+                    parent, char
+                )
+            }
+            InvariantViolation::SparseNode { label, children } => {
+                write!(f, "{:?} has no value and {} children", label, children)
+            }
+            InvariantViolation::LenMismatch { stored, counted } => {
+                write!(f, "len is {} but {} values are stored", stored, counted)
+            }
+            InvariantViolation::CountMismatch {
+                label,
+                stored,
+                counted,
+            } => write!(
+                f,
+                "{:?} counts {} values but holds {}",
+                label, stored, counted
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvariantViolation {}
+
+impl<T> RadixTrie<T> {
+    /// Checks the structural invariants of the trie and returns the first one broken, in pre-order.
+    /// A trie only changed through its public API always passes,
+    /// so this is meant for debug assertions, fuzzing, and checks after recovering stored data
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("lab", 3);
+    /// trie.insert("label", 5);
+    /// assert_eq!(trie.validate(), Ok(()));
+    /// ```
+    pub fn validate(&self) -> Result<(), InvariantViolation> {
+        if !self.entry.label().is_empty() || !matches!(self.entry, Element::Base { .. }) {
+            return Err(InvariantViolation::MisplacedRoot {
+                label: self.entry.label().into(),
+            });
+        }
+        check_children(self.entry.children(), "")?;
+        let mut counted = 0;
+        let mut label = String::new();
+        // elements to check with the length of their parent label
+        let mut stack = self
+            .entry
+            .children()
+            .iter()
+            .rev()
+            .map(|child| (0, child))
+            .collect::<Vec<_>>();
+        while let Some((parent_len, element)) = stack.pop() {
+            label.truncate(parent_len);
+            validate_element(element, &label)?;
+            label.push_str(element.label());
+            counted += element.value().is_some() as usize;
+            check_children(element.children(), &label)?;
+            let label_len = label.len();
+            let children = element.children().iter().rev();
+            stack.extend(children.map(|child| (label_len, child)));
+        }
+        if counted != self.len {
+            return Err(InvariantViolation::LenMismatch {
+                stored: self.len,
+                counted,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Checks that the children are sorted by distinct first chars.
+/// Empty labels are left to `validate_element`
+fn check_children<T>(children: &[Element<T>], parent: &str) -> Result<(), InvariantViolation> {
+    for pair in children.windows(2) {
+        let (first, second) = (pair[0].label(), pair[1].label());
+        if first.is_empty() || second.is_empty() {
+            continue;
+        }
+        let (first, second) = (util::first_char(first), util::first_char(second));
+        if first == second {
+            return Err(InvariantViolation::SharedFirstChar {
+                parent: parent.into(),
+                char: first,
+            });
+        }
+        if first > second {
+            return Err(InvariantViolation::UnsortedChildren {
+                parent: parent.into(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Checks an element below the root, given the joined labels of its ancestors
+fn validate_element<T>(element: &Element<T>, parent: &str) -> Result<(), InvariantViolation> {
+    let label = || {
+        let mut label = String::from(parent);
+        label.push_str(element.label());
+        label
+    };
+    match element {
+        Element::Base { .. } => Err(InvariantViolation::MisplacedRoot { label: label() }),
+        _ if element.label().is_empty() => Err(InvariantViolation::EmptyLabel {
+            parent: parent.into(),
+        }),
+        Element::Node { children, .. } if children.len() < 2 => {
+            Err(InvariantViolation::SparseNode {
+                label: label(),
+                children: children.len(),
+            })
+        }
+        _ if element.value_count() != element.counted_values() => {
+            Err(InvariantViolation::CountMismatch {
+                label: label(),
+                stored: element.value_count(),
+                counted: element.counted_values(),
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod validate_tests {
+    use crate::element::Element;
+    use crate::test_util::MIXED_WORDS;
+    use crate::trie::RadixTrie;
+    use crate::validate::InvariantViolation;
+
+    fn leaf(label: &str) -> Element<usize> {
+        Element::pack(label.into(), Some(0), vec![])
+    }
+
+    fn trie_with(children: Vec<Element<usize>>, len: usize) -> RadixTrie<usize> {
+        let mut trie = RadixTrie::new();
+        *trie.entry.children_mut() = children;
+        trie.len = len;
+        trie
+    }
+
+    #[test]
+    fn test_valid_tries() {
+        let mut trie = RadixTrie::<usize>::new();
+        assert_eq!(trie.validate(), Ok(()));
+        for word in &MIXED_WORDS {
+            trie.insert(word, word.len());
+            assert_eq!(trie.validate(), Ok(()));
+        }
+        trie.remove("Won");
+        trie.remove("多");
+        assert_eq!(trie.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_violations() {
+        let node = |label: &str, children| Element::pack(label.into(), None, children);
+        let cases = vec![
+            (
+                trie_with(vec![leaf("b"), leaf("a")], 2),
+                InvariantViolation::UnsortedChildren { parent: "".into() },
+            ),
+            (
+                trie_with(vec![node("x", vec![leaf("ab"), leaf("ac")])], 2),
+                InvariantViolation::SharedFirstChar {
+                    parent: "x".into(),
+                    char: 'a',
+                },
+            ),
+            (
+                trie_with(vec![node("x", vec![leaf("a")])], 1),
+                InvariantViolation::SparseNode {
+                    label: "x".into(),
+                    children: 1,
+                },
+            ),
+            (
+                trie_with(vec![node("x", vec![leaf(""), leaf("a")])], 2),
+                InvariantViolation::EmptyLabel { parent: "x".into() },
+            ),
+            (
+                trie_with(vec![leaf("a"), leaf("b")], 3),
+                InvariantViolation::LenMismatch {
+                    stored: 3,
+                    counted: 2,
+                },
+            ),
+            (
+                trie_with(
+                    vec![Element::Value {
+                        label: "a".into(),
+                        value: 0,
+                        children: vec![leaf("b")],
+                        count: 1,
+                    }],
+                    2,
+                ),
+                InvariantViolation::CountMismatch {
+                    label: "a".into(),
+                    stored: 1,
+                    counted: 2,
+                },
+            ),
+            (
+                trie_with(
+                    vec![Element::Base {
+                        label: "a".into(),
+                        children: vec![],
+                    }],
+                    0,
+                ),
+                InvariantViolation::MisplacedRoot { label: "a".into() },
+            ),
+        ];
+        for (trie, expected) in cases {
+            assert_eq!(trie.validate(), Err(expected));
+        }
+        let error = InvariantViolation::SparseNode {
+            label: "x".into(),
+            children: 1,
+        };
+        assert_eq!(error.to_string(), "\"x\" has no value and 1 children");
+    }
+}
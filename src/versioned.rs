@@ -0,0 +1,203 @@
+use crate::persistent::{PersistentIter, PersistentRadixTrie};
+use alloc::collections::VecDeque;
+
+/// A history of versions of a persistent trie, numbered from 0 for the empty trie.
+///
+/// Every change commits a new version sharing the unchanged subtrees with the previous one,
+/// so old versions stay readable without copies, such as the value a key resolved to
+/// at a past deployment.
+///
+/// # Example
+/// ```rust
+/// use another_radix_trie::VersionedRadixTrie;
+///
+/// let mut config = VersionedRadixTrie::new();
+/// let first = config.insert("app/timeout", 30);
+/// let second = config.insert("app/timeout", 5);
+/// assert_eq!(config.get_at(first, "app/timeout"), Some(&30));
+/// assert_eq!(config.get_at(second, "app/timeout"), Some(&5));
+/// assert_eq!(config.get_at(0, "app/timeout"), None);
+/// ```
+pub struct VersionedRadixTrie<T> {
+    /// The versions kept, the oldest first
+    versions: VecDeque<PersistentRadixTrie<T>>,
+    /// The number of the oldest version kept
+    oldest: usize,
+}
+
+impl<T> Default for VersionedRadixTrie<T> {
+    fn default() -> Self {
+        VersionedRadixTrie::new()
+    }
+}
+
+impl<T> VersionedRadixTrie<T> {
+    /// Construct a history holding only version 0, the empty trie
+    pub fn new() -> Self {
+        VersionedRadixTrie {
+            versions: vec![PersistentRadixTrie::new()].into(),
+            oldest: 0,
+        }
+    }
+
+    /// Returns the number of the latest version
+    pub fn latest_version(&self) -> usize {
+        self.oldest + self.versions.len() - 1
+    }
+
+    /// Returns the number of the oldest version kept
+    pub fn oldest_version(&self) -> usize {
+        self.oldest
+    }
+
+    /// Returns the latest version of the trie
+    pub fn latest(&self) -> &PersistentRadixTrie<T> {
+        self.versions.back().unwrap()
+    }
+
+    /// Returns the given version of the trie,
+    /// or None if it is not committed yet or no longer kept
+    pub fn at(&self, version: usize) -> Option<&PersistentRadixTrie<T>> {
+        self.versions.get(version.checked_sub(self.oldest)?)
+    }
+
+    /// Returns the borrowed value of the label in the given version.
+    /// If the label does not exist in that version, or the version is not kept, return None
+    pub fn get_at(&self, version: usize, label: &str) -> Option<&T> {
+        self.at(version)?.find(label)
+    }
+
+    /// Returns a lazy iterator over the labels and values of the given version
+    /// in lexicographic order, or None if the version is not kept
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::VersionedRadixTrie;
+    ///
+    /// let mut trie = VersionedRadixTrie::new();
+    /// let first = trie.insert("lab", 3);
+    /// trie.insert("label", 5);
+    /// let labels = trie.iter_at(first).unwrap().map(|(label, _)| label);
+    /// assert_eq!(labels.collect::<Vec<_>>(), vec!["lab"]);
+    /// assert!(trie.iter_at(7).is_none());
+    /// ```
+    pub fn iter_at(&self, version: usize) -> Option<PersistentIter<'_, T>> {
+        Some(self.at(version)?.iter())
+    }
+
+    /// Drops the versions older than the given one, keeping the latest version in any case,
+    /// and returns the number of versions dropped.
+    /// The versions share their elements by reference counting, so an element is freed
+    /// once no kept version and no clone handed out before still uses it
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::VersionedRadixTrie;
+    ///
+    /// let mut trie = VersionedRadixTrie::new();
+    /// let first = trie.insert("lab", 3);
+    /// let second = trie.insert("label", 5);
+    /// assert_eq!(trie.retain_versions(second), 2);
+    /// assert_eq!(trie.get_at(first, "lab"), None);
+    /// assert_eq!(trie.get_at(second, "lab"), Some(&3));
+    /// ```
+    pub fn retain_versions(&mut self, after: usize) -> usize {
+        let dropped = after
+            .saturating_sub(self.oldest)
+            .min(self.versions.len() - 1);
+        self.versions.drain(..dropped);
+        self.oldest += dropped;
+        dropped
+    }
+
+    /// Commits the trie, usually derived from [`VersionedRadixTrie::latest`] with several changes,
+    /// as the next version and returns its number
+    pub fn commit(&mut self, trie: PersistentRadixTrie<T>) -> usize {
+        self.versions.push_back(trie);
+        self.latest_version()
+    }
+}
+
+impl<T: Clone> VersionedRadixTrie<T> {
+    /// Commits a version with the value inserted under the label and returns its number
+    pub fn insert(&mut self, label: &str, value: T) -> usize {
+        let next = self.latest().insert(label, value);
+        self.commit(next)
+    }
+
+    /// Commits a version without the label and returns its number
+    pub fn remove(&mut self, label: &str) -> usize {
+        let next = self.latest().remove(label);
+        self.commit(next)
+    }
+}
+
+#[cfg(test)]
+mod versioned_tests {
+    use crate::test_util::WORDS;
+    use crate::versioned::VersionedRadixTrie;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_time_travel_reads() {
+        let mut trie = VersionedRadixTrie::new();
+        for word in &WORDS {
+            trie.insert(word, word.len());
+        }
+        let removed = trie.remove("Wonder");
+        let batch = trie.latest().insert("Wo", 2).insert("Won", 0);
+        assert_eq!(trie.commit(batch), removed + 1);
+        assert_eq!(trie.latest_version(), WORDS.len() + 2);
+        for version in 0..=WORDS.len() {
+            for (index, word) in WORDS.iter().enumerate() {
+                let expected = if index < version {
+                    Some(word.len())
+                } else {
+                    None
+                };
+                assert_eq!(trie.get_at(version, word).copied(), expected);
+            }
+            assert_eq!(trie.iter_at(version).unwrap().count(), version);
+        }
+        assert_eq!(trie.get_at(removed, "Wonder"), None);
+        assert_eq!(trie.get_at(removed, "Wonderful"), Some(&9));
+        let latest = trie.iter_at(trie.latest_version()).unwrap();
+        let latest = latest.map(|(label, value)| (label, *value));
+        assert_eq!(
+            latest.collect::<Vec<_>>(),
+            vec![
+                (String::from("Axes"), 4),
+                (String::from("Wo"), 2),
+                (String::from("Won"), 0),
+                (String::from("Wonderful"), 9),
+                (String::from("World"), 5),
+            ]
+        );
+        assert!(trie.at(trie.latest_version() + 1).is_none());
+        assert_eq!(trie.get_at(100, "Won"), None);
+    }
+
+    #[test]
+    fn test_retain_versions() {
+        let mut trie = VersionedRadixTrie::new();
+        let values = (0..5).map(Rc::new).collect::<Vec<_>>();
+        for value in &values {
+            trie.insert("Won", Rc::clone(value));
+        }
+        trie.insert("Wonder", Rc::clone(&values[0]));
+        assert_eq!(trie.retain_versions(0), 0);
+        assert_eq!(trie.retain_versions(3), 3);
+        assert_eq!((trie.oldest_version(), trie.latest_version()), (3, 6));
+        assert!(trie.at(2).is_none());
+        assert_eq!(trie.get_at(3, "Won").map(|value| **value), Some(2));
+        // the values only held by the dropped versions are freed
+        assert_eq!(Rc::strong_count(&values[0]), 2);
+        assert_eq!(Rc::strong_count(&values[1]), 1);
+        assert_eq!(Rc::strong_count(&values[2]), 2);
+        // going back is a no-op, and the latest version is always kept
+        assert_eq!(trie.retain_versions(1), 0);
+        assert_eq!(trie.retain_versions(100), 3);
+        assert_eq!(trie.oldest_version(), 6);
+        assert_eq!(trie.get_at(6, "Won").map(|value| **value), Some(4));
+        assert_eq!(trie.insert("Axes", Rc::clone(&values[1])), 7);
+        assert_eq!(trie.iter_at(7).unwrap().count(), 3);
+    }
+}
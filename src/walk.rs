@@ -0,0 +1,233 @@
+use crate::element::Element;
+use crate::trie::RadixTrie;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Decision returned by the visitor of [`RadixTrie::walk_mut`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Walk {
+    /// Keep walking, including the descendants of the current entry
+    Continue,
+    /// Skip the descendants of the current entry and continue with its siblings
+    SkipChildren,
+    /// Stop the walk immediately
+    Stop,
+}
+
+impl<T> RadixTrie<T> {
+    /// Visit every label and its mutable value in lexicographic order.
+    /// The visitor decides whether the walk goes deeper, skips the subtree, or stops
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::{RadixTrie, Walk};
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("/api", 0);
+    /// trie.insert("/api/users", 0);
+    /// trie.insert("/web", 0);
+    /// trie.walk_mut(|label, hits| {
+    ///     *hits += 1;
+    ///     if label == "/api" { Walk::SkipChildren } else { Walk::Continue }
+    /// });
+    /// assert_eq!(trie.find("/api"), Some(&1));
+    /// assert_eq!(trie.find("/api/users"), Some(&0));
+    /// assert_eq!(trie.find("/web"), Some(&1));
+    /// ```
+    pub fn walk_mut<F>(&mut self, mut visit: F)
+    where
+        F: FnMut(&str, &mut T) -> Walk,
+    {
+        let mut label = String::new();
+        // each element is paired with the length of its parent label
+        let mut stack: Vec<(usize, &mut Element<T>)> = self
+            .entry
+            .children_mut()
+            .iter_mut()
+            .rev()
+            .map(|child| (0, child))
+            .collect();
+        while let Some((prefix_len, element)) = stack.pop() {
+            label.truncate(prefix_len);
+            label.push_str(element.label());
+            let (value, children) = element.value_and_children_mut();
+            let decision = match value {
+                Some(value) => visit(&label, value),
+                None => Walk::Continue,
+            };
+            match decision {
+                Walk::Stop => return,
+                Walk::SkipChildren => continue,
+                Walk::Continue => {
+                    stack.extend(children.iter_mut().rev().map(|child| (label.len(), child)))
+                }
+            }
+        }
+    }
+
+    /// Visit every label starting with the given prefix in lexicographic order.
+    /// The label is rebuilt in the given buffer, so no key is allocated during the walk
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("lab", 3);
+    /// trie.insert("label", 5);
+    /// let mut buf = String::new();
+    /// let mut total = 0;
+    /// trie.for_each_key_with_buf("la", &mut buf, |label, value| total += label.len() * value);
+    /// assert_eq!(total, 34);
+    /// ```
+    pub fn for_each_key_with_buf<F>(&self, prefix: &str, buf: &mut String, visit: F)
+    where
+        F: FnMut(&str, &T),
+    {
+        buf.clear();
+        let element = match self.find_prefix_element(prefix, |label| buf.push_str(label)) {
+            Some(element) => element,
+            None => return,
+        };
+        element.for_each_value(buf, visit)
+    }
+
+    /// Visit every label starting with the given prefix in lexicographic order,
+    /// passing the label as the segments stored on the path from the root instead of joining them.
+    /// The segments concatenated give the label, and the first ones may cover more than the prefix.
+    /// The empty prefix visits every label
+    /// # Example
+    /// ```rust
+    /// use another_radix_trie::RadixTrie;
+    ///
+    /// let mut trie = RadixTrie::<usize>::new();
+    /// trie.insert("lab", 3);
+    /// trie.insert("label", 5);
+    /// let mut paths = vec![];
+    /// trie.for_each_prefix("la", |segments, value| paths.push((segments.to_vec(), *value)));
+    /// assert_eq!(paths, vec![(vec!["lab"], 3), (vec!["lab", "el"], 5)]);
+    /// ```
+    pub fn for_each_prefix<'a, F>(&'a self, prefix: &str, mut visit: F)
+    where
+        F: FnMut(&[&'a str], &'a T),
+    {
+        let mut segments = vec![];
+        // each element is paired with the number of segments of its ancestors
+        let mut stack: Vec<(usize, &Element<T>)> = if prefix.is_empty() {
+            self.entry
+                .children()
+                .iter()
+                .rev()
+                .map(|child| (0, child))
+                .collect()
+        } else {
+            let element = self.find_prefix_element(prefix, |label| segments.push(label));
+            element
+                .map(|element| (segments.len(), element))
+                .into_iter()
+                .collect()
+        };
+        while let Some((depth, element)) = stack.pop() {
+            segments.truncate(depth);
+            segments.push(element.label());
+            if let Some(value) = element.value() {
+                visit(&segments, value);
+            }
+            let children = element.children().iter().rev();
+            stack.extend(children.map(|child| (segments.len(), child)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod walk_tests {
+    use crate::test_util::{words_trie, WORDS};
+    use crate::trie::RadixTrie;
+    use crate::walk::Walk;
+
+    #[test]
+    fn test_walk_mut_order_and_stop() {
+        let mut trie = RadixTrie::<usize>::new();
+        for word in &WORDS {
+            trie.insert(word, 0);
+        }
+        let mut visited = vec![];
+        trie.walk_mut(|label, value| {
+            visited.push(label.to_owned());
+            *value += 1;
+            if label == "Wonder" {
+                Walk::Stop
+            } else {
+                Walk::Continue
+            }
+        });
+        assert_eq!(visited, vec!["Axes", "Won", "Wonder"]);
+        assert_eq!(trie.find("Wonder"), Some(&1));
+        assert_eq!(trie.find("Wonderful"), Some(&0));
+        assert_eq!(trie.find("World"), Some(&0));
+    }
+
+    #[test]
+    fn test_walk_mut_skip_children() {
+        let mut trie = RadixTrie::<usize>::new();
+        for word in &WORDS {
+            trie.insert(word, 0);
+        }
+        let mut visited = vec![];
+        trie.walk_mut(|label, _| {
+            visited.push(label.to_owned());
+            if label == "Won" {
+                Walk::SkipChildren
+            } else {
+                Walk::Continue
+            }
+        });
+        assert_eq!(visited, vec!["Axes", "Won", "World"]);
+    }
+
+    #[test]
+    fn test_for_each_key_with_buf() {
+        let trie = words_trie();
+        let mut buf = String::from("leftover");
+        let mut visited = vec![];
+        trie.for_each_key_with_buf("Wo", &mut buf, |label, value| {
+            assert_eq!(label.len(), *value);
+            visited.push(label.to_owned())
+        });
+        assert_eq!(visited, vec!["Won", "Wonder", "Wonderful", "World"]);
+        visited.clear();
+        trie.for_each_key_with_buf("Wonde", &mut buf, |label, _| visited.push(label.to_owned()));
+        assert_eq!(visited, vec!["Wonder", "Wonderful"]);
+        visited.clear();
+        trie.for_each_key_with_buf("Wa", &mut buf, |label, _| visited.push(label.to_owned()));
+        assert!(visited.is_empty());
+        trie.for_each_key_with_buf("", &mut buf, |label, _| visited.push(label.to_owned()));
+        assert_eq!(visited, trie.keys().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_for_each_prefix() {
+        let mut trie = RadixTrie::<usize>::new();
+        let words = ["Won", "Wonder", "Wonderful", "World", "Axes", "多倫多"];
+        for word in &words {
+            trie.insert(word, word.len());
+        }
+        for prefix in &["", "W", "Wo", "Wonde", "多", "Wa"] {
+            let mut visited = vec![];
+            trie.for_each_prefix(prefix, |segments, value| {
+                visited.push((segments.concat(), *value));
+            });
+            let expected = if prefix.is_empty() {
+                trie.iter().collect::<Vec<_>>()
+            } else {
+                trie.find_prefix_iter(prefix).collect::<Vec<_>>()
+            };
+            let expected = expected
+                .into_iter()
+                .map(|(label, value)| (label, *value))
+                .collect::<Vec<_>>();
+            assert_eq!(visited, expected, "{}", prefix);
+        }
+        let mut paths = vec![];
+        trie.for_each_prefix("Wonderf", |segments, _| paths.push(segments.to_vec()));
+        assert_eq!(paths, vec![vec!["Wo", "n", "der", "ful"]]);
+    }
+}
@@ -0,0 +1,74 @@
+use crate::trie::RadixTrie;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+macro_rules! impl_weak_values {
+    ( $module: ident, $strong: ident ) => {
+        impl<T> RadixTrie<alloc::$module::Weak<T>> {
+            /// Returns the value associated with related label if it is still alive,
+            /// while [`RadixTrie::get`] returns the weak reference itself.
+            /// If the label does not exist or the value was dropped, return None
+            pub fn get_strong(&self, label: &str) -> Option<alloc::$module::$strong<T>> {
+                self.find(label).and_then(alloc::$module::Weak::upgrade)
+            }
+
+            /// Removes all the entries whose values were dropped,
+            /// and returns the number of removed entries
+            pub fn prune_dead(&mut self) -> usize {
+                let dead = self
+                    .entry
+                    .collect_all_child_values()
+                    .into_iter()
+                    .filter(|(_, value)| value.strong_count() == 0)
+                    .map(|(label, _)| label)
+                    .collect::<Vec<_>>();
+                self.remove_many(dead.iter().map(String::as_str))
+                    .into_iter()
+                    .flatten()
+                    .count()
+            }
+        }
+    };
+}
+
+impl_weak_values!(rc, Rc);
+impl_weak_values!(sync, Arc);
+
+#[cfg(test)]
+mod weak_tests {
+    use crate::trie::RadixTrie;
+    use std::rc::{Rc, Weak};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_get_strong_and_prune_dead() {
+        let mut trie = RadixTrie::<Weak<usize>>::new();
+        let exe = Rc::new(3);
+        let execute = Rc::new(7);
+        let example = Rc::new(7);
+        trie.insert("exe", Rc::downgrade(&exe));
+        trie.insert("execute", Rc::downgrade(&execute));
+        trie.insert("example", Rc::downgrade(&example));
+        assert_eq!(trie.get_strong("execute"), Some(execute.clone()));
+        drop(exe);
+        drop(example);
+        assert_eq!(trie.get_strong("exe"), None);
+        // the dead weak reference is still stored until pruned
+        assert_eq!(trie.get("exe").map(Weak::strong_count), Some(0));
+        assert_eq!(trie.prune_dead(), 2);
+        assert!(trie.find("exe").is_none());
+        assert!(trie.find("example").is_none());
+        assert_eq!(trie.get_strong("execute"), Some(execute));
+        assert_eq!(trie.entry.children()[0].label(), "execute");
+    }
+
+    #[test]
+    fn test_sync_weak() {
+        let mut trie = RadixTrie::<std::sync::Weak<&str>>::new();
+        let value = Arc::new("value");
+        trie.insert("label", Arc::downgrade(&value));
+        assert_eq!(trie.get_strong("label"), Some(value.clone()));
+        drop(value);
+        assert_eq!(trie.prune_dead(), 1);
+    }
+}